@@ -0,0 +1,413 @@
+/*
+    src/autopad.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{cmp, fmt, sync::Mutex, convert::{TryFrom, TryInto}};
+use ncursesw::{
+    ChtypeChar, ChtypeString, ComplexChar, ComplexString,
+    WideString, SCREEN, WINDOW
+};
+use crate::{Screen, Origin, Size, Pad, NCurseswWinError, gen::*, funcs::{TAB_SIZE, char_width}};
+
+// the default number of lines and columns a pad grows by.
+const DEFAULT_CHUNK: Size = Size { lines: 128, columns: 32 };
+
+// the largest number of lines or columns of a pad.
+const MAX_PAD_LENGTH: u16 = i16::MAX as u16;
+
+// the columns reserved for a complex character as it may be double width.
+const COMPLEX_CHAR_WIDTH: u16 = 2;
+
+struct AutoPadState {
+    pad:       Pad,         // the pad, resized in place so it's handle never changes.
+    chunk:     Size,        // the number of lines and columns the pad grows by.
+    max_lines: Option<u16>, // the maximum lines before the oldest lines are dropped.
+    dropped:   u64          // the total number of lines dropped.
+}
+
+/// A pad that grows as content is written to it.
+///
+/// Writing with the add functions past the size of the pad grows the pad by
+/// chunks of lines and columns, the pad is resized in place with `wresize()`
+/// so it's handle, contents, cursor, attributes and background are kept.
+/// When a maximum number of lines is set the oldest lines are dropped from
+/// the top of the pad instead of growing past the maximum, the origin passed
+/// to a `mv*` add function refers to the lines before any are dropped.
+///
+/// The add functions grow the pad and write to it while holding the pad's
+/// lock, use `with_pad()` to hold the lock across other calls. A subpad is
+/// not supported, all other functions behave as they do for a `Pad` of the
+/// current size.
+pub struct AutoPad {
+    screen: Option<SCREEN>,
+    state:  Mutex<AutoPadState>
+}
+
+impl HasHandle<WINDOW> for AutoPad {
+    fn _from(screen: Option<SCREEN>, handle: WINDOW, free_on_drop: bool) -> Self {
+        let state = AutoPadState { pad: Pad::_from(screen, handle, free_on_drop), chunk: DEFAULT_CHUNK, max_lines: None, dropped: 0 };
+
+        Self { screen, state: Mutex::new(state) }
+    }
+
+    fn _screen(&self) -> Option<SCREEN> {
+        self.screen
+    }
+
+    fn _handle(&self) -> WINDOW {
+        self.with_state(|state| state.pad._handle())
+    }
+}
+
+impl AutoPad {
+    /// Create a new growable pad with an initial size.
+    pub fn new(size: Size) -> result!(Self) {
+        Ok(Self::_from(None, ncursesw::newpad(size.try_into()?)?, true))
+    }
+
+    /// Create a new growable pad with an initial size on a screen.
+    pub fn new_sp(screen: &Screen, size: Size) -> result!(Self) {
+        Ok(Self::_from(Some(screen._handle()), ncursesw::newpad_sp(screen._handle(), size.try_into()?)?, true))
+    }
+
+    /// The screen associated with the pad.
+    pub fn screen(&self) -> Option<Screen> {
+        self.screen.map(|screen| Screen::_from(screen, false))
+    }
+
+    /// Call a function with the pad while holding the pad's lock so it can't
+    /// grow until the function returns.
+    pub fn with_pad<F, T>(&self, func: F) -> T
+        where F: FnOnce(&Pad) -> T
+    {
+        self.with_state(|state| func(&state.pad))
+    }
+
+    /// The number of lines and columns the pad grows by.
+    pub fn chunk(&self) -> Size {
+        self.with_state(|state| state.chunk)
+    }
+
+    /// Set the number of lines and columns the pad grows by.
+    pub fn set_chunk(&self, chunk: Size) {
+        self.with_state(|state| state.chunk = Size { lines: cmp::max(chunk.lines, 1), columns: cmp::max(chunk.columns, 1) })
+    }
+
+    /// The maximum number of lines of the pad.
+    pub fn max_lines(&self) -> Option<u16> {
+        self.with_state(|state| state.max_lines)
+    }
+
+    /// Set the maximum number of lines of the pad, the oldest lines are
+    /// dropped when content is written past the maximum, a pad that already
+    /// has more lines than the maximum is not shrunk.
+    pub fn set_max_lines(&self, max_lines: Option<u16>) {
+        self.with_state(|state| state.max_lines = max_lines.map(|max_lines| cmp::max(max_lines, 1)))
+    }
+
+    /// The total number of lines dropped from the top of the pad.
+    pub fn dropped_lines(&self) -> u64 {
+        self.with_state(|state| state.dropped)
+    }
+
+    /// Grow the pad so it's at least a size, returning the number of lines
+    /// dropped from the top of the pad.
+    pub fn reserve(&self, size: Size) -> result!(u16) {
+        self.with_state(|state| state.reserve(size))
+    }
+
+    // grow the pad for content written from an origin, or the cursor if `None`, and write it with
+    // the origin adjusted for any lines dropped, the lock is held so the pad can't grow in between.
+    fn write_from<E, W>(&self, origin: Option<Origin>, extent: E, write: W) -> result!(())
+        where E: FnOnce(Origin) -> Size,
+              W: FnOnce(WINDOW, Origin) -> result!(())
+    {
+        self.with_state(|state| {
+            let handle = state.pad._handle();
+            let origin = match origin {
+                Some(origin) => origin,
+                None         => Origin::try_from(ncursesw::getcuryx(handle)?)?
+            };
+            let dropped = state.reserve(extent(origin))?;
+
+            write(handle, Origin { y: origin.y.saturating_sub(dropped), x: origin.x })
+        })
+    }
+
+    // grow the pad for a string written from an origin and write it.
+    fn write_str<I, W>(&self, origin: Option<Origin>, chars: I, write: W) -> result!(())
+        where I: Iterator<Item = char>,
+              W: FnOnce(WINDOW, Origin) -> result!(())
+    {
+        self.write_from(origin, |origin| text_extent(origin, chars), write)
+    }
+
+    // grow the pad for a string of cells written from an origin without wrapping and write it.
+    fn write_cells<W>(&self, origin: Option<Origin>, cells: usize, length: Option<u16>, write: W) -> result!(())
+        where W: FnOnce(WINDOW, Origin) -> result!(())
+    {
+        let cells = u16::try_from(cells).unwrap_or(u16::MAX);
+        let cells = length.map_or(cells, |length| cmp::min(length, cells));
+
+        self.write_from(origin, |origin| Size { lines: origin.y + 1, columns: origin.x.saturating_add(cells) }, write)
+    }
+
+    fn with_state<F, T>(&self, func: F) -> T
+        where F: FnOnce(&mut AutoPadState) -> T
+    {
+        func(&mut self.state.lock().unwrap_or_else(|_| panic!("AutoPad::with_state() : self.state.lock() failed!!!")))
+    }
+}
+
+impl AutoPadState {
+    fn reserve(&mut self, required: Size) -> result!(u16) {
+        let handle = self.pad._handle();
+        let current = Size::try_from(ncursesw::getmaxyx(handle)?)?;
+        let (size, dropped) = grown_size(current, required, self.chunk, self.max_lines);
+
+        if size != current {
+            // resizing in place keeps the handle valid for anyone holding it.
+            ncursesw::wresize(handle, size.try_into()?)?;
+        }
+
+        if dropped > 0 {
+            // scroll the oldest lines off the top, this doesn't move the cursor.
+            let cursor = Origin::try_from(ncursesw::getcuryx(handle)?)?;
+            let scrollok = ncursesw::is_scrollok(handle);
+
+            ncursesw::scrollok(handle, true)?;
+            ncursesw::wscrl(handle, i32::from(dropped))?;
+            ncursesw::scrollok(handle, scrollok)?;
+            ncursesw::wmove(handle, Origin { y: cursor.y.saturating_sub(dropped), x: cursor.x }.try_into()?)?;
+        }
+
+        self.dropped += u64::from(dropped);
+
+        Ok(dropped)
+    }
+}
+
+impl IsPad for AutoPad {
+    // a subpad addresses fixed lines of the pad which move as the oldest lines are dropped.
+    fn subpad(&self, _: Size, _: Origin) -> result!(Pad) {
+        Err(NCurseswWinError::NotSupported { func: "AutoPad::subpad".to_string() })
+    }
+
+    fn getparent(&self) -> Option<Pad> {
+        None
+    }
+}
+impl BaseCanvas for AutoPad { }
+impl Mouseable for AutoPad { }
+impl Scrollable for AutoPad { }
+
+impl HasYAxis for AutoPad { }
+impl HasYXAxis for AutoPad { }
+impl HasXAxis for AutoPad { }
+impl GraphicsTransform for AutoPad { }
+impl HasGraphicFunctions for AutoPad { }
+impl HasBackground for AutoPad { }
+impl HasAttributes for AutoPad { }
+impl HasMvAttributes for AutoPad { }
+impl HasDelFunctions for AutoPad { }
+impl HasMvDelFunctions for AutoPad { }
+impl HasInFunctions for AutoPad { }
+impl HasMvInFunctions for AutoPad { }
+impl HasInsFunctions for AutoPad { }
+impl HasMvInsFunctions for AutoPad { }
+impl HasNonBlocking for AutoPad { }
+impl HasGetFunctions for AutoPad { }
+impl HasMvGetFunctions for AutoPad { }
+
+impl HasAddFunctions for AutoPad {
+    fn addchnstr(&self, chstr: &ChtypeString, length: Option<u16>) -> result!(()) {
+        self.write_cells(None, chstr.len(), length, |handle, _| Ok(ncursesw::waddchnstr(handle, chstr, option_length!(length)?)?))
+    }
+
+    fn addch(&self, ch: ChtypeChar) -> result!(()) {
+        self.write_str(None, std::iter::once(ch.as_char()), |handle, _| Ok(ncursesw::waddch(handle, ch)?))
+    }
+
+    fn addchstr(&self, chstr: &ChtypeString) -> result!(()) {
+        self.write_cells(None, chstr.len(), None, |handle, _| Ok(ncursesw::waddchstr(handle, chstr)?))
+    }
+
+    fn addnstr<S: Into<String>>(&self, str: S, length: Option<u16>) -> result!(()) {
+        let str = str.into();
+
+        self.write_str(None, str.chars().take(length.map_or(usize::MAX, usize::from)), |handle, _| Ok(ncursesw::waddnstr(handle, str.as_str(), option_length!(length)?)?))
+    }
+
+    fn addnwstr(&self, wstr: &WideString, length: Option<u16>) -> result!(()) {
+        self.write_str(None, wide_chars(wstr).take(length.map_or(usize::MAX, usize::from)), |handle, _| Ok(ncursesw::waddnwstr(handle, wstr, option_length!(length)?)?))
+    }
+
+    fn addstr<S: Into<String>>(&self, str: S) -> result!(()) {
+        let str = str.into();
+
+        self.write_str(None, str.chars(), |handle, _| Ok(ncursesw::waddstr(handle, str.as_str())?))
+    }
+
+    fn add_wchnstr(&self, wchstr: &ComplexString, length: Option<u16>) -> result!(()) {
+        self.write_cells(None, wchstr.len(), length, |handle, _| Ok(ncursesw::wadd_wchnstr(handle, wchstr, option_length!(length)?)?))
+    }
+
+    fn add_wch(&self, wch: ComplexChar) -> result!(()) {
+        self.write_cells(None, usize::from(COMPLEX_CHAR_WIDTH), None, |handle, _| Ok(ncursesw::wadd_wch(handle, wch)?))
+    }
+
+    fn add_wchstr(&self, wchstr: &ComplexString) -> result!(()) {
+        self.write_cells(None, wchstr.len(), None, |handle, _| Ok(ncursesw::wadd_wchstr(handle, wchstr)?))
+    }
+
+    fn addwstr(&self, wstr: &WideString) -> result!(()) {
+        self.write_str(None, wide_chars(wstr), |handle, _| Ok(ncursesw::waddwstr(handle, wstr)?))
+    }
+}
+
+impl HasMvAddFunctions for AutoPad {
+    fn mvaddchnstr(&self, origin: Origin, chstr: &ChtypeString, length: Option<u16>) -> result!(()) {
+        self.write_cells(Some(origin), chstr.len(), length, |handle, origin| Ok(ncursesw::mvwaddchnstr(handle, origin.try_into()?, chstr, option_length!(length)?)?))
+    }
+
+    fn mvaddch(&self, origin: Origin, ch: ChtypeChar) -> result!(()) {
+        self.write_str(Some(origin), std::iter::once(ch.as_char()), |handle, origin| Ok(ncursesw::mvwaddch(handle, origin.try_into()?, ch)?))
+    }
+
+    fn mvaddchstr(&self, origin: Origin, chstr: &ChtypeString) -> result!(()) {
+        self.write_cells(Some(origin), chstr.len(), None, |handle, origin| Ok(ncursesw::mvwaddchstr(handle, origin.try_into()?, chstr)?))
+    }
+
+    fn mvaddnstr<S: Into<String>>(&self, origin: Origin, str: S, length: Option<u16>) -> result!(()) {
+        let str = str.into();
+
+        self.write_str(Some(origin), str.chars().take(length.map_or(usize::MAX, usize::from)), |handle, origin| Ok(ncursesw::mvwaddnstr(handle, origin.try_into()?, str.as_str(), option_length!(length)?)?))
+    }
+
+    fn mvaddnwstr(&self, origin: Origin, wstr: &WideString, length: Option<u16>) -> result!(()) {
+        self.write_str(Some(origin), wide_chars(wstr).take(length.map_or(usize::MAX, usize::from)), |handle, origin| Ok(ncursesw::mvwaddnwstr(handle, origin.try_into()?, wstr, option_length!(length)?)?))
+    }
+
+    fn mvaddstr<S: Into<String>>(&self, origin: Origin, str: S) -> result!(()) {
+        let str = str.into();
+
+        self.write_str(Some(origin), str.chars(), |handle, origin| Ok(ncursesw::mvwaddstr(handle, origin.try_into()?, str.as_str())?))
+    }
+
+    fn mvadd_wchnstr(&self, origin: Origin, wchstr: &ComplexString, length: Option<u16>) -> result!(()) {
+        self.write_cells(Some(origin), wchstr.len(), length, |handle, origin| Ok(ncursesw::mvwadd_wchnstr(handle, origin.try_into()?, wchstr, option_length!(length)?)?))
+    }
+
+    fn mvadd_wch(&self, origin: Origin, wch: ComplexChar) -> result!(()) {
+        self.write_cells(Some(origin), usize::from(COMPLEX_CHAR_WIDTH), None, |handle, origin| Ok(ncursesw::mvwadd_wch(handle, origin.try_into()?, wch)?))
+    }
+
+    fn mvadd_wchstr(&self, origin: Origin, wchstr: &ComplexString) -> result!(()) {
+        self.write_cells(Some(origin), wchstr.len(), None, |handle, origin| Ok(ncursesw::mvwadd_wchstr(handle, origin.try_into()?, wchstr)?))
+    }
+
+    fn mvaddwstr(&self, origin: Origin, wstr: &WideString) -> result!(()) {
+        self.write_str(Some(origin), wide_chars(wstr), |handle, origin| Ok(ncursesw::mvwaddwstr(handle, origin.try_into()?, wstr)?))
+    }
+}
+
+impl Drop for AutoPad {
+    // the pad is free'd when the state is dropped.
+    fn drop(&mut self) { }
+}
+
+unsafe impl Send for AutoPad { } // too make thread safe
+unsafe impl Sync for AutoPad { } // too make thread safe
+
+impl fmt::Debug for AutoPad {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.with_state(|state| write!(f, "AutoPad {{ screen: {:?}, pad: {:?}, chunk: {}, max_lines: {:?} }}", self.screen, state.pad, state.chunk, state.max_lines))
+    }
+}
+
+// round a length up to a multiple of a chunk.
+fn round_up(length: u16, chunk: u16) -> u16 {
+    let chunk = u32::from(cmp::max(chunk, 1));
+
+    u16::try_from(u32::from(length).div_ceil(chunk) * chunk).unwrap_or(u16::MAX)
+}
+
+// the size a pad grows to for a required size and the number of lines dropped from the top
+// when the required lines are past the maximum number of lines.
+fn grown_size(current: Size, required: Size, chunk: Size, max_lines: Option<u16>) -> (Size, u16) {
+    let max_lines = cmp::min(max_lines.unwrap_or(MAX_PAD_LENGTH), MAX_PAD_LENGTH);
+
+    let size = Size {
+        lines:   cmp::max(current.lines, cmp::min(round_up(required.lines, chunk.lines), max_lines)),
+        columns: cmp::max(current.columns, cmp::min(round_up(required.columns, chunk.columns), MAX_PAD_LENGTH))
+    };
+
+    (size, cmp::min(required.lines.saturating_sub(size.lines), current.lines))
+}
+
+fn wide_chars(wstr: &WideString) -> impl Iterator<Item = char> {
+    let wide: Vec<_> = wstr.clone().into();
+
+    wide.into_iter().filter_map(|ch| char::from_u32(ch as u32))
+}
+
+// the size needed to write text from an origin, the pad grows rather than the text
+// wrapping so a line is as wide as the text written to it plus the cursor after it.
+fn text_extent<I>(origin: Origin, chars: I) -> Size
+    where I: Iterator<Item = char>
+{
+    let mut extent = Size { lines: origin.y + 1, columns: origin.x + 1 };
+    let mut cursor = origin;
+
+    for ch in chars {
+        match ch {
+            '\n' => cursor = Origin { y: cursor.y.saturating_add(1), x: 0 },
+            '\r' => cursor.x = 0,
+            '\t' => cursor.x = (cursor.x / TAB_SIZE + 1).saturating_mul(TAB_SIZE),
+            '\x08' => cursor.x = cursor.x.saturating_sub(1),
+            _ => cursor.x = cursor.x.saturating_add(char_width(ch))
+        }
+
+        extent.lines = cmp::max(extent.lines, cursor.y.saturating_add(1));
+        extent.columns = cmp::max(extent.columns, cursor.x.saturating_add(1));
+    }
+
+    extent
+}
+
+#[test]
+fn auto_pad_test() {
+    assert_eq!(text_extent(Origin { y: 2, x: 5 }, "abc".chars()), Size { lines: 3, columns: 9 });
+    assert_eq!(text_extent(Origin { y: 0, x: 0 }, "ab\n\tx\n".chars()), Size { lines: 3, columns: 10 });
+    assert_eq!(text_extent(Origin { y: 0, x: 0 }, "漢字".chars()), Size { lines: 1, columns: 5 });
+    assert_eq!(round_up(130, 128), 256);
+    assert_eq!(round_up(128, 128), 128);
+
+
+    let chunk = Size { lines: 128, columns: 32 };
+
+    assert_eq!(grown_size(Size { lines: 128, columns: 32 }, Size { lines: 129, columns: 10 }, chunk, None), (Size { lines: 256, columns: 32 }, 0));
+    assert_eq!(grown_size(Size { lines: 128, columns: 32 }, Size { lines: 10, columns: 33 }, chunk, None), (Size { lines: 128, columns: 64 }, 0));
+    assert_eq!(grown_size(Size { lines: 128, columns: 32 }, Size { lines: 10, columns: 10 }, chunk, None), (Size { lines: 128, columns: 32 }, 0));
+    assert_eq!(grown_size(Size { lines: 128, columns: 32 }, Size { lines: 210, columns: 10 }, chunk, Some(200)), (Size { lines: 200, columns: 32 }, 10));
+    assert_eq!(grown_size(Size { lines: 200, columns: 32 }, Size { lines: 500, columns: 10 }, chunk, Some(200)), (Size { lines: 200, columns: 32 }, 200));
+    assert_eq!(grown_size(Size { lines: 1, columns: 1 }, Size { lines: 1, columns: 40000 }, chunk, None), (Size { lines: 128, columns: MAX_PAD_LENGTH }, 0));
+}
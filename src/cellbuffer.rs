@@ -0,0 +1,593 @@
+/*
+    src/cellbuffer.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{cmp, convert::TryFrom};
+use ncursesw::{AttributesColorPairSet, ComplexString};
+use crate::{
+    Origin, Size, BoxDrawingType, BoxDrawingGraphic, HorizontalGraphic, VerticalGraphic,
+    NCurseswWinError, HasMvAddFunctions, graphics::wide_box_graphic,
+    funcs::{TAB_SIZE, char_width, complex_char, default_attributes_color_pair}
+};
+
+// the character of the second cell of a double width character.
+const CONTINUATION: char = '\0';
+
+// the number of unchanged cells between two changed runs of cells that will
+// be written as one run when blitting rather than as two calls.
+const RUN_GAP: usize = 4;
+
+// the order that box drawing graphics are matched to an existing character,
+// the centered lines are before their upper/lower and left/right variants.
+const BOX_DRAWING_GRAPHICS: [BoxDrawingGraphic; 15] = [
+    BoxDrawingGraphic::HorizontalLine,
+    BoxDrawingGraphic::VerticalLine,
+    BoxDrawingGraphic::UpperLeftCorner,
+    BoxDrawingGraphic::LowerLeftCorner,
+    BoxDrawingGraphic::UpperRightCorner,
+    BoxDrawingGraphic::LowerRightCorner,
+    BoxDrawingGraphic::RightTee,
+    BoxDrawingGraphic::LeftTee,
+    BoxDrawingGraphic::LowerTee,
+    BoxDrawingGraphic::UpperTee,
+    BoxDrawingGraphic::Plus,
+    BoxDrawingGraphic::UpperHorizontalLine,
+    BoxDrawingGraphic::LowerHorizontalLine,
+    BoxDrawingGraphic::LeftVerticalLine,
+    BoxDrawingGraphic::RightVerticalLine
+];
+
+/// A character cell of a `CellBuffer`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Cell {
+    character:       char,
+    attrs_colorpair: AttributesColorPairSet
+}
+
+impl Cell {
+    /// Create a new cell of a character and it's attributes and color pair.
+    pub fn new(character: char, attrs_colorpair: AttributesColorPairSet) -> Self {
+        Self { character, attrs_colorpair }
+    }
+
+    // the second column of a double width character.
+    pub(in crate) fn continuation(attrs_colorpair: AttributesColorPairSet) -> Self {
+        Self::new(CONTINUATION, attrs_colorpair)
+    }
+
+    /// The character of the cell.
+    pub fn character(&self) -> char {
+        self.character
+    }
+
+    /// The attributes and color pair of the cell.
+    pub fn attributes_color_pair(&self) -> AttributesColorPairSet {
+        self.attrs_colorpair
+    }
+
+    /// Is the cell the second column of a double width character.
+    pub fn is_continuation(&self) -> bool {
+        self.character == CONTINUATION
+    }
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self::new(' ', default_attributes_color_pair())
+    }
+}
+
+/// An off-screen buffer of character cells.
+///
+/// The buffer is held in memory and has an add, cursor and box drawing API similar
+/// to a `Window` but does not require NCurses to be initialised. `blit()` writes
+/// the buffer to a window and on subsequent calls only the cells that have changed
+/// since the previous blit are written.
+///
+/// Each cell holds a single spacing character, zero width characters are ignored and
+/// a double width character occupies two cells with the second being a continuation cell.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CellBuffer {
+    size:            Size,                       // size of the buffer.
+    cells:           Vec<Cell>,                  // the cells of the buffer.
+    cursor:          Origin,                     // the cursor used by the add functions.
+    attrs_colorpair: AttributesColorPairSet,     // the attributes and color pair used by the add functions.
+    blitted:         Option<(Origin, Vec<Cell>)> // the origin and cells of the previous blit.
+}
+
+impl CellBuffer {
+    /// Create a new cell buffer of a size.
+    pub fn new(size: Size) -> Self {
+        Self {
+            size,
+            cells:           vec!(Cell::default(); usize::from(size.lines) * usize::from(size.columns)),
+            cursor:          Origin::default(),
+            attrs_colorpair: default_attributes_color_pair(),
+            blitted:         None
+        }
+    }
+
+    /// The size of the buffer.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Resize the buffer, the existing cells are retained from the upper left
+    /// origin and the next `blit()` will write all cells.
+    pub fn resize(&mut self, size: Size) {
+        let mut cells = vec!(Cell::default(); usize::from(size.lines) * usize::from(size.columns));
+
+        for line in 0..cmp::min(size.lines, self.size.lines) {
+            for column in 0..cmp::min(size.columns, self.size.columns) {
+                cells[usize::from(line) * usize::from(size.columns) + usize::from(column)] = *self.cell(Origin { y: line, x: column });
+            }
+        }
+
+        // a double width character may have lost it's continuation cell.
+        if size.columns < self.size.columns && size.columns > 0 {
+            for line in 0..usize::from(size.lines) {
+                let cell = &mut cells[line * usize::from(size.columns) + usize::from(size.columns) - 1];
+
+                if char_width(cell.character) > 1 {
+                    cell.character = ' ';
+                }
+            }
+        }
+
+        self.size = size;
+        self.cells = cells;
+        self.cursor = Origin { y: cmp::min(self.cursor.y, size.lines.saturating_sub(1)), x: cmp::min(self.cursor.x, size.columns.saturating_sub(1)) };
+        self.blitted = None;
+    }
+
+    /// The cursor used by the add functions.
+    pub fn cursor(&self) -> Origin {
+        self.cursor
+    }
+
+    /// Set the cursor used by the add functions.
+    pub fn set_cursor(&mut self, origin: Origin) {
+        self.assert_origin("set_cursor", origin);
+
+        self.cursor = origin;
+    }
+
+    /// The attributes and color pair used by the add functions.
+    pub fn attributes_color_pair(&self) -> AttributesColorPairSet {
+        self.attrs_colorpair
+    }
+
+    /// Set the attributes and color pair used by the add functions.
+    pub fn set_attributes_color_pair(&mut self, attrs_colorpair: AttributesColorPairSet) {
+        self.attrs_colorpair = attrs_colorpair;
+    }
+
+    /// The cell at origin.
+    pub fn cell(&self, origin: Origin) -> &Cell {
+        self.assert_origin("cell", origin);
+
+        &self.cells[self.index(origin)]
+    }
+
+    /// The characters of a line, continuation cells are omitted.
+    pub fn line_string(&self, line: u16) -> String {
+        assert!(line < self.size.lines, "CellBuffer::line_string() : line={} >= size.lines={}", line, self.size.lines);
+
+        let start = usize::from(line) * usize::from(self.size.columns);
+
+        self.cells[start..start + usize::from(self.size.columns)]
+            .iter()
+            .filter(|cell| !cell.is_continuation())
+            .map(|cell| cell.character)
+            .collect()
+    }
+
+    /// Set all cells to a space with the default attributes and color pair and
+    /// move the cursor to the upper left origin.
+    pub fn erase(&mut self) {
+        self.cells.iter_mut().for_each(|cell| *cell = Cell::default());
+        self.cursor = Origin::default();
+    }
+
+    /// As `erase()` but the next `blit()` will write all cells.
+    pub fn clear(&mut self) {
+        self.erase();
+        self.invalidate();
+    }
+
+    /// Force the next `blit()` to write all cells.
+    pub fn invalidate(&mut self) {
+        self.blitted = None;
+    }
+
+    /// Erase from the cursor to the end of the line.
+    pub fn clrtoeol(&mut self) {
+        let cursor = self.cursor;
+
+        for x in cursor.x..self.size.columns {
+            self.put_cell(Origin { y: cursor.y, x }, Cell::default());
+        }
+    }
+
+    /// Add a character at the cursor and advance the cursor.
+    ///
+    /// A newline erases to the end of the line and moves the cursor to the start of the next line,
+    /// a carriage return moves the cursor to the start of the line and a tab advances the cursor
+    /// to the next tab stop. Other control and zero width characters are ignored.
+    pub fn addch(&mut self, ch: char) {
+        if self.cells.is_empty() {
+            return;
+        }
+
+        match ch {
+            '\n' => {
+                self.clrtoeol();
+                self.cursor = Origin { y: cmp::min(self.cursor.y + 1, self.size.lines - 1), x: 0 };
+            },
+            '\r' => self.cursor.x = 0,
+            '\t' => {
+                for _ in 0..TAB_SIZE - self.cursor.x % TAB_SIZE {
+                    self.addch(' ');
+                }
+            },
+            _    => {
+                let width = char_width(ch);
+
+                if width == 0 {
+                    return;
+                } else if self.cursor.x + width > self.size.columns {
+                    // wrap to the next line if the character won't fit.
+                    if self.cursor.y + 1 >= self.size.lines {
+                        return;
+                    }
+
+                    self.cursor = Origin { y: self.cursor.y + 1, x: 0 };
+                }
+
+                self.put(self.cursor, ch, self.attrs_colorpair);
+                self.cursor.x += width;
+
+                if self.cursor.x >= self.size.columns {
+                    if self.cursor.y + 1 < self.size.lines {
+                        self.cursor = Origin { y: self.cursor.y + 1, x: 0 };
+                    } else {
+                        self.cursor.x = self.size.columns - 1;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Move the cursor to origin and add a character.
+    pub fn mvaddch(&mut self, origin: Origin, ch: char) {
+        self.set_cursor(origin);
+        self.addch(ch);
+    }
+
+    /// Add a string at the cursor.
+    pub fn addstr<S: Into<String>>(&mut self, str: S) {
+        self.addnstr(str, None);
+    }
+
+    /// Move the cursor to origin and add a string.
+    pub fn mvaddstr<S: Into<String>>(&mut self, origin: Origin, str: S) {
+        self.set_cursor(origin);
+        self.addstr(str);
+    }
+
+    /// Add at most `length` characters of a string at the cursor, if `length` is `None` then add all of the string.
+    pub fn addnstr<S: Into<String>>(&mut self, str: S, length: Option<u16>) {
+        let str = str.into();
+
+        for ch in str.chars().take(length.map_or_else(|| usize::MAX, usize::from)) {
+            self.addch(ch);
+        }
+    }
+
+    /// Move the cursor to origin and add at most `length` characters of a string.
+    pub fn mvaddnstr<S: Into<String>>(&mut self, origin: Origin, str: S, length: Option<u16>) {
+        self.set_cursor(origin);
+        self.addnstr(str, length);
+    }
+
+    /// Set the cell at origin without moving the cursor.
+    pub fn mvadd_cell(&mut self, origin: Origin, cell: Cell) {
+        self.assert_origin("mvadd_cell", origin);
+
+        self.put(origin, cell.character, cell.attrs_colorpair);
+    }
+
+    /// Draw a horizontal line at the cursor of a length using the box drawing type.
+    ///
+    /// The original attributes and color pairs are retained from characters that are overwritten.
+    pub fn thline_set(&mut self, box_drawing_type: BoxDrawingType, graphic: HorizontalGraphic, length: u16) -> result!(()) {
+        self.mvthline_set(self.cursor, box_drawing_type, graphic, length)
+    }
+
+    /// Draw a horizontal line at origin of a length using the box drawing type.
+    ///
+    /// The original attributes and color pairs are retained from characters that are overwritten.
+    pub fn mvthline_set(
+        &mut self,
+        origin:           Origin,
+        box_drawing_type: BoxDrawingType,
+        graphic:          HorizontalGraphic,
+        length:           u16
+    ) -> result!(()) {
+        self.assert_origin("mvthline_set", origin);
+
+        let box_drawing_graphic = match graphic {
+            HorizontalGraphic::Upper  => BoxDrawingGraphic::UpperHorizontalLine,
+            HorizontalGraphic::Center => BoxDrawingGraphic::HorizontalLine,
+            HorizontalGraphic::Lower  => BoxDrawingGraphic::LowerHorizontalLine
+        };
+
+        for x in origin.x..cmp::min(origin.x.saturating_add(length), self.size.columns) {
+            self.put_graphic(Origin { y: origin.y, x }, box_drawing_type, box_drawing_graphic)?;
+        }
+
+        Ok(())
+    }
+
+    /// Draw a vertical line at the cursor of a length using the box drawing type.
+    ///
+    /// The original attributes and color pairs are retained from characters that are overwritten.
+    pub fn tvline_set(&mut self, box_drawing_type: BoxDrawingType, graphic: VerticalGraphic, length: u16) -> result!(()) {
+        self.mvtvline_set(self.cursor, box_drawing_type, graphic, length)
+    }
+
+    /// Draw a vertical line at origin of a length using the box drawing type.
+    ///
+    /// The original attributes and color pairs are retained from characters that are overwritten.
+    pub fn mvtvline_set(
+        &mut self,
+        origin:           Origin,
+        box_drawing_type: BoxDrawingType,
+        graphic:          VerticalGraphic,
+        length:           u16
+    ) -> result!(()) {
+        self.assert_origin("mvtvline_set", origin);
+
+        let box_drawing_graphic = match graphic {
+            VerticalGraphic::Left   => BoxDrawingGraphic::LeftVerticalLine,
+            VerticalGraphic::Center => BoxDrawingGraphic::VerticalLine,
+            VerticalGraphic::Right  => BoxDrawingGraphic::RightVerticalLine
+        };
+
+        for y in origin.y..cmp::min(origin.y.saturating_add(length), self.size.lines) {
+            self.put_graphic(Origin { y, x: origin.x }, box_drawing_type, box_drawing_graphic)?;
+        }
+
+        Ok(())
+    }
+
+    /// Draw a box at the cursor of a size using the box drawing type.
+    ///
+    /// The original attributes and color pairs are retained from characters that are overwritten.
+    pub fn tbox_set(&mut self, size: Size, box_drawing_type: BoxDrawingType) -> result!(()) {
+        self.mvtbox_set(self.cursor, size, box_drawing_type)
+    }
+
+    /// Draw a box at origin of a size using the box drawing type.
+    ///
+    /// The original attributes and color pairs are retained from characters that are overwritten.
+    pub fn mvtbox_set(&mut self, origin: Origin, size: Size, box_drawing_type: BoxDrawingType) -> result!(()) {
+        self.assert_origin("mvtbox_set", origin);
+        assert!(size.lines >= 2 && size.columns >= 2, "CellBuffer::mvtbox_set() : size is invalid, size={}", size);
+        assert!(
+            origin.y + size.lines <= self.size.lines && origin.x + size.columns <= self.size.columns,
+            "CellBuffer::mvtbox_set() : attempting to write over buffer edge, origin={}, size={}, buffer size={}", origin, size, self.size
+        );
+
+        let bottom = origin.y + size.lines - 1;
+        let right = origin.x + size.columns - 1;
+
+        self.put_graphic(origin, box_drawing_type, BoxDrawingGraphic::UpperLeftCorner)?;
+        self.put_graphic(Origin { y: bottom, x: origin.x }, box_drawing_type, BoxDrawingGraphic::LowerLeftCorner)?;
+        self.put_graphic(Origin { y: origin.y, x: right }, box_drawing_type, BoxDrawingGraphic::UpperRightCorner)?;
+        self.put_graphic(Origin { y: bottom, x: right }, box_drawing_type, BoxDrawingGraphic::LowerRightCorner)?;
+
+        if size.columns > 2 {
+            self.mvthline_set(Origin { y: origin.y, x: origin.x + 1 }, box_drawing_type, HorizontalGraphic::Upper, size.columns - 2)?;
+            self.mvthline_set(Origin { y: bottom, x: origin.x + 1 }, box_drawing_type, HorizontalGraphic::Lower, size.columns - 2)?;
+        }
+
+        if size.lines > 2 {
+            self.mvtvline_set(Origin { y: origin.y + 1, x: origin.x }, box_drawing_type, VerticalGraphic::Left, size.lines - 2)?;
+            self.mvtvline_set(Origin { y: origin.y + 1, x: right }, box_drawing_type, VerticalGraphic::Right, size.lines - 2)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write the buffer to a window with the buffer's upper left cell at origin.
+    ///
+    /// If the previous blit was to the same origin then only the cells that have
+    /// changed since are written. The buffer is clipped to the window's edge.
+    pub fn blit<W: HasMvAddFunctions>(&mut self, window: &W, origin: Origin) -> result!(()) {
+        let window_size = window.size()?;
+
+        assert_origin!("blit", window_size, origin);
+
+        let lines = usize::from(cmp::min(self.size.lines, window_size.lines.saturating_sub(origin.y)));
+        let columns = usize::from(cmp::min(self.size.columns, window_size.columns.saturating_sub(origin.x)));
+        let buffer_columns = usize::from(self.size.columns);
+        let previous = match self.blitted.take() {
+            Some((blitted_origin, cells)) if blitted_origin == origin && cells.len() == self.cells.len() => Some(cells),
+            _                                                                                           => None
+        };
+
+        for line in 0..lines {
+            let range = line * buffer_columns..line * buffer_columns + columns;
+            let current = &self.cells[range.clone()];
+
+            for (start, end) in changed_runs(current, previous.as_ref().map(|cells| &cells[range.clone()])) {
+                let mut wchstr = ComplexString::with_capacity(end - start);
+
+                for cell in current[start..end].iter().filter(|cell| !cell.is_continuation()) {
+                    wchstr.push(&complex_char(cell.character, cell.attrs_colorpair)?);
+                }
+
+                let line_origin = Origin { y: origin.y + u16::try_from(line)?, x: origin.x + u16::try_from(start)? };
+
+                window.mvadd_wchnstr(line_origin, &wchstr, Some(u16::try_from(wchstr.len())?))?;
+            }
+        }
+
+        self.blitted = Some((origin, self.cells.clone()));
+
+        Ok(())
+    }
+
+    fn assert_origin(&self, func: &str, origin: Origin) {
+        assert!(
+            origin.y < self.size.lines && origin.x < self.size.columns,
+            "CellBuffer::{}() : origin is invalid, origin={}, size={}", func, origin, self.size
+        );
+    }
+
+    fn index(&self, origin: Origin) -> usize {
+        usize::from(origin.y) * usize::from(self.size.columns) + usize::from(origin.x)
+    }
+
+    // put a character at origin, a double width character that won't fit is ignored.
+    fn put(&mut self, origin: Origin, character: char, attrs_colorpair: AttributesColorPairSet) {
+        let width = char_width(character);
+
+        if width == 0 || origin.x + width > self.size.columns {
+            return;
+        }
+
+        self.put_cell(origin, Cell::new(character, attrs_colorpair));
+
+        if width > 1 {
+            let continuation = Origin { y: origin.y, x: origin.x + 1 };
+
+            self.put_cell(continuation, Cell::new(CONTINUATION, attrs_colorpair));
+        }
+    }
+
+    // put a cell at origin, any double width character partially overwritten is replaced with a space.
+    fn put_cell(&mut self, origin: Origin, cell: Cell) {
+        let index = self.index(origin);
+        let current = self.cells[index];
+
+        if current.is_continuation() && origin.x > 0 && !cell.is_continuation() {
+            self.cells[index - 1].character = ' ';
+        } else if char_width(current.character) > 1 && origin.x + 1 < self.size.columns {
+            self.cells[index + 1].character = ' ';
+        }
+
+        self.cells[index] = cell;
+    }
+
+    // put a box drawing graphic at origin transformed with any existing box drawing graphic.
+    fn put_graphic(&mut self, origin: Origin, box_drawing_type: BoxDrawingType, box_drawing_graphic: BoxDrawingGraphic) -> result!(()) {
+        let current = *self.cell(origin);
+        let box_drawing_graphic = match box_graphic(box_drawing_type, current.character)? {
+            Some(current_graphic) => box_drawing_graphic.transform(current_graphic, true),
+            None                  => box_drawing_graphic
+        };
+
+        self.put(origin, wide_box_graphic(box_drawing_type, box_drawing_graphic).as_char()?, current.attrs_colorpair);
+
+        Ok(())
+    }
+}
+
+// the box drawing graphic of a character using the box drawing type.
+fn box_graphic(box_drawing_type: BoxDrawingType, character: char) -> result!(Option<BoxDrawingGraphic>) {
+    for &box_drawing_graphic in BOX_DRAWING_GRAPHICS.iter() {
+        if wide_box_graphic(box_drawing_type, box_drawing_graphic).as_char()? == character {
+            return Ok(Some(box_drawing_graphic));
+        }
+    }
+
+    Ok(None)
+}
+
+// the (start, end) runs of cells of a line that differ from the previous line, runs
+// separated by a small gap are merged and runs are extended so they don't split a
+// double width character.
+fn changed_runs(current: &[Cell], previous: Option<&[Cell]>) -> Vec<(usize, usize)> {
+    let mut runs: Vec<(usize, usize)> = vec!();
+
+    for column in 0..current.len() {
+        if previous.map_or_else(|| true, |previous| previous[column] != current[column]) {
+            match runs.last_mut() {
+                Some((_, end)) if column <= *end + RUN_GAP => *end = column + 1,
+                _                                          => runs.push((column, column + 1))
+            }
+        }
+    }
+
+    for (start, end) in runs.iter_mut() {
+        while *start > 0 && current[*start].is_continuation() {
+            *start -= 1;
+        }
+
+        while *end < current.len() && current[*end].is_continuation() {
+            *end += 1;
+        }
+    }
+
+    runs
+}
+
+#[test]
+fn cell_buffer_test() {
+    let mut buffer = CellBuffer::new(Size { lines: 3, columns: 6 });
+
+    buffer.mvaddstr(Origin { y: 0, x: 0 }, "ab\u{4e2d}cdef");
+
+    assert_eq!(buffer.line_string(0), "ab\u{4e2d}cd");
+    assert_eq!(buffer.line_string(1), "ef    ");
+    assert!(buffer.cell(Origin { y: 0, x: 3 }).is_continuation());
+    assert_eq!(buffer.cursor(), Origin { y: 1, x: 2 });
+
+    // overwriting half of a double width character replaces the other half with a space.
+    buffer.mvaddch(Origin { y: 0, x: 3 }, 'x');
+
+    assert_eq!(buffer.line_string(0), "ab xcd");
+
+    buffer.erase();
+
+    // overlapping boxes are joined.
+    buffer.mvtbox_set(Origin { y: 0, x: 0 }, Size { lines: 3, columns: 4 }, BoxDrawingType::default()).unwrap();
+    buffer.mvtbox_set(Origin { y: 0, x: 2 }, Size { lines: 3, columns: 4 }, BoxDrawingType::default()).unwrap();
+
+    assert_eq!(buffer.line_string(0), "\u{250c}\u{2500}\u{252c}\u{252c}\u{2500}\u{2510}");
+    assert_eq!(buffer.line_string(1), "\u{2502} \u{2502}\u{2502} \u{2502}");
+    assert_eq!(buffer.line_string(2), "\u{2514}\u{2500}\u{2534}\u{2534}\u{2500}\u{2518}");
+
+    let previous = vec!(Cell::default(); 12);
+    let mut current = previous.clone();
+
+    assert_eq!(changed_runs(&current, None), vec!((0, 12)));
+    assert!(changed_runs(&current, Some(&previous)).is_empty());
+
+    current[1].character = 'a';
+    current[3].character = 'b';
+    current[9].character = '\u{4e2d}';
+    current[10].character = CONTINUATION;
+
+    assert_eq!(changed_runs(&current, Some(&previous)), vec!((1, 4), (9, 11)));
+}
@@ -0,0 +1,380 @@
+/*
+    src/export.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{fmt::Write, convert::TryFrom};
+use ncursesw::{AttributesColorPairSet, ComplexChar, ComplexString, getcchar};
+use crate::{
+    Origin, NCurseswWinError, ColorPairColors, ColorsType, ColorType,
+    gen::HasMvInFunctions, funcs::char_width
+};
+
+// the colors used for a cell when the terminal's default color is used.
+const DEFAULT_FOREGROUND: (u8, u8, u8) = (229, 229, 229);
+const DEFAULT_BACKGROUND: (u8, u8, u8) = (0, 0, 0);
+
+// the colors of the first 16 colors used if the color content can't be retrived.
+const BASIC_COLORS: [(u8, u8, u8); 16] = [
+    (0, 0, 0),       (205, 0, 0),     (0, 205, 0),     (205, 205, 0),
+    (0, 0, 238),     (205, 0, 205),   (0, 205, 205),   (229, 229, 229),
+    (127, 127, 127), (255, 0, 0),     (0, 255, 0),     (255, 255, 0),
+    (92, 92, 255),   (255, 0, 255),   (0, 255, 255),   (255, 255, 255)
+];
+
+// the size of a character cell in pixels and the font size in a svg export.
+const SVG_CELL_WIDTH: usize = 10;
+const SVG_CELL_HEIGHT: usize = 20;
+const SVG_FONT_SIZE: usize = 16;
+
+/// The format of an export of a window's contents.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// Text with ANSI escape sequences for the attributes and colors.
+    Ansi,
+    /// A standalone HTML document.
+    Html,
+    /// A standalone SVG image.
+    Svg
+}
+
+// the color of an exported cell.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(in crate) struct ExportColor {
+    number: i32,
+    rgb:    (u8, u8, u8)
+}
+
+// the attributes and colors of an exported cell.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub(in crate) struct ExportStyle {
+    foreground: Option<ExportColor>,
+    background: Option<ExportColor>,
+    bold:       bool,
+    dim:        bool,
+    italic:     bool,
+    underline:  bool,
+    reverse:    bool,
+    blink:      bool,
+    invisible:  bool
+}
+
+impl ExportStyle {
+    // the rgb foreground and background colors with reverse applied.
+    fn rgb(&self) -> ((u8, u8, u8), (u8, u8, u8)) {
+        let foreground = self.foreground.map_or_else(|| DEFAULT_FOREGROUND, |color| color.rgb);
+        let background = self.background.map_or_else(|| DEFAULT_BACKGROUND, |color| color.rgb);
+
+        if self.reverse { (background, foreground) } else { (foreground, background) }
+    }
+}
+
+// a character and it's attributes and colors.
+pub(in crate) type ExportCell = (char, ExportStyle);
+
+// export the contents of a window as a string in the format.
+pub(in crate) fn export_window<W: HasMvInFunctions + ?Sized>(window: &W, format: Format) -> result!(String) {
+    let size = window.size()?;
+    let mut lines = Vec::with_capacity(usize::from(size.lines));
+
+    for y in 0..size.lines {
+        let complex_chars: Vec<ComplexChar> = ComplexString::into(window.mvin_wchnstr(Origin { y, x: 0 }, size.columns)?);
+        let mut line = Vec::with_capacity(complex_chars.len());
+
+        for complex_char in complex_chars {
+            let char_attr_pair = getcchar(complex_char)?;
+
+            line.push((char_attr_pair.character().as_char()?, export_style(char_attr_pair.attributes_and_color_pair())));
+        }
+
+        lines.push(line);
+    }
+
+    Ok(export_lines(&lines, format))
+}
+
+// export lines of cells as a string in the format.
+pub(in crate) fn export_lines(lines: &[Vec<ExportCell>], format: Format) -> String {
+    match format {
+        Format::Ansi => export_ansi(lines),
+        Format::Html => export_html(lines),
+        Format::Svg  => export_svg(lines)
+    }
+}
+
+// decode the attributes and color pair of a cell.
+fn export_style(attrs_colorpair: AttributesColorPairSet) -> ExportStyle {
+    macro_rules! decode {
+        ($attrs_colorpair: ident) => {{
+            let attrs = $attrs_colorpair.attributes();
+            let colors = $attrs_colorpair.color_pair().colors().ok();
+            let color = |number: i32, rgb: Option<(i32, i32, i32)>| -> Option<ExportColor> {
+                if number < 0 {
+                    None
+                } else {
+                    Some(ExportColor { number, rgb: rgb.map_or_else(|| basic_color(number), |(r, g, b)| (scale(r), scale(g), scale(b))) })
+                }
+            };
+
+            ExportStyle {
+                foreground: colors.and_then(|colors| {
+                    let foreground = colors.foreground();
+
+                    color(foreground.number(), foreground.rgb().ok().map(|rgb| rgb_content(rgb.red(), rgb.green(), rgb.blue())))
+                }),
+                background: colors.and_then(|colors| {
+                    let background = colors.background();
+
+                    color(background.number(), background.rgb().ok().map(|rgb| rgb_content(rgb.red(), rgb.green(), rgb.blue())))
+                }),
+                bold:       attrs.is_bold(),
+                dim:        attrs.is_dim(),
+                italic:     attrs.is_italic(),
+                underline:  attrs.is_underline(),
+                reverse:    attrs.is_reverse() || attrs.is_standout(),
+                blink:      attrs.is_blink(),
+                invisible:  attrs.is_invisible()
+            }
+        }}
+    }
+
+    match attrs_colorpair {
+        AttributesColorPairSet::Normal(attrs_colorpair) => decode!(attrs_colorpair),
+        AttributesColorPairSet::Extend(attrs_colorpair) => decode!(attrs_colorpair)
+    }
+}
+
+// the red, green and blue content of a normal or extended color.
+fn rgb_content<T: Into<i32>>(red: T, green: T, blue: T) -> (i32, i32, i32) {
+    (red.into(), green.into(), blue.into())
+}
+
+// scale a ncurses color content (0..1000) to 0..255.
+fn scale(content: i32) -> u8 {
+    u8::try_from((content.clamp(0, 1000) * 255 + 500) / 1000).unwrap_or(u8::MAX)
+}
+
+// the rgb of a basic color or the xterm 256 color palette.
+fn basic_color(number: i32) -> (u8, u8, u8) {
+    match number {
+        0..=15    => BASIC_COLORS[number as usize],
+        16..=231  => {
+            let level = |value: i32| -> u8 { if value == 0 { 0 } else { (55 + value * 40) as u8 } };
+            let number = number - 16;
+
+            (level(number / 36), level(number / 6 % 6), level(number % 6))
+        },
+        232..=255 => {
+            let gray = (8 + (number - 232) * 10) as u8;
+
+            (gray, gray, gray)
+        },
+        _         => DEFAULT_FOREGROUND
+    }
+}
+
+// split a line of cells into runs of cells of the same style.
+fn style_runs(line: &[ExportCell]) -> Vec<(ExportStyle, String)> {
+    let mut runs: Vec<(ExportStyle, String)> = vec!();
+
+    for &(ch, style) in line {
+        let ch = if style.invisible { ' ' } else { ch };
+
+        match runs.last_mut() {
+            Some((run_style, text)) if *run_style == style => text.push(ch),
+            _                                              => runs.push((style, ch.to_string()))
+        }
+    }
+
+    runs
+}
+
+fn export_ansi(lines: &[Vec<ExportCell>]) -> String {
+    let ansi_color = |color: ExportColor, base: u8| -> String {
+        if color.number < 256 {
+            format!(";{};5;{}", base, color.number)
+        } else {
+            format!(";{};2;{};{};{}", base, color.rgb.0, color.rgb.1, color.rgb.2)
+        }
+    };
+    let mut ansi = String::new();
+
+    for line in lines {
+        for (style, text) in style_runs(line) {
+            let mut sgr = String::from("\x1b[0");
+
+            for &(enabled, code) in &[(style.bold, ";1"), (style.dim, ";2"), (style.italic, ";3"), (style.underline, ";4"), (style.blink, ";5"), (style.reverse, ";7")] {
+                if enabled {
+                    sgr.push_str(code);
+                }
+            }
+
+            if let Some(foreground) = style.foreground {
+                sgr.push_str(&ansi_color(foreground, 38));
+            }
+
+            if let Some(background) = style.background {
+                sgr.push_str(&ansi_color(background, 48));
+            }
+
+            let _ = write!(ansi, "{}m{}", sgr, text);
+        }
+
+        ansi.push_str("\x1b[0m\n");
+    }
+
+    ansi
+}
+
+fn export_html(lines: &[Vec<ExportCell>]) -> String {
+    let mut html = String::new();
+
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<style>\npre {{ color: {}; background-color: {}; font-family: monospace; line-height: 1.2; }}\n</style>\n</head>\n<body>\n<pre>",
+        css_color(DEFAULT_FOREGROUND), css_color(DEFAULT_BACKGROUND)
+    );
+
+    for line in lines {
+        for (style, text) in style_runs(line) {
+            let (foreground, background) = style.rgb();
+            let mut css = format!("color: {}; background-color: {};", css_color(foreground), css_color(background));
+
+            for &(enabled, property) in &[
+                (style.bold, " font-weight: bold;"),
+                (style.dim, " opacity: 0.5;"),
+                (style.italic, " font-style: italic;"),
+                (style.underline, " text-decoration: underline;")
+            ] {
+                if enabled {
+                    css.push_str(property);
+                }
+            }
+
+            let _ = write!(html, "<span style=\"{}\">{}</span>", css, escape_xml(&text));
+        }
+
+        html.push('\n');
+    }
+
+    html.push_str("</pre>\n</body>\n</html>\n");
+
+    html
+}
+
+fn export_svg(lines: &[Vec<ExportCell>]) -> String {
+    let columns = lines.iter().map(|line| line.iter().map(|&(ch, _)| usize::from(char_width(ch))).sum::<usize>()).max().unwrap_or(0);
+    let (width, height) = (columns * SVG_CELL_WIDTH, lines.len() * SVG_CELL_HEIGHT);
+    let mut svg = String::new();
+
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" font-family=\"monospace\" font-size=\"{}\">",
+        width, height, width, height, SVG_FONT_SIZE
+    );
+    let _ = writeln!(svg, "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>", css_color(DEFAULT_BACKGROUND));
+
+    for (y, line) in lines.iter().enumerate() {
+        let mut x = 0;
+
+        for (style, text) in style_runs(line) {
+            let (foreground, background) = style.rgb();
+            let text_columns: usize = text.chars().map(|ch| usize::from(char_width(ch))).sum();
+            let (px, py) = (x * SVG_CELL_WIDTH, y * SVG_CELL_HEIGHT);
+
+            if background != DEFAULT_BACKGROUND {
+                let _ = writeln!(
+                    svg,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                    px, py, text_columns * SVG_CELL_WIDTH, SVG_CELL_HEIGHT, css_color(background)
+                );
+            }
+
+            if !text.trim().is_empty() {
+                let mut attributes = format!("fill=\"{}\"", css_color(foreground));
+
+                for &(enabled, attribute) in &[
+                    (style.bold, " font-weight=\"bold\""),
+                    (style.dim, " opacity=\"0.5\""),
+                    (style.italic, " font-style=\"italic\""),
+                    (style.underline, " text-decoration=\"underline\"")
+                ] {
+                    if enabled {
+                        attributes.push_str(attribute);
+                    }
+                }
+
+                let _ = writeln!(
+                    svg,
+                    "<text x=\"{}\" y=\"{}\" textLength=\"{}\" lengthAdjust=\"spacingAndGlyphs\" xml:space=\"preserve\" {}>{}</text>",
+                    px, py + SVG_FONT_SIZE, text_columns * SVG_CELL_WIDTH, attributes, escape_xml(&text)
+                );
+            }
+
+            x += text_columns;
+        }
+    }
+
+    svg.push_str("</svg>\n");
+
+    svg
+}
+
+fn css_color((red, green, blue): (u8, u8, u8)) -> String {
+    format!("#{:02x}{:02x}{:02x}", red, green, blue)
+}
+
+fn escape_xml(text: &str) -> String {
+    text.chars().fold(String::with_capacity(text.len()), |mut escaped, ch| {
+        match ch {
+            '&'  => escaped.push_str("&amp;"),
+            '<'  => escaped.push_str("&lt;"),
+            '>'  => escaped.push_str("&gt;"),
+            '"'  => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _    => escaped.push(ch)
+        }
+
+        escaped
+    })
+}
+
+#[test]
+fn export_test() {
+    let red = ExportColor { number: 1, rgb: basic_color(1) };
+    let bold_red = ExportStyle { foreground: Some(red), bold: true, ..ExportStyle::default() };
+    let lines = vec!(
+        vec!(('a', ExportStyle::default()), ('<', bold_red), ('b', bold_red)),
+        vec!(('c', ExportStyle { reverse: true, ..ExportStyle::default() }))
+    );
+
+    assert_eq!(export_lines(&lines, Format::Ansi), "\x1b[0ma\x1b[0;1;38;5;1m<b\x1b[0m\n\x1b[0;7mc\x1b[0m\n");
+
+    let html = export_lines(&lines, Format::Html);
+
+    assert!(html.contains("<span style=\"color: #cd0000; background-color: #000000; font-weight: bold;\">&lt;b</span>"));
+    assert!(html.contains("<span style=\"color: #000000; background-color: #e5e5e5;\">c</span>"));
+
+    let svg = export_lines(&lines, Format::Svg);
+
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"30\" height=\"40\""));
+    assert!(svg.contains("<rect x=\"0\" y=\"20\" width=\"10\" height=\"20\" fill=\"#e5e5e5\"/>"));
+    assert!(svg.contains(">&lt;b</text>"));
+}
@@ -22,11 +22,16 @@
 
 #![allow(non_snake_case)]
 
-use std::{sync::atomic::Ordering, convert::TryFrom};
-use ncursesw::{ColorsType, ColorType, ColorAttributeTypes, CursorType};
+use std::{cmp, sync::atomic::Ordering, convert::TryFrom};
+use unicode_width::UnicodeWidthChar;
+use ncursesw::{
+    AttributesColorPairSet, ComplexChar, ComplexString, ColorsType, ColorType,
+    ColorAttributeTypes, CursorType
+};
 use crate::{
-    InputMode, Origin, Size, Window, NCurseswWinError,
-    gen::HasHandle, ncurses::{INITSCR_CALLED, COLOR_STARTED}
+    InputMode, Origin, Size, Window, BoxDrawingType, BoxDrawingGraphic, NCurseswWinError,
+    gen::{HasHandle, HasMvAddFunctions}, graphics::wide_box_graphic,
+    ncurses::{INITSCR_CALLED, COLOR_STARTED}
 };
 
 /// Return the maximum number of lines.
@@ -197,6 +202,172 @@ pub fn terminal_bottom_right_origin() -> result!(Origin) {
     Ok(Origin { y: LINES()? - 1, x: COLS()? - 1 })
 }
 
+// crate public functions.
+
+// the attributes and color pair used when a caller hasn't specified one.
+pub(in crate) fn default_attributes_color_pair() -> AttributesColorPairSet {
+    AttributesColorPairSet::Normal(crate::normal::AttributesColorPair::default())
+}
+
+// the attributes and color pair with the reverse attribute toggled.
+pub(in crate) fn reverse_attributes_color_pair(attrs_colorpair: AttributesColorPairSet) -> AttributesColorPairSet {
+    match attrs_colorpair {
+        AttributesColorPairSet::Normal(attrs_colorpair) => {
+            let attrs = attrs_colorpair.attributes();
+
+            AttributesColorPairSet::Normal(crate::normal::AttributesColorPair::new(attrs.set_reverse(!attrs.is_reverse()), attrs_colorpair.color_pair()))
+        },
+        AttributesColorPairSet::Extend(attrs_colorpair) => {
+            let attrs = attrs_colorpair.attributes();
+
+            AttributesColorPairSet::Extend(crate::extend::AttributesColorPair::new(attrs.set_reverse(!attrs.is_reverse()), attrs_colorpair.color_pair()))
+        }
+    }
+}
+
+// the attributes and color pair with the bold attribute set.
+pub(in crate) fn bold_attributes_color_pair(attrs_colorpair: AttributesColorPairSet) -> AttributesColorPairSet {
+    match attrs_colorpair {
+        AttributesColorPairSet::Normal(attrs_colorpair) => {
+            let attrs = attrs_colorpair.attributes();
+
+            AttributesColorPairSet::Normal(crate::normal::AttributesColorPair::new(attrs.set_bold(true), attrs_colorpair.color_pair()))
+        },
+        AttributesColorPairSet::Extend(attrs_colorpair) => {
+            let attrs = attrs_colorpair.attributes();
+
+            AttributesColorPairSet::Extend(crate::extend::AttributesColorPair::new(attrs.set_bold(true), attrs_colorpair.color_pair()))
+        }
+    }
+}
+
+// the attributes and color pair with the dim attribute set.
+pub(in crate) fn dim_attributes_color_pair(attrs_colorpair: AttributesColorPairSet) -> AttributesColorPairSet {
+    match attrs_colorpair {
+        AttributesColorPairSet::Normal(attrs_colorpair) => {
+            let attrs = attrs_colorpair.attributes();
+
+            AttributesColorPairSet::Normal(crate::normal::AttributesColorPair::new(attrs.set_dim(true), attrs_colorpair.color_pair()))
+        },
+        AttributesColorPairSet::Extend(attrs_colorpair) => {
+            let attrs = attrs_colorpair.attributes();
+
+            AttributesColorPairSet::Extend(crate::extend::AttributesColorPair::new(attrs.set_dim(true), attrs_colorpair.color_pair()))
+        }
+    }
+}
+
+// create a `ComplexChar` from a character and a normal or extended attributes and color pair.
+pub(in crate) fn complex_char(ch: char, attrs_colorpair: AttributesColorPairSet) -> result!(ComplexChar) {
+    Ok(match attrs_colorpair {
+        AttributesColorPairSet::Normal(attrs_colorpair) => ComplexChar::from_char(ch, &attrs_colorpair.attributes(), &attrs_colorpair.color_pair())?,
+        AttributesColorPairSet::Extend(attrs_colorpair) => ComplexChar::from_char(ch, &attrs_colorpair.attributes(), &attrs_colorpair.color_pair())?
+    })
+}
+
+// create a `ComplexChar` of a box drawing graphic and a normal or extended attributes and color pair.
+pub(in crate) fn complex_box_char(
+    box_drawing_type:    BoxDrawingType,
+    box_drawing_graphic: BoxDrawingGraphic,
+    attrs_colorpair:     AttributesColorPairSet
+) -> result!(ComplexChar) {
+    complex_char(wide_box_graphic(box_drawing_type, box_drawing_graphic).as_char()?, attrs_colorpair)
+}
+
+// the tab stop width used by `addch()`.
+pub(in crate) const TAB_SIZE: u16 = 8;
+
+// the number of columns a character occupies on the terminal, control characters occupy none.
+pub(in crate) fn char_width(ch: char) -> u16 {
+    ch.width().map_or_else(|| 0, |width| width as u16)
+}
+
+// the number of columns a string occupies on the terminal.
+pub(in crate) fn str_width(str: &str) -> u16 {
+    str.chars().fold(0, |width, ch| width.saturating_add(char_width(ch)))
+}
+
+// truncate a string to fit within a number of columns, if truncated and `ellipsis`
+// is true then the last column is replaced with an ellipsis.
+pub(in crate) fn truncate_str(str: &str, columns: u16, ellipsis: bool) -> String {
+    if str_width(str) <= columns {
+        return str.to_string();
+    } else if columns == 0 {
+        return String::new();
+    }
+
+    let columns = if ellipsis { columns.saturating_sub(1) } else { columns };
+    let mut width = 0;
+    let mut truncated: String = str
+        .chars()
+        .take_while(|&ch| {
+            width += char_width(ch);
+
+            width <= columns
+        })
+        .collect();
+
+    if ellipsis {
+        truncated.push('\u{2026}');
+    }
+
+    truncated
+}
+
+// write a string at origin padded with spaces or truncated to a number of columns using the
+// attributes and color pair, the string is clipped to the window's edge and the cursor is not moved.
+pub(in crate) fn mvadd_str_columns<W: HasMvAddFunctions>(
+    window:          &W,
+    origin:          Origin,
+    str:             &str,
+    columns:         u16,
+    attrs_colorpair: AttributesColorPairSet
+) -> result!(()) {
+    let window_size = window.size()?;
+
+    if origin.y >= window_size.lines || origin.x >= window_size.columns {
+        return Ok(());
+    }
+
+    let columns = cmp::min(columns, window_size.columns - origin.x);
+    let mut wchstr = ComplexString::with_capacity(usize::from(columns));
+    let mut width = 0;
+
+    for ch in str.chars().filter(|&ch| char_width(ch) > 0) {
+        let ch_width = char_width(ch);
+
+        if width + ch_width > columns {
+            break;
+        }
+
+        wchstr.push(&complex_char(ch, attrs_colorpair)?);
+        width += ch_width;
+    }
+
+    for _ in width..columns {
+        wchstr.push(&complex_char(' ', attrs_colorpair)?);
+    }
+
+    if wchstr.is_empty() {
+        Ok(())
+    } else {
+        window.mvadd_wchnstr(origin, &wchstr, Some(u16::try_from(wchstr.len())?))
+    }
+}
+
+// write complex characters at origin, they are clipped to the window's edge and the cursor is not moved.
+pub(in crate) fn mvadd_complex_chars<W: HasMvAddFunctions>(window: &W, origin: Origin, chars: &[ComplexChar]) -> result!(()) {
+    let window_size = window.size()?;
+
+    if origin.y >= window_size.lines || origin.x >= window_size.columns || chars.is_empty() {
+        return Ok(());
+    }
+
+    let length = cmp::min(chars.len(), usize::from(window_size.columns - origin.x));
+
+    window.mvadd_wchnstr(origin, &ComplexString::from(&chars[..length].to_vec()), Some(u16::try_from(length)?))
+}
+
 // private module functions.
 
 // check if `initscr()` has been called.
@@ -209,6 +380,6 @@ fn check_initscr_called() -> result!(()) {
 }
 
 // check if `start_color()` has been called.
-fn color_started() -> bool {
+pub(in crate) fn color_started() -> bool {
     COLOR_STARTED.load(Ordering::SeqCst)
 }
@@ -24,7 +24,7 @@
 
 use std::convert::{TryInto, TryFrom};
 use ncursesw::{ChtypeChar, ChtypeString, ComplexChar, ComplexString, WideString, WINDOW};
-use crate::{Origin, NCurseswWinError, gen::{HasHandle, HasYXAxis}};
+use crate::{Origin, Format, NCurseswWinError, gen::{HasHandle, HasYXAxis}, export::export_window};
 
 /// Does the window canvas type have ncursesw in origin functions.
 pub trait HasMvInFunctions: HasHandle<WINDOW> + HasYXAxis {
@@ -91,4 +91,12 @@ pub trait HasMvInFunctions: HasHandle<WINDOW> + HasYXAxis {
 
         Ok(ncursesw::mvwinwstr(self._handle(), origin.try_into()?)?)
     }
+
+    /// Export the contents of the window as ANSI text, a HTML document or a SVG image.
+    ///
+    /// The cells are read with `mvin_wchnstr()` and their attributes and colors decoded
+    /// using the color pair and color content of the screen.
+    fn export(&self, format: Format) -> result!(String) {
+        export_window(self, format)
+    }
 }
@@ -20,9 +20,9 @@
     IN THE SOFTWARE.
 */
 
-use std::convert::{TryFrom, TryInto};
-use ncursesw::WINDOW;
-use crate::{Region, NCurseswWinError, gen::{HasHandle, HasYXAxis}};
+use std::{cmp, convert::{TryFrom, TryInto}};
+use ncursesw::{ComplexString, WINDOW};
+use crate::{Origin, Region, NCurseswWinError, gen::{HasHandle, HasYXAxis}};
 
 /// Is the window canvas type scrollable.
 pub trait Scrollable: HasHandle<WINDOW> + HasYXAxis {
@@ -45,4 +45,78 @@ pub trait Scrollable: HasHandle<WINDOW> + HasYXAxis {
     fn getscrreg(&self) -> result!(Region) {
         Region::try_from(ncursesw::wgetscrreg(self._handle())?)
     }
+
+    /// Scroll the lines of the scrolling region horizontally by a number of
+    /// columns keeping their attributes and colors, a positive number of
+    /// columns shifts the contents to the left and a negative number to the
+    /// right. The exposed columns are cleared to the background and the
+    /// cursor is not moved.
+    fn hscrl(&self, columns: i32) -> result!(()) {
+        self.hscrl_with(columns, |_, _| Ok(()))
+    }
+
+    /// Scroll the lines of the scrolling region horizontally as `hscrl()`,
+    /// `redraw` is then called for each line with the origin of the first
+    /// exposed column and the number of exposed columns so they can be
+    /// redrawn from a source buffer.
+    fn hscrl_with<F>(&self, columns: i32, mut redraw: F) -> result!(())
+        where F: FnMut(Origin, u16) -> result!(())
+    {
+        let size = self.size()?;
+        let region = self.getscrreg()?;
+        let cursor = self.cursor()?;
+        let shift = hscrl_shift(columns, size.columns);
+
+        if shift.exposed == 0 {
+            return Ok(());
+        }
+
+        let blank = ncursesw::wgetbkgrnd(self._handle())?;
+        let blanks = ComplexString::from(&vec!(blank; usize::from(shift.exposed)));
+
+        for y in region.top..=cmp::min(region.bottom, size.lines.saturating_sub(1)) {
+            if shift.length > 0 {
+                let line = ncursesw::mvwin_wchnstr(self._handle(), Origin { y, x: shift.source }.try_into()?, i32::from(shift.length))?;
+
+                ncursesw::mvwadd_wchnstr(self._handle(), Origin { y, x: shift.destination }.try_into()?, &line, i32::from(shift.length))?;
+            }
+
+            ncursesw::mvwadd_wchnstr(self._handle(), Origin { y, x: shift.exposed_x }.try_into()?, &blanks, i32::from(shift.exposed))?;
+        }
+
+        for y in region.top..=cmp::min(region.bottom, size.lines.saturating_sub(1)) {
+            redraw(Origin { y, x: shift.exposed_x }, shift.exposed)?;
+        }
+
+        self.set_cursor(cursor)
+    }
+}
+
+// the columns copied and exposed when a line is scrolled horizontally.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct HorizontalShift {
+    source:      u16, // the first column copied.
+    destination: u16, // the column the first copied column is copied to.
+    length:      u16, // the number of columns copied.
+    exposed_x:   u16, // the first exposed column.
+    exposed:     u16  // the number of exposed columns.
+}
+
+fn hscrl_shift(columns: i32, width: u16) -> HorizontalShift {
+    let shift = u16::try_from(cmp::min(columns.unsigned_abs(), u32::from(width))).unwrap_or(width);
+    let length = width - shift;
+
+    if columns >= 0 {
+        HorizontalShift { source: shift, destination: 0, length, exposed_x: length, exposed: shift }
+    } else {
+        HorizontalShift { source: 0, destination: shift, length, exposed_x: 0, exposed: shift }
+    }
+}
+
+#[test]
+fn hscrl_shift_test() {
+    assert_eq!(hscrl_shift(3, 10), HorizontalShift { source: 3, destination: 0, length: 7, exposed_x: 7, exposed: 3 });
+    assert_eq!(hscrl_shift(-3, 10), HorizontalShift { source: 0, destination: 3, length: 7, exposed_x: 0, exposed: 3 });
+    assert_eq!(hscrl_shift(-30, 10), HorizontalShift { source: 0, destination: 10, length: 0, exposed_x: 0, exposed: 10 });
+    assert_eq!(hscrl_shift(0, 10).exposed, 0);
 }
@@ -0,0 +1,514 @@
+/*
+    src/graphics/braillecanvas.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{cmp, convert::TryFrom};
+use ncursesw::{AttributesColorPairSet, ComplexString};
+use crate::{
+    Origin, Size, NCurseswWinError, HasMvAddFunctions,
+    funcs::{complex_char, default_attributes_color_pair}
+};
+
+// the unicode code point of the blank braille pattern.
+const BRAILLE_BLANK: u32 = 0x2800;
+
+// the number of pixels (dots) in a character cell.
+const CELL_COLUMNS: i32 = 2;
+const CELL_LINES: i32 = 4;
+
+// the braille dot bit for each pixel of a character cell indexed as [line][column].
+//
+//   ,___,
+//   |1 4|
+//   |2 5|
+//   |3 6|
+//   |7 8|
+//   `````
+const BRAILLE_DOTS: [[u8; CELL_COLUMNS as usize]; CELL_LINES as usize] = [
+    [0x01, 0x08],
+    [0x02, 0x10],
+    [0x04, 0x20],
+    [0x40, 0x80]
+];
+
+/// The operation applied to a pixel when drawing on a `BrailleCanvas`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BrailleOperation {
+    /// Set the pixel.
+    #[default]
+    Set,
+    /// Clear the pixel.
+    Clear,
+    /// Toggle the pixel.
+    Toggle
+}
+
+/// A sub-cell pixel canvas giving 2x4 pixels per character cell using the
+/// unicode braille patterns (U+2800 to U+28FF).
+///
+/// Pixels are addressed by an `Origin` where `y` is the pixel line and `x` the
+/// pixel column, drawing outside of the canvas is clipped. The canvas is held in
+/// memory until `blit()` writes it to a window.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BrailleCanvas {
+    size:      Size,                                // size of the canvas in character cells.
+    cells:     Vec<u8>,                             // braille dot pattern of each character cell.
+    colors:    Vec<Option<AttributesColorPairSet>>, // attributes and color pair of each character cell.
+    pen:       Option<AttributesColorPairSet>,      // attributes and color pair applied to a cell when drawing.
+    operation: BrailleOperation                     // the operation applied to pixels when drawing.
+}
+
+impl BrailleCanvas {
+    /// Create a new canvas of a size in character cells.
+    pub fn new(size: Size) -> Self {
+        let cells = usize::from(size.lines) * usize::from(size.columns);
+
+        Self {
+            size,
+            cells:     vec!(0; cells),
+            colors:    vec!(None; cells),
+            pen:       None,
+            operation: BrailleOperation::default()
+        }
+    }
+
+    /// The size of the canvas in character cells.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// The size of the canvas in pixels.
+    pub fn pixel_size(&self) -> Size {
+        Size {
+            lines:   self.size.lines.saturating_mul(CELL_LINES as u16),
+            columns: self.size.columns.saturating_mul(CELL_COLUMNS as u16)
+        }
+    }
+
+    /// The attributes and color pair applied to a character cell when a pixel is drawn in it.
+    pub fn pen(&self) -> Option<AttributesColorPairSet> {
+        self.pen
+    }
+
+    /// Set the attributes and color pair applied to a character cell when a pixel is drawn
+    /// in it, `None` leaves the character cell's attributes and color pair unchanged.
+    pub fn set_pen(&mut self, pen: Option<AttributesColorPairSet>) {
+        self.pen = pen;
+    }
+
+    /// The operation applied to pixels by the drawing functions.
+    pub fn operation(&self) -> BrailleOperation {
+        self.operation
+    }
+
+    /// Set the operation applied to pixels by the drawing functions.
+    pub fn set_operation(&mut self, operation: BrailleOperation) {
+        self.operation = operation;
+    }
+
+    /// Clear all pixels and character cell attributes and color pairs.
+    pub fn erase(&mut self) {
+        self.cells.iter_mut().for_each(|cell| *cell = 0);
+        self.colors.iter_mut().for_each(|color| *color = None);
+    }
+
+    /// Is the pixel set.
+    pub fn pixel(&self, pixel: Origin) -> bool {
+        self.cell_dot(i32::from(pixel.x), i32::from(pixel.y))
+            .map_or_else(|| false, |(index, dot)| self.cells[index] & dot != 0)
+    }
+
+    /// Set a pixel.
+    pub fn set_pixel(&mut self, pixel: Origin) {
+        self.plot(i32::from(pixel.x), i32::from(pixel.y), BrailleOperation::Set);
+    }
+
+    /// Clear a pixel.
+    pub fn clear_pixel(&mut self, pixel: Origin) {
+        self.plot(i32::from(pixel.x), i32::from(pixel.y), BrailleOperation::Clear);
+    }
+
+    /// Toggle a pixel.
+    pub fn toggle_pixel(&mut self, pixel: Origin) {
+        self.plot(i32::from(pixel.x), i32::from(pixel.y), BrailleOperation::Toggle);
+    }
+
+    /// The attributes and color pair of a character cell.
+    pub fn cell_color(&self, cell: Origin) -> Option<AttributesColorPairSet> {
+        self.cell_index(cell).and_then(|index| self.colors[index])
+    }
+
+    /// Set the attributes and color pair of a character cell.
+    pub fn set_cell_color(&mut self, cell: Origin, color: Option<AttributesColorPairSet>) {
+        if let Some(index) = self.cell_index(cell) {
+            self.colors[index] = color;
+        }
+    }
+
+    /// The braille character of a character cell.
+    pub fn cell_char(&self, cell: Origin) -> char {
+        braille_char(self.cell_index(cell).map_or_else(|| 0, |index| self.cells[index]))
+    }
+
+    /// Draw a line between two pixels using Bresenham's line algorithm.
+    pub fn line(&mut self, from: Origin, to: Origin) {
+        let pixels = line_pixels(pixel_xy(from), pixel_xy(to), self.pixel_size());
+
+        self.draw(pixels);
+    }
+
+    /// Draw a rectangle with it's upper left pixel at origin of a size in pixels.
+    pub fn rectangle(&mut self, origin: Origin, size: Size, filled: bool) {
+        if size.lines == 0 || size.columns == 0 {
+            return;
+        }
+
+        let pixel_size = self.pixel_size();
+        let (x0, y0) = pixel_xy(origin);
+        let (x1, y1) = (x0 + i32::from(size.columns) - 1, y0 + i32::from(size.lines) - 1);
+
+        // only generate the pixels on the canvas.
+        let (columns, lines) = match (clip_span(x0, x1, pixel_size.columns), clip_span(y0, y1, pixel_size.lines)) {
+            (Some(columns), Some(lines)) => (columns, lines),
+            _                            => return
+        };
+        let mut pixels = vec!();
+
+        for y in lines.0..=lines.1 {
+            if filled || y == y0 || y == y1 {
+                pixels.extend((columns.0..=columns.1).map(|x| (x, y)));
+            } else {
+                pixels.extend([x0, x1].iter().filter(|&&x| x >= columns.0 && x <= columns.1).map(|&x| (x, y)));
+            }
+        }
+
+        self.draw(pixels);
+    }
+
+    /// Draw a circle centered on a pixel with a radius in pixels using the midpoint circle algorithm.
+    pub fn circle(&mut self, center: Origin, radius: u16, filled: bool) {
+        let pixel_size = self.pixel_size();
+        let (cx, cy) = pixel_xy(center);
+        let radius = i32::from(radius);
+        let mut pixels = vec!();
+
+        if filled {
+            // only generate the spans of the lines on the canvas.
+            if let Some((top, bottom)) = clip_span(cy - radius, cy + radius, pixel_size.lines) {
+                for y in top..=bottom {
+                    let dy = i64::from(y - cy);
+                    let dx = ((i64::from(radius).pow(2) - dy * dy) as f64).sqrt() as i32;
+
+                    if let Some((left, right)) = clip_span(cx - dx, cx + dx, pixel_size.columns) {
+                        pixels.extend((left..=right).map(|x| (x, y)));
+                    }
+                }
+            }
+        } else {
+            let (mut x, mut y) = (radius, 0);
+            let mut error = 1 - radius;
+
+            while x >= y {
+                pixels.extend([
+                    (cx + x, cy + y), (cx + y, cy + x), (cx - y, cy + x), (cx - x, cy + y),
+                    (cx - x, cy - y), (cx - y, cy - x), (cx + y, cy - x), (cx + x, cy - y)
+                ].into_iter().filter(|&pixel| on_canvas(pixel, pixel_size)));
+
+                y += 1;
+
+                if error < 0 {
+                    error += 2 * y + 1;
+                } else {
+                    x -= 1;
+                    error += 2 * (y - x) + 1;
+                }
+            }
+        }
+
+        self.draw(pixels);
+    }
+
+    /// Draw a closed polygon through the vertices, a filled polygon uses the even-odd rule.
+    pub fn polygon(&mut self, vertices: &[Origin], filled: bool) {
+        let pixel_size = self.pixel_size();
+        let points: Vec<(i32, i32)> = vertices.iter().map(|&vertex| pixel_xy(vertex)).collect();
+        let mut pixels = vec!();
+
+        // the outline of the polygon.
+        for (i, &point) in points.iter().enumerate() {
+            pixels.extend(line_pixels(point, points[(i + 1) % points.len()], pixel_size));
+        }
+
+        // the interior of the polygon sampled at the center of each pixel, only the
+        // lines and spans on the canvas are generated.
+        if filled && points.len() > 2 {
+            let min_y = points.iter().map(|&(_, y)| y).min().unwrap_or(0);
+            let max_y = points.iter().map(|&(_, y)| y).max().unwrap_or(0);
+            let (top, bottom) = match clip_span(min_y, max_y, pixel_size.lines) {
+                Some(lines) => lines,
+                None        => return self.draw(pixels)
+            };
+
+            for y in top..=bottom {
+                let scan_y = f64::from(y);
+                let mut crossings = vec!();
+
+                for (i, &(x0, y0)) in points.iter().enumerate() {
+                    let (x1, y1) = points[(i + 1) % points.len()];
+                    let (fx0, fy0, fx1, fy1) = (f64::from(x0), f64::from(y0), f64::from(x1), f64::from(y1));
+
+                    if (fy0 <= scan_y && fy1 > scan_y) || (fy1 <= scan_y && fy0 > scan_y) {
+                        crossings.push(fx0 + (scan_y - fy0) / (fy1 - fy0) * (fx1 - fx0));
+                    }
+                }
+
+                crossings.sort_by(|lhs, rhs| lhs.partial_cmp(rhs).unwrap_or(cmp::Ordering::Equal));
+
+                for pair in crossings.chunks_exact(2) {
+                    if let Some((left, right)) = clip_span(pair[0].ceil() as i32, pair[1].floor() as i32, pixel_size.columns) {
+                        pixels.extend((left..=right).map(|x| (x, y)));
+                    }
+                }
+            }
+        }
+
+        self.draw(pixels);
+    }
+
+    /// Write the canvas to a window with the canvas's upper left character cell at origin.
+    ///
+    /// The canvas is clipped to the window's edge.
+    pub fn blit<W: HasMvAddFunctions>(&self, window: &W, origin: Origin) -> result!(()) {
+        let window_size = window.size()?;
+
+        assert_origin!("blit", window_size, origin);
+
+        let lines = cmp::min(self.size.lines, window_size.lines.saturating_sub(origin.y));
+        let columns = cmp::min(self.size.columns, window_size.columns.saturating_sub(origin.x));
+
+        if columns == 0 {
+            return Ok(());
+        }
+
+        for line in 0..lines {
+            let mut wchstr = ComplexString::with_capacity(usize::from(columns));
+
+            for column in 0..columns {
+                let index = usize::from(line) * usize::from(self.size.columns) + usize::from(column);
+                let attrs_colorpair = self.colors[index].unwrap_or_else(default_attributes_color_pair);
+
+                wchstr.push(&complex_char(braille_char(self.cells[index]), attrs_colorpair)?);
+            }
+
+            window.mvadd_wchnstr(Origin { y: origin.y + line, x: origin.x }, &wchstr, Some(columns))?;
+        }
+
+        Ok(())
+    }
+
+    // apply an operation to a pixel, pixels outside of the canvas are ignored.
+    fn plot(&mut self, x: i32, y: i32, operation: BrailleOperation) {
+        if let Some((index, dot)) = self.cell_dot(x, y) {
+            match operation {
+                BrailleOperation::Set    => self.cells[index] |= dot,
+                BrailleOperation::Clear  => self.cells[index] &= !dot,
+                BrailleOperation::Toggle => self.cells[index] ^= dot
+            }
+
+            if self.pen.is_some() && operation != BrailleOperation::Clear {
+                self.colors[index] = self.pen;
+            }
+        }
+    }
+
+    // apply the current operation once to each of the pixels.
+    fn draw(&mut self, mut pixels: Vec<(i32, i32)>) {
+        pixels.sort_unstable();
+        pixels.dedup();
+
+        for (x, y) in pixels {
+            self.plot(x, y, self.operation);
+        }
+    }
+
+    // the character cell index and the braille dot of a pixel.
+    fn cell_dot(&self, x: i32, y: i32) -> Option<(usize, u8)> {
+        if !on_canvas((x, y), self.pixel_size()) {
+            return None;
+        }
+
+        let index = usize::try_from((y / CELL_LINES) * i32::from(self.size.columns) + (x / CELL_COLUMNS)).ok()?;
+
+        Some((index, BRAILLE_DOTS[(y % CELL_LINES) as usize][(x % CELL_COLUMNS) as usize]))
+    }
+
+    // the index of a character cell.
+    fn cell_index(&self, cell: Origin) -> Option<usize> {
+        if cell.y < self.size.lines && cell.x < self.size.columns {
+            Some(usize::from(cell.y) * usize::from(self.size.columns) + usize::from(cell.x))
+        } else {
+            None
+        }
+    }
+}
+
+// the part of a span of pixels from first to last on a canvas of a length in pixels.
+fn clip_span(first: i32, last: i32, length: u16) -> Option<(i32, i32)> {
+    let (first, last) = (cmp::max(first, 0), cmp::min(last, i32::from(length) - 1));
+
+    if first <= last { Some((first, last)) } else { None }
+}
+
+// is a pixel on a canvas of a size in pixels.
+fn on_canvas((x, y): (i32, i32), pixel_size: Size) -> bool {
+    x >= 0 && y >= 0 && x < i32::from(pixel_size.columns) && y < i32::from(pixel_size.lines)
+}
+
+// the x and y axis of a pixel.
+fn pixel_xy(pixel: Origin) -> (i32, i32) {
+    (i32::from(pixel.x), i32::from(pixel.y))
+}
+
+// the pixels of a line on a canvas of a size in pixels using Bresenham's line algorithm.
+fn line_pixels(from: (i32, i32), to: (i32, i32), pixel_size: Size) -> Vec<(i32, i32)> {
+    let ((mut x0, mut y0), (x1, y1)) = (from, to);
+    let (columns, lines) = (i32::from(pixel_size.columns), i32::from(pixel_size.lines));
+
+    // a line with both ends on the same side of the canvas never crosses it.
+    if (x0 < 0 && x1 < 0) || (y0 < 0 && y1 < 0) || (x0 >= columns && x1 >= columns) || (y0 >= lines && y1 >= lines) {
+        return vec!();
+    }
+
+    let dx = (x1 - x0).abs();
+    let dy = -(y1 - y0).abs();
+    let sx = if x0 < x1 { 1 } else { -1 };
+    let sy = if y0 < y1 { 1 } else { -1 };
+    let mut error = dx + dy;
+    let mut pixels = vec!();
+
+    loop {
+        // the line only crosses the canvas once so it's finished when it leaves the canvas.
+        if on_canvas((x0, y0), pixel_size) {
+            pixels.push((x0, y0));
+        } else if !pixels.is_empty() {
+            break pixels;
+        }
+
+        if x0 == x1 && y0 == y1 {
+            break pixels;
+        }
+
+        let error2 = error * 2;
+
+        if error2 >= dy {
+            error += dy;
+            x0 += sx;
+        }
+
+        if error2 <= dx {
+            error += dx;
+            y0 += sy;
+        }
+    }
+}
+
+// the braille character of a dot pattern.
+fn braille_char(dots: u8) -> char {
+    char::from_u32(BRAILLE_BLANK + u32::from(dots)).unwrap_or(' ')
+}
+
+#[test]
+fn braille_canvas_test() {
+    let mut canvas = BrailleCanvas::new(Size { lines: 2, columns: 2 });
+
+    assert_eq!(canvas.pixel_size(), Size { lines: 8, columns: 4 });
+
+    // each dot of the first cell.
+    for y in 0..4 {
+        for x in 0..2 {
+            canvas.set_pixel(Origin { y, x });
+        }
+    }
+
+    assert_eq!(canvas.cell_char(Origin { y: 0, x: 0 }), '\u{28ff}');
+    assert_eq!(canvas.cell_char(Origin { y: 0, x: 1 }), '\u{2800}');
+
+    canvas.clear_pixel(Origin { y: 3, x: 1 });
+    assert_eq!(canvas.cell_char(Origin { y: 0, x: 0 }), '\u{287f}');
+
+    canvas.toggle_pixel(Origin { y: 3, x: 1 });
+    canvas.toggle_pixel(Origin { y: 0, x: 0 });
+    assert_eq!(canvas.cell_char(Origin { y: 0, x: 0 }), '\u{28fe}');
+
+    // drawing off the canvas is clipped.
+    canvas.erase();
+    canvas.set_pixel(Origin { y: 8, x: 4 });
+    canvas.line(Origin { y: 0, x: 0 }, Origin { y: 0, x: 100 });
+    assert_eq!(canvas.cell_char(Origin { y: 0, x: 0 }), '\u{2809}');
+    assert_eq!(canvas.cell_char(Origin { y: 0, x: 1 }), '\u{2809}');
+    assert_eq!(canvas.cell_char(Origin { y: 1, x: 0 }), '\u{2800}');
+
+    // a filled rectangle and a filled polygon of the same area.
+    let mut polygon = BrailleCanvas::new(Size { lines: 2, columns: 2 });
+
+    canvas.erase();
+    canvas.rectangle(Origin { y: 1, x: 1 }, Size { lines: 4, columns: 2 }, true);
+    polygon.polygon(&[Origin { y: 1, x: 1 }, Origin { y: 1, x: 2 }, Origin { y: 4, x: 2 }, Origin { y: 4, x: 1 }], true);
+    assert_eq!(canvas, polygon);
+
+    // a circle is symmetrical.
+    canvas.erase();
+    canvas.circle(Origin { y: 3, x: 1 }, 1, false);
+    assert!(canvas.pixel(Origin { y: 2, x: 1 }));
+    assert!(canvas.pixel(Origin { y: 4, x: 1 }));
+    assert!(canvas.pixel(Origin { y: 3, x: 0 }));
+    assert!(canvas.pixel(Origin { y: 3, x: 2 }));
+    assert!(!canvas.pixel(Origin { y: 3, x: 1 }));
+
+    // toggling a closed shape twice restores the canvas.
+    canvas.erase();
+    canvas.set_operation(BrailleOperation::Toggle);
+    canvas.polygon(&[Origin { y: 0, x: 0 }, Origin { y: 0, x: 3 }, Origin { y: 7, x: 0 }], true);
+    canvas.polygon(&[Origin { y: 0, x: 0 }, Origin { y: 0, x: 3 }, Origin { y: 7, x: 0 }], true);
+    assert_eq!(canvas, BrailleCanvas { operation: BrailleOperation::Toggle, ..BrailleCanvas::new(Size { lines: 2, columns: 2 }) });
+
+    // large shapes are clipped to the canvas without overflowing.
+    canvas.erase();
+    canvas.circle(Origin { y: 0, x: 0 }, u16::MAX, true);
+    assert_eq!(canvas.cell_char(Origin { y: 1, x: 1 }), '\u{28ff}');
+
+    canvas.erase();
+    canvas.rectangle(Origin { y: 2, x: 1 }, Size { lines: u16::MAX, columns: u16::MAX }, false);
+    assert!(canvas.pixel(Origin { y: 2, x: 3 }));
+    assert!(canvas.pixel(Origin { y: 7, x: 1 }));
+    assert!(!canvas.pixel(Origin { y: 7, x: 3 }));
+
+    canvas.erase();
+    canvas.set_operation(BrailleOperation::Set);
+    canvas.polygon(&[Origin { y: 0, x: 0 }, Origin { y: 0, x: u16::MAX }, Origin { y: u16::MAX, x: u16::MAX }], true);
+    assert!(canvas.pixel(Origin { y: 0, x: 3 }));
+    assert!(canvas.pixel(Origin { y: 3, x: 3 }));
+    assert!(!canvas.pixel(Origin { y: 7, x: 0 }));
+
+    canvas.erase();
+    canvas.polygon(&[Origin { y: 0, x: 100 }, Origin { y: 0, x: u16::MAX }, Origin { y: u16::MAX, x: 100 }], true);
+    assert_eq!(canvas, BrailleCanvas::new(Size { lines: 2, columns: 2 }));
+}
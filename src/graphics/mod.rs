@@ -24,13 +24,14 @@ mod boxdrawing;
 mod boxdrawinggraphic;
 mod boxdrawingtypedetail;
 mod boxdrawingtype;
+mod braillecanvas;
 mod funcs;
 mod horizontalgraphic;
 mod matrixkey;
 mod verticalgraphic;
 
 pub use self::{
-    boxdrawing::*, boxdrawinggraphic::*, boxdrawingtypedetail::*, boxdrawingtype::*,
+    boxdrawing::*, boxdrawinggraphic::*, boxdrawingtypedetail::*, boxdrawingtype::*, braillecanvas::*,
     funcs::*, horizontalgraphic::*, verticalgraphic::*
 };
 pub(in crate) use matrixkey::*;
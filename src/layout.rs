@@ -0,0 +1,382 @@
+/*
+    src/layout.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::cmp;
+use crate::{Origin, Size, Window, Panel, NCurseswWinError, HasYXAxis, Moveable, LINES, COLS};
+
+/// A constraint on the length of an area of a `Layout`, the length is in
+/// lines for a layout of rows and columns for a layout of columns.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Constraint {
+    /// A fixed length.
+    Length(u16),
+    /// A percentage of the available length.
+    Percentage(u16),
+    /// A ratio of the available length i.e. `Ratio(1, 3)` is a third.
+    Ratio(u32, u32),
+    /// At least a length, growing with any remaining length.
+    Min(u16),
+    /// At most a length, growing into any remaining length up to the maximum.
+    Max(u16),
+    /// A weighted share of any remaining length.
+    Fill(u16)
+}
+
+/// The direction the areas of a `Layout` are arranged in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LayoutDirection {
+    /// The areas are one above the other.
+    Rows,
+    /// The areas are side by side.
+    Columns
+}
+
+/// The margin around the areas of a `Layout`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Margin {
+    pub top:    u16,
+    pub bottom: u16,
+    pub left:   u16,
+    pub right:  u16
+}
+
+impl Margin {
+    /// A margin of the same width on all sides.
+    pub fn uniform(margin: u16) -> Self {
+        Self { top: margin, bottom: margin, left: margin, right: margin }
+    }
+}
+
+/// What is placed in an area of a `Layout`.
+#[derive(Debug)]
+pub enum LayoutTarget {
+    /// The area is left empty.
+    Empty,
+    /// The area is a window which is resized and moved.
+    Window(Window),
+    /// The area is a panel whose window is resized and moved with `move_panel()`,
+    /// the panel is hidden while it's area has no lines or columns.
+    Panel(Panel),
+    /// The area is divided by a nested layout.
+    Layout(Layout)
+}
+
+/// A layout of rows or columns of areas described by constraints.
+///
+/// `split()` calculates the origin and size of each area, and `apply()`
+/// resizes and moves the windows and panels placed in the areas of the
+/// layout and any nested layouts. `apply_to_screen()` applies the layout to
+/// the whole screen when the terminal size has changed, so it can be called
+/// on every `KeyBinding::ResizeEvent` or loop of an event loop.
+///
+/// Where the fixed lengths of the constraints exceed the available length the
+/// areas are shrunk starting from the last area, where they are less than the
+/// available length the remainder is shared between the `Min`, `Max` and
+/// `Fill` areas by weight (`Min` and `Max` have a weight of 1), a `Max` area
+/// not growing beyond it's maximum.
+#[derive(Debug)]
+pub struct Layout {
+    direction:   LayoutDirection,
+    margin:      Margin,
+    spacing:     u16,
+    areas:       Vec<(Constraint, LayoutTarget)>,
+    screen_size: Option<Size>
+}
+
+impl Layout {
+    /// Create a new layout of areas in the specified direction.
+    pub fn new(direction: LayoutDirection) -> Self {
+        Self { direction, margin: Margin::default(), spacing: 0, areas: vec!(), screen_size: None }
+    }
+
+    /// Create a new layout of areas one above the other.
+    pub fn rows() -> Self {
+        Self::new(LayoutDirection::Rows)
+    }
+
+    /// Create a new layout of areas side by side.
+    pub fn columns() -> Self {
+        Self::new(LayoutDirection::Columns)
+    }
+
+    /// Add an area to the layout.
+    pub fn add(mut self, constraint: Constraint, target: LayoutTarget) -> Self {
+        self.push(constraint, target);
+
+        self
+    }
+
+    /// Add an area to the layout.
+    pub fn push(&mut self, constraint: Constraint, target: LayoutTarget) {
+        self.areas.push((constraint, target));
+    }
+
+    /// Set the margin around the areas of the layout.
+    pub fn with_margin(mut self, margin: Margin) -> Self {
+        self.margin = margin;
+
+        self
+    }
+
+    /// Set the number of lines or columns between the areas of the layout.
+    pub fn with_spacing(mut self, spacing: u16) -> Self {
+        self.spacing = spacing;
+
+        self
+    }
+
+    /// The direction of the layout.
+    pub fn direction(&self) -> LayoutDirection {
+        self.direction
+    }
+
+    /// The constraints and targets of the areas of the layout.
+    pub fn areas(&self) -> &[(Constraint, LayoutTarget)] {
+        &self.areas
+    }
+
+    /// Calculate the origin and size of each area of the layout within a region.
+    pub fn split(&self, origin: Origin, size: Size) -> Vec<(Origin, Size)> {
+        let inner_origin = Origin { y: origin.y + self.margin.top, x: origin.x + self.margin.left };
+        let inner_size = Size {
+            lines:   size.lines.saturating_sub(self.margin.top + self.margin.bottom),
+            columns: size.columns.saturating_sub(self.margin.left + self.margin.right)
+        };
+
+        let (length, breadth) = match self.direction {
+            LayoutDirection::Rows    => (inner_size.lines, inner_size.columns),
+            LayoutDirection::Columns => (inner_size.columns, inner_size.lines)
+        };
+
+        let spacing = self.spacing.saturating_mul(u16::try_from(self.areas.len().saturating_sub(1)).unwrap_or(u16::MAX));
+        let constraints: Vec<Constraint> = self.areas.iter().map(|(constraint, _)| *constraint).collect();
+        let lengths = solve(&constraints, length.saturating_sub(spacing));
+        let mut offset = 0;
+
+        lengths.into_iter().map(|area_length| {
+            let area = match self.direction {
+                LayoutDirection::Rows    => (
+                    Origin { y: inner_origin.y + offset, x: inner_origin.x },
+                    Size { lines: area_length, columns: breadth }
+                ),
+                LayoutDirection::Columns => (
+                    Origin { y: inner_origin.y, x: inner_origin.x + offset },
+                    Size { lines: breadth, columns: area_length }
+                )
+            };
+
+            offset += area_length + self.spacing;
+
+            area
+        }).collect()
+    }
+
+    /// Resize and move the windows and panels of the layout and any nested
+    /// layouts into a region.
+    ///
+    /// ncurses does not allow a window with no lines or columns, so a window
+    /// in an area with no lines or columns is shrunk to one line and column
+    /// inside the region, and a panel is hidden until it's area is not empty.
+    pub fn apply(&self, origin: Origin, size: Size) -> result!(()) {
+        for ((_, target), (area_origin, area_size)) in self.areas.iter().zip(self.split(origin, size)) {
+            let (window_origin, window_size) = window_area(origin, size, area_origin, area_size);
+
+            match target {
+                LayoutTarget::Empty          => { },
+                LayoutTarget::Window(window) => place_window(window, window_origin, window_size, |origin| window.mvwin(origin))?,
+                LayoutTarget::Panel(panel)   => {
+                    if area_size.lines == 0 || area_size.columns == 0 {
+                        if !panel.panel_hidden()? {
+                            panel.hide_panel()?;
+                        }
+                    } else {
+                        place_window(&panel.panel_window()?, window_origin, window_size, |origin| panel.move_panel(origin))?;
+
+                        if panel.panel_hidden()? {
+                            panel.show_panel()?;
+                        }
+                    }
+                },
+                LayoutTarget::Layout(layout) => layout.apply(area_origin, area_size)?
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Apply the layout to the whole screen if the terminal size has changed
+    /// since the layout was last applied to the screen, returning `true` if
+    /// the layout was applied.
+    pub fn apply_to_screen(&mut self) -> result!(bool) {
+        let screen_size = Size { lines: LINES()?, columns: COLS()? };
+
+        if self.screen_size == Some(screen_size) {
+            return Ok(false);
+        }
+
+        self.apply(Origin::default(), screen_size)?;
+        self.screen_size = Some(screen_size);
+
+        Ok(true)
+    }
+}
+
+// the origin and size of the window of an area of a region, ncurses does not allow
+// a window with no lines or columns so an empty area has a window of one line and
+// column, moved back inside the region when the area is at the far edge of it.
+fn window_area(origin: Origin, size: Size, area_origin: Origin, area_size: Size) -> (Origin, Size) {
+    let last = Origin {
+        y: origin.y.saturating_add(size.lines.saturating_sub(1)),
+        x: origin.x.saturating_add(size.columns.saturating_sub(1))
+    };
+
+    (
+        Origin { y: cmp::min(area_origin.y, last.y), x: cmp::min(area_origin.x, last.x) },
+        Size { lines: cmp::max(area_size.lines, 1), columns: cmp::max(area_size.columns, 1) }
+    )
+}
+
+// resize and move a window, shrinking it before it's moved and growing it
+// after so it stays on the screen.
+fn place_window<F>(window: &Window, origin: Origin, size: Size, move_window: F) -> result!(())
+    where F: Fn(Origin) -> result!(())
+{
+    let current = window.size()?;
+
+    window.resize(Size { lines: cmp::min(current.lines, size.lines), columns: cmp::min(current.columns, size.columns) })?;
+    move_window(origin)?;
+    window.resize(size)
+}
+
+// the growth weight of a constraint when sharing out any remaining length.
+fn weight(constraint: Constraint) -> u32 {
+    match constraint {
+        Constraint::Min(_) | Constraint::Max(_) => 1,
+        Constraint::Fill(weight)                => u32::from(weight),
+        _                                       => 0
+    }
+}
+
+// calculate the length of each area from it's constraint.
+fn solve(constraints: &[Constraint], length: u16) -> Vec<u16> {
+    let available = u32::from(length);
+
+    let mut lengths: Vec<u32> = constraints.iter().map(|constraint| match *constraint {
+        Constraint::Length(length) | Constraint::Min(length) => u32::from(length),
+        Constraint::Percentage(percent)                       => available * u32::from(cmp::min(percent, 100)) / 100,
+        Constraint::Ratio(numerator, denominator)             => {
+            if denominator == 0 {
+                0
+            } else {
+                (u64::from(available) * u64::from(cmp::min(numerator, denominator)) / u64::from(denominator)) as u32
+            }
+        },
+        Constraint::Max(_) | Constraint::Fill(_)              => 0
+    }).collect();
+
+    // shrink from the last area until the areas fit.
+    let mut excess = lengths.iter().sum::<u32>().saturating_sub(available);
+
+    for length in lengths.iter_mut().rev() {
+        let shrink = cmp::min(*length, excess);
+
+        *length -= shrink;
+        excess -= shrink;
+    }
+
+    // share the remaining length by weight, redistributing what a `Max` area can't take.
+    let mut remaining = available - lengths.iter().sum::<u32>();
+    let mut growable: Vec<usize> = (0..constraints.len()).filter(|&i| weight(constraints[i]) > 0).collect();
+
+    while remaining > 0 && !growable.is_empty() {
+        let total_weight: u32 = growable.iter().map(|&i| weight(constraints[i])).sum();
+        let mut shares: Vec<u32> = growable.iter().map(|&i| remaining * weight(constraints[i]) / total_weight).collect();
+
+        // hand out the rounding remainder one at a time from the first area.
+        let mut remainder = remaining - shares.iter().sum::<u32>();
+
+        for share in shares.iter_mut() {
+            if remainder == 0 {
+                break;
+            }
+
+            *share += 1;
+            remainder -= 1;
+        }
+
+        // fill any `Max` areas that can't take their share and share out the rest again.
+        let capped: Vec<usize> = growable.iter().zip(shares.iter()).filter_map(|(&i, &share)| match constraints[i] {
+            Constraint::Max(max) if share >= u32::from(max).saturating_sub(lengths[i]) => Some(i),
+            _                                                                          => None
+        }).collect();
+
+        if capped.is_empty() {
+            for (&i, share) in growable.iter().zip(shares) {
+                lengths[i] += share;
+            }
+
+            break;
+        }
+
+        for &i in capped.iter() {
+            if let Constraint::Max(max) = constraints[i] {
+                let room = u32::from(max).saturating_sub(lengths[i]);
+
+                lengths[i] += room;
+                remaining -= room;
+            }
+        }
+
+        growable.retain(|i| !capped.contains(i));
+    }
+
+    lengths.into_iter().map(|length| length as u16).collect()
+}
+
+#[test]
+fn layout_test() {
+    assert_eq!(solve(&[Constraint::Length(3), Constraint::Fill(1), Constraint::Length(1)], 24), vec!(3, 20, 1));
+    assert_eq!(solve(&[Constraint::Percentage(25), Constraint::Ratio(1, 2), Constraint::Min(2)], 40), vec!(10, 20, 10));
+    assert_eq!(solve(&[Constraint::Max(5), Constraint::Fill(1), Constraint::Fill(2)], 35), vec!(5, 10, 20));
+    assert_eq!(solve(&[Constraint::Length(10), Constraint::Length(10)], 15), vec!(10, 5));
+
+    let layout = Layout::columns()
+        .with_margin(Margin::uniform(1))
+        .with_spacing(1)
+        .add(Constraint::Length(10), LayoutTarget::Empty)
+        .add(Constraint::Fill(1), LayoutTarget::Empty);
+
+    assert_eq!(layout.split(Origin { y: 2, x: 0 }, Size { lines: 10, columns: 40 }), vec!(
+        (Origin { y: 3, x: 1 }, Size { lines: 8, columns: 10 }),
+        (Origin { y: 3, x: 12 }, Size { lines: 8, columns: 27 })
+    ));
+
+    let layout = Layout::columns()
+        .add(Constraint::Length(80), LayoutTarget::Empty)
+        .add(Constraint::Fill(1), LayoutTarget::Empty);
+    let size = Size { lines: 24, columns: 80 };
+    let areas = layout.split(Origin::default(), size);
+
+    assert_eq!(areas[1], (Origin { y: 0, x: 80 }, Size { lines: 24, columns: 0 }));
+    assert_eq!(window_area(Origin::default(), size, areas[1].0, areas[1].1), (Origin { y: 0, x: 79 }, Size { lines: 24, columns: 1 }));
+    assert_eq!(window_area(Origin::default(), size, areas[0].0, areas[0].1), areas[0]);
+}
@@ -27,12 +27,16 @@ extern crate lazy_static;
 extern crate strum;
 extern crate strum_macros;
 extern crate errno;
+extern crate unicode_width;
 
 mod macros;
 
+mod autopad;
+mod cellbuffer;
 mod cstring;
 /// Extended color's, color pairs and attributes module.
 pub mod extend;
+mod export;
 /// Form module
 ///
 /// The form library provides terminal-independent facilities for composing
@@ -53,6 +57,12 @@ mod funcs;
 mod gen;
 mod graphics;
 mod inputmode;
+/// Layout module
+///
+/// Layouts divide a region into rows or columns of areas described by
+/// constraints and resize and move windows and panels to fit the areas
+/// whenever the terminal size changes.
+pub mod layout;
 /// Menu module
 ///
 /// The menu library provides terminal-independent facilities for composing
@@ -68,6 +78,7 @@ mod nonblockingresult;
 pub mod normal;
 mod origin;
 mod pad;
+mod padviewport;
 mod panels;
 mod position;
 mod region;
@@ -75,12 +86,22 @@ mod ripoff;
 mod screen;
 mod size;
 mod timeout;
+/// Widgets module
+///
+/// Widgets render into a region of a window given an `Origin` and a `Size`,
+/// the module includes charts such as sparklines, bar charts, line charts
+/// and gauges, and interactive widgets such as list views, tables, trees,
+/// text inputs, text areas, modal dialogs, a file picker, a status bar,
+/// split panes, a scrollback window, scrollbars, a pager, a log console,
+/// toast notifications, a hex view and a command palette.
+pub mod widgets;
 mod window;
+mod windowtext;
 
 pub use crate::{
-    funcs::*, gen::*, graphics::*, inputmode::*, mouse::*, ncurses::*,
-    ncurseswwinerror::*, nonblockingresult::*, origin::*, pad::*,
-    panels::*, position::*, region::*, ripoff::*, screen::*, size::*,
+    autopad::*, cellbuffer::*, export::*, funcs::*, gen::*, graphics::*, inputmode::*,
+    mouse::*, ncurses::*, ncurseswwinerror::*, nonblockingresult::*,
+    origin::*, pad::*, padviewport::*, panels::*, position::*, region::*, ripoff::*, screen::*, size::*,
     timeout::*, window::*
 };
 
@@ -20,7 +20,7 @@
     IN THE SOFTWARE.
 */
 
-use std::{num, convert, ffi};
+use std::{num, convert, ffi, io};
 use thiserror::Error;
 use ncursesw::{
     NCurseswError, panels::NCurseswPanelsError, mouse::NCurseswMouseError,
@@ -29,7 +29,7 @@ use ncursesw::{
 use crate::ripoff::MAX_RIPOFF_LINES;
 
 /// NCurseswWin Errors.
-#[derive(Error, Debug, PartialEq, Eq)]
+#[derive(Error, Debug)]
 pub enum NCurseswWinError {
     #[error("ncurses has already been initialised")]
     InitscrAlreadyCalled,
@@ -53,6 +53,10 @@ pub enum NCurseswWinError {
     FieldTypeArguments { func: String, args: u8 },
     #[error("softlabel already defined.")]
     SoftLabelAlreadyDefined,
+    #[error("line {line} of window text is invalid, {message}")]
+    WindowFormat { line: usize, message: String },
+    #[error("{func}() is not supported")]
+    NotSupported { func: String },
 
     #[error("{source}")]
     NCurseswError { #[from] source: NCurseswError },
@@ -65,6 +69,8 @@ pub enum NCurseswWinError {
     #[error("{source}")]
     FormError { #[from] source: NCurseswFormError },
 
+    #[error("{source}")]
+    IOError { #[from] source: io::Error },
     #[error("{source}")]
     TryFromIntError { #[from] source: num::TryFromIntError },
     #[error("{source}")]
@@ -72,3 +78,38 @@ pub enum NCurseswWinError {
     #[error("{source}")]
     Infallible { #[from] source: convert::Infallible }
 }
+
+// `std::io::Error` isn't comparable so io errors are equal if their kind and message are.
+impl PartialEq for NCurseswWinError {
+    fn eq(&self, other: &Self) -> bool {
+        use NCurseswWinError::*;
+
+        match (self, other) {
+            (InitscrAlreadyCalled, InitscrAlreadyCalled)                             |
+            (InitscrNotCalled, InitscrNotCalled)                                     |
+            (StartColorAlreadyCalled, StartColorAlreadyCalled)                       |
+            (StartColorNotCalled, StartColorNotCalled)                               |
+            (InternalError, InternalError)                                           |
+            (MouseId, MouseId)                                                       |
+            (SoftLabelAlreadyDefined, SoftLabelAlreadyDefined)                       => true,
+            (MaximumRipoffLines { number: lhs }, MaximumRipoffLines { number: rhs }) => lhs == rhs,
+            (Panic { message: lhs }, Panic { message: rhs })                         => lhs == rhs,
+            (OutOfMemory { func: lhs }, OutOfMemory { func: rhs })                   => lhs == rhs,
+            (FieldTypeArguments { func: lhs, args: lhs_args }, FieldTypeArguments { func: rhs, args: rhs_args }) => lhs == rhs && lhs_args == rhs_args,
+            (WindowFormat { line: lhs, message: lhs_message }, WindowFormat { line: rhs, message: rhs_message }) => lhs == rhs && lhs_message == rhs_message,
+            (NotSupported { func: lhs }, NotSupported { func: rhs })                 => lhs == rhs,
+            (NCurseswError { source: lhs }, NCurseswError { source: rhs })           => lhs == rhs,
+            (PanelsError { source: lhs }, PanelsError { source: rhs })               => lhs == rhs,
+            (MouseError { source: lhs }, MouseError { source: rhs })                 => lhs == rhs,
+            (MenuError { source: lhs }, MenuError { source: rhs })                   => lhs == rhs,
+            (FormError { source: lhs }, FormError { source: rhs })                   => lhs == rhs,
+            (IOError { source: lhs }, IOError { source: rhs })                       => lhs.kind() == rhs.kind() && lhs.to_string() == rhs.to_string(),
+            (TryFromIntError { source: lhs }, TryFromIntError { source: rhs })       => lhs == rhs,
+            (NulError { source: lhs }, NulError { source: rhs })                     => lhs == rhs,
+            (Infallible { source: lhs }, Infallible { source: rhs })                 => lhs == rhs,
+            _                                                                        => false
+        }
+    }
+}
+
+impl Eq for NCurseswWinError { }
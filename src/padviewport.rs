@@ -0,0 +1,250 @@
+/*
+    src/padviewport.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::cmp;
+use crate::{Origin, Size, Pad, NCurseswWinError, IsPad, HasYXAxis};
+
+/// A pad shown in a rectangle of the screen scrolled to an offset.
+///
+/// The viewport calculates the origins passed to `prefresh()` and
+/// `pnoutrefresh()` and clamps the scroll offset so the pad never scrolls
+/// past the edges of it's content, by default the content is the whole pad
+/// but it can be set to the part of the pad that has been written to.
+#[derive(Debug)]
+pub struct PadViewport {
+    pad:     Pad,
+    origin:  Origin,       // the screen origin of the rectangle.
+    size:    Size,         // the size of the rectangle.
+    scroll:  Origin,       // the origin of the pad shown at the top left of the rectangle.
+    content: Option<Size>  // the size of the content if less than the pad.
+}
+
+impl PadViewport {
+    /// Create a new viewport showing a pad in a rectangle of the screen.
+    pub fn new(pad: Pad, origin: Origin, size: Size) -> Self {
+        Self { pad, origin, size, scroll: Origin::default(), content: None }
+    }
+
+    /// The pad of the viewport.
+    pub fn pad(&self) -> &Pad {
+        &self.pad
+    }
+
+    /// Consume the viewport returning it's pad.
+    pub fn into_pad(self) -> Pad {
+        self.pad
+    }
+
+    /// The screen origin of the viewport.
+    pub fn origin(&self) -> Origin {
+        self.origin
+    }
+
+    /// The size of the viewport on the screen.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Move and resize the viewport's rectangle, for example on a terminal
+    /// resize, the scroll offset is clamped to the new size.
+    pub fn resize(&mut self, origin: Origin, size: Size) -> result!(()) {
+        self.origin = origin;
+        self.size = size;
+
+        self.scroll_to(self.scroll).map(|_| ())
+    }
+
+    /// The size of the content of the pad.
+    pub fn content_size(&self) -> result!(Size) {
+        let size = self.pad.size()?;
+
+        Ok(self.content.map_or(size, |content| Size {
+            lines:   cmp::min(content.lines, size.lines),
+            columns: cmp::min(content.columns, size.columns)
+        }))
+    }
+
+    /// Set the size of the content of the pad, `None` for the whole pad.
+    pub fn set_content_size(&mut self, content: Option<Size>) -> result!(()) {
+        self.content = content;
+
+        self.scroll_to(self.scroll).map(|_| ())
+    }
+
+    /// The origin of the pad shown at the top left of the viewport.
+    pub fn scroll(&self) -> Origin {
+        self.scroll
+    }
+
+    /// Scroll the viewport to an origin of the pad, returning `true` if the
+    /// scroll offset changed.
+    pub fn scroll_to(&mut self, origin: Origin) -> result!(bool) {
+        let scroll = clamp_scroll(origin, self.content_size()?, self.size);
+        let changed = scroll != self.scroll;
+
+        self.scroll = scroll;
+
+        Ok(changed)
+    }
+
+    /// Scroll the viewport by a number of lines and columns, returning `true`
+    /// if the scroll offset changed.
+    pub fn scroll_by(&mut self, lines: isize, columns: isize) -> result!(bool) {
+        self.scroll_to(Origin { y: offset(self.scroll.y, lines), x: offset(self.scroll.x, columns) })
+    }
+
+    /// Scroll the viewport up by a page.
+    pub fn page_up(&mut self) -> result!(bool) {
+        self.scroll_by(-page(self.size.lines), 0)
+    }
+
+    /// Scroll the viewport down by a page.
+    pub fn page_down(&mut self) -> result!(bool) {
+        self.scroll_by(page(self.size.lines), 0)
+    }
+
+    /// Scroll the viewport to the top left of the pad.
+    pub fn scroll_to_top(&mut self) -> result!(bool) {
+        self.scroll_to(Origin::default())
+    }
+
+    /// Scroll the viewport to the last page of the content.
+    pub fn scroll_to_bottom(&mut self) -> result!(bool) {
+        self.scroll_to(Origin { y: u16::MAX, x: self.scroll.x })
+    }
+
+    /// Is an origin of the pad visible in the viewport.
+    pub fn is_visible(&self, origin: Origin) -> bool {
+        origin.y >= self.scroll.y && origin.y - self.scroll.y < self.size.lines &&
+        origin.x >= self.scroll.x && origin.x - self.scroll.x < self.size.columns
+    }
+
+    /// Scroll the viewport by the least amount so an origin of the pad is
+    /// visible, returning `true` if the scroll offset changed.
+    pub fn ensure_visible(&mut self, origin: Origin) -> result!(bool) {
+        self.scroll_to(visible_scroll(self.scroll, self.size, origin))
+    }
+
+    /// Scroll the viewport so the pad's cursor is visible, returning `true`
+    /// if the scroll offset changed.
+    pub fn follow_cursor(&mut self) -> result!(bool) {
+        self.ensure_visible(self.pad.cursor()?)
+    }
+
+    /// The screen origin of a pad origin if it's visible in the viewport.
+    pub fn screen_origin(&self, origin: Origin) -> Option<Origin> {
+        if self.is_visible(origin) {
+            Some(Origin { y: self.origin.y + origin.y - self.scroll.y, x: self.origin.x + origin.x - self.scroll.x })
+        } else {
+            None
+        }
+    }
+
+    /// The pad origin of a screen origin if it's within the viewport.
+    pub fn pad_origin(&self, screen_origin: Origin) -> Option<Origin> {
+        if screen_origin.y >= self.origin.y && screen_origin.y - self.origin.y < self.size.lines &&
+           screen_origin.x >= self.origin.x && screen_origin.x - self.origin.x < self.size.columns
+        {
+            Some(Origin { y: self.scroll.y + screen_origin.y - self.origin.y, x: self.scroll.x + screen_origin.x - self.origin.x })
+        } else {
+            None
+        }
+    }
+
+    /// Copy the visible part of the pad to the screen.
+    pub fn refresh(&self) -> result!(()) {
+        if let Some(screen_max) = self.screen_max() {
+            self.pad.prefresh(self.scroll, self.origin, screen_max)?;
+        }
+
+        Ok(())
+    }
+
+    /// Copy the visible part of the pad to the virtual screen.
+    pub fn noutrefresh(&self) -> result!(()) {
+        if let Some(screen_max) = self.screen_max() {
+            self.pad.pnoutrefresh(self.scroll, self.origin, screen_max)?;
+        }
+
+        Ok(())
+    }
+
+    // the bottom right screen origin of the rectangle, `None` for an empty rectangle.
+    fn screen_max(&self) -> Option<Origin> {
+        if self.size.lines == 0 || self.size.columns == 0 {
+            None
+        } else {
+            Some(Origin { y: self.origin.y + self.size.lines - 1, x: self.origin.x + self.size.columns - 1 })
+        }
+    }
+}
+
+// a page of lines, keeping one line of context when the page is large enough.
+fn page(lines: u16) -> isize {
+    cmp::max(lines.saturating_sub(1), 1) as isize
+}
+
+fn offset(value: u16, delta: isize) -> u16 {
+    let value = if delta < 0 {
+        usize::from(value).saturating_sub(delta.unsigned_abs())
+    } else {
+        usize::from(value).saturating_add(delta as usize)
+    };
+
+    u16::try_from(value).unwrap_or(u16::MAX)
+}
+
+// clamp a scroll offset so the viewport doesn't scroll past the content.
+fn clamp_scroll(scroll: Origin, content: Size, view: Size) -> Origin {
+    Origin {
+        y: cmp::min(scroll.y, content.lines.saturating_sub(view.lines)),
+        x: cmp::min(scroll.x, content.columns.saturating_sub(view.columns))
+    }
+}
+
+// the least scroll offset change that makes an origin visible.
+fn visible_scroll(scroll: Origin, view: Size, origin: Origin) -> Origin {
+    let axis = |scroll: u16, view: u16, position: u16| {
+        if position < scroll {
+            position
+        } else if view > 0 && position >= scroll + view {
+            position + 1 - view
+        } else {
+            scroll
+        }
+    };
+
+    Origin { y: axis(scroll.y, view.lines, origin.y), x: axis(scroll.x, view.columns, origin.x) }
+}
+
+#[test]
+fn pad_viewport_test() {
+    let content = Size { lines: 100, columns: 40 };
+    let view = Size { lines: 10, columns: 20 };
+
+    assert_eq!(clamp_scroll(Origin { y: 95, x: 30 }, content, view), Origin { y: 90, x: 20 });
+    assert_eq!(clamp_scroll(Origin { y: 5, x: 5 }, Size { lines: 4, columns: 4 }, view), Origin::default());
+    assert_eq!(visible_scroll(Origin { y: 10, x: 0 }, view, Origin { y: 25, x: 3 }), Origin { y: 16, x: 0 });
+    assert_eq!(visible_scroll(Origin { y: 10, x: 10 }, view, Origin { y: 4, x: 2 }), Origin { y: 4, x: 2 });
+    assert_eq!(offset(3, -5), 0);
+    assert_eq!(offset(3, 5), 8);
+}
@@ -30,7 +30,7 @@ use ncursesw::{SCREEN, panels, mouse};
 use crate::{
     ColorType, ColorsType, ColorAttributeTypes, HasHandle, NCurseswWinError,
     ChtypeChar, WideChar, ComplexChar, Panel, InputMode, CursorType, KeyBinding,
-    Window, Size, Origin, Legacy
+    Window, Size, Origin, Legacy, Format, curscr, export::export_window
 };
 
 pub struct Screen {
@@ -218,6 +218,18 @@ impl Screen {
         Ok(ncursesw::erasechar_sp(self.handle)?)
     }
 
+    /// Export the contents of the screen as ANSI text, a HTML document or a SVG image.
+    ///
+    /// The screen is made the current screen while it's `curscr` is exported.
+    pub fn export(&self, format: Format) -> result!(String) {
+        let previous = self.set_term()?;
+        let export = export_window(&curscr(), format);
+
+        previous.set_term()?;
+
+        export
+    }
+
     pub fn flash(&self) -> result!(()) {
         Ok(ncursesw::flash_sp(self.handle)?)
     }
@@ -0,0 +1,333 @@
+/*
+    src/widgets/barchart.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::cmp;
+use ncursesw::AttributesColorPairSet;
+use crate::{
+    Origin, Size, NCurseswWinError, HasMvAddFunctions,
+    widgets::{
+        ColorScale,
+        funcs::{scaled_ratio, format_value, ratio_eighths, eighths_char, HORIZONTAL_EIGHTHS, VERTICAL_EIGHTHS}
+    },
+    funcs::{
+        complex_char, default_attributes_color_pair, mvadd_complex_chars,
+        mvadd_str_columns, str_width, truncate_str
+    }
+};
+
+/// The direction that the bars of a `BarChart` are drawn.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BarDirection {
+    /// Bars grow from the left to the right with the labels on the left.
+    Horizontal,
+    /// Bars grow from the bottom to the top with the labels underneath.
+    Vertical
+}
+
+/// A labeled bar of a `BarChart`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bar {
+    label: String,
+    value: f64,
+    style: Option<AttributesColorPairSet>
+}
+
+impl Bar {
+    /// Create a new bar with a label and a value.
+    pub fn new<S: Into<String>>(label: S, value: f64) -> Self {
+        Self { label: label.into(), value, style: None }
+    }
+
+    /// The label of the bar.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// The value of the bar.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Set the value of the bar.
+    pub fn set_value(&mut self, value: f64) {
+        self.value = value;
+    }
+
+    /// Set the attributes and color pair of the bar, this overrides the chart's style and color scale.
+    pub fn set_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.style = style;
+    }
+}
+
+/// A horizontal or vertical bar chart with labels and optional values.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BarChart {
+    bars:        Vec<Bar>,
+    direction:   BarDirection,
+    max:         Option<f64>,
+    bar_width:   u16,
+    bar_gap:     u16,
+    show_values: bool,
+    precision:   usize,
+    style:       Option<AttributesColorPairSet>,
+    label_style: Option<AttributesColorPairSet>,
+    value_style: Option<AttributesColorPairSet>,
+    color_scale: Option<ColorScale>
+}
+
+impl BarChart {
+    /// Create a new bar chart of bars in a direction.
+    pub fn new(bars: Vec<Bar>, direction: BarDirection) -> Self {
+        Self {
+            bars,
+            direction,
+            max:         None,
+            bar_width:   1,
+            bar_gap:     1,
+            show_values: true,
+            precision:   2,
+            style:       None,
+            label_style: None,
+            value_style: None,
+            color_scale: None
+        }
+    }
+
+    /// The bars of the chart.
+    pub fn bars(&self) -> &[Bar] {
+        &self.bars
+    }
+
+    /// The bars of the chart as mutable.
+    pub fn bars_mut(&mut self) -> &mut Vec<Bar> {
+        &mut self.bars
+    }
+
+    /// The direction the bars are drawn.
+    pub fn direction(&self) -> BarDirection {
+        self.direction
+    }
+
+    /// Set the direction the bars are drawn.
+    pub fn set_direction(&mut self, direction: BarDirection) {
+        self.direction = direction;
+    }
+
+    /// Set the value drawn as a full bar, if `None` the maximum value of the bars is used.
+    pub fn set_max(&mut self, max: Option<f64>) {
+        self.max = max;
+    }
+
+    /// Set the thickness of each bar (lines for horizontal bars, columns for vertical bars)
+    /// and the gap between each bar.
+    pub fn set_bar_width(&mut self, bar_width: u16, bar_gap: u16) {
+        assert!(bar_width > 0, "BarChart::set_bar_width() : bar_width == 0");
+
+        self.bar_width = bar_width;
+        self.bar_gap = bar_gap;
+    }
+
+    /// Show the value of each bar and the maximum decimal places of the values.
+    pub fn set_show_values(&mut self, show_values: bool, precision: usize) {
+        self.show_values = show_values;
+        self.precision = precision;
+    }
+
+    /// Set the attributes and color pair of the bars.
+    pub fn set_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.style = style;
+    }
+
+    /// Set the attributes and color pair of the labels.
+    pub fn set_label_style(&mut self, label_style: Option<AttributesColorPairSet>) {
+        self.label_style = label_style;
+    }
+
+    /// Set the attributes and color pair of the values.
+    pub fn set_value_style(&mut self, value_style: Option<AttributesColorPairSet>) {
+        self.value_style = value_style;
+    }
+
+    /// Set the color scale, when set each bar is colored by it's value.
+    pub fn set_color_scale(&mut self, color_scale: Option<ColorScale>) {
+        self.color_scale = color_scale;
+    }
+
+    /// Render the bar chart into the region of a window at origin of size.
+    pub fn render<W: HasMvAddFunctions>(&self, window: &W, origin: Origin, size: Size) -> result!(()) {
+        assert_origin!("render", window.size()?, origin);
+
+        if size.lines == 0 || size.columns == 0 {
+            return Ok(());
+        }
+
+        match self.direction {
+            BarDirection::Horizontal => self.render_horizontal(window, origin, size),
+            BarDirection::Vertical   => self.render_vertical(window, origin, size)
+        }
+    }
+
+    fn render_horizontal<W: HasMvAddFunctions>(&self, window: &W, origin: Origin, size: Size) -> result!(()) {
+        let max = self.max_value();
+        let label_width = cmp::min(self.bars.iter().map(|bar| str_width(&bar.label)).max().unwrap_or(0), size.columns / 3);
+        let bar_origin_x = if label_width > 0 { label_width + 1 } else { 0 };
+        let bar_columns = size.columns.saturating_sub(bar_origin_x);
+        let label_style = self.label_style.unwrap_or_else(default_attributes_color_pair);
+        let value_style = self.value_style.unwrap_or_else(default_attributes_color_pair);
+
+        for (i, bar) in self.bars.iter().enumerate() {
+            let bar_line = usize::from(self.bar_width + self.bar_gap) * i;
+
+            if bar_line >= usize::from(size.lines) {
+                break;
+            }
+
+            let ratio = scaled_ratio(bar.value, max);
+            let style = self.bar_style(bar, ratio);
+            let eighths = ratio_eighths(ratio, bar_columns);
+            let value = format_value(bar.value, self.precision);
+            let bar_end = eighths.div_ceil(8);
+
+            for thickness in 0..cmp::min(usize::from(self.bar_width), usize::from(size.lines) - bar_line) {
+                let line_origin = Origin { y: origin.y + (bar_line + thickness) as u16, x: origin.x };
+
+                if label_width > 0 {
+                    let label = if thickness == 0 { truncate_str(&bar.label, label_width, true) } else { String::new() };
+
+                    mvadd_str_columns(window, line_origin, &label, bar_origin_x, label_style)?;
+                }
+
+                let mut chars = Vec::with_capacity(usize::from(bar_columns));
+
+                for column in 0..usize::from(bar_columns) {
+                    let ch = eighths_char(&HORIZONTAL_EIGHTHS, eighths, column);
+
+                    chars.push(complex_char(ch, if column * 8 < eighths { style } else { label_style })?);
+                }
+
+                mvadd_complex_chars(window, Origin { y: line_origin.y, x: line_origin.x + bar_origin_x }, &chars)?;
+
+                // the value is drawn after the end of the bar on the bars first line if it fits.
+                if self.show_values && thickness == 0 && bar_end + 1 + usize::from(str_width(&value)) <= usize::from(bar_columns) {
+                    let value_origin = Origin { y: line_origin.y, x: line_origin.x + bar_origin_x + bar_end as u16 + 1 };
+
+                    mvadd_str_columns(window, value_origin, &value, str_width(&value), value_style)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn render_vertical<W: HasMvAddFunctions>(&self, window: &W, origin: Origin, size: Size) -> result!(()) {
+        let max = self.max_value();
+        let label_style = self.label_style.unwrap_or_else(default_attributes_color_pair);
+        let value_style = self.value_style.unwrap_or_else(default_attributes_color_pair);
+
+        // the bottom line is used for the labels and a line above each bar for it's value.
+        let bar_lines = size.lines.saturating_sub(if self.show_values { 2 } else { 1 });
+        let label_origin = Origin { y: origin.y + size.lines - 1, x: origin.x };
+
+        for line in 0..size.lines {
+            mvadd_str_columns(window, Origin { y: origin.y + line, x: origin.x }, "", size.columns, label_style)?;
+        }
+
+        for (i, bar) in self.bars.iter().enumerate() {
+            let bar_column = usize::from(self.bar_width + self.bar_gap) * i;
+
+            if bar_column >= usize::from(size.columns) {
+                break;
+            }
+
+            let bar_width = cmp::min(usize::from(self.bar_width), usize::from(size.columns) - bar_column) as u16;
+            let bar_x = origin.x + bar_column as u16;
+            let ratio = scaled_ratio(bar.value, max);
+            let style = self.bar_style(bar, ratio);
+            let eighths = ratio_eighths(ratio, bar_lines);
+
+            for line in 0..bar_lines {
+                let cell = usize::from(bar_lines - line - 1);
+
+                if cell * 8 < eighths {
+                    let chars = vec!(complex_char(eighths_char(&VERTICAL_EIGHTHS, eighths, cell), style)?; usize::from(bar_width));
+
+                    mvadd_complex_chars(window, Origin { y: origin.y + size.lines - bar_lines - 1 + line, x: bar_x }, &chars)?;
+                }
+            }
+
+            if self.show_values && size.lines > 2 {
+                let value = truncate_str(&format_value(bar.value, self.precision), bar_width, false);
+                let bar_height = eighths.div_ceil(8) as u16;
+                let value_y = origin.y + size.lines - 2 - bar_height;
+
+                mvadd_str_columns(window, Origin { y: value_y, x: bar_x }, &value, bar_width, value_style)?;
+            }
+
+            mvadd_str_columns(window, Origin { y: label_origin.y, x: bar_x }, &truncate_str(&bar.label, bar_width, true), bar_width, label_style)?;
+        }
+
+        Ok(())
+    }
+
+    // the value of a full bar.
+    fn max_value(&self) -> f64 {
+        self.max.unwrap_or_else(|| self.bars.iter().map(|bar| bar.value).fold(0.0, f64::max))
+    }
+
+    // the attributes and color pair of a bar.
+    fn bar_style(&self, bar: &Bar, ratio: f64) -> AttributesColorPairSet {
+        bar.style
+            .or_else(|| self.color_scale.as_ref().map(|color_scale| color_scale.color(ratio)))
+            .or(self.style)
+            .unwrap_or_else(default_attributes_color_pair)
+    }
+}
+
+#[test]
+fn bar_chart_test() {
+    use crate::funcs::reverse_attributes_color_pair;
+
+    // all zero bars are empty.
+    let mut bar_chart = BarChart::new(vec!(Bar::new("a", 0.0), Bar::new("b", 0.0)), BarDirection::Horizontal);
+
+    assert_eq!(bar_chart.max_value(), 0.0);
+    assert_eq!(scaled_ratio(bar_chart.bars()[0].value(), bar_chart.max_value()), 0.0);
+
+    // equal bars are full.
+    bar_chart.bars_mut().iter_mut().for_each(|bar| bar.set_value(3.0));
+    assert_eq!(bar_chart.max_value(), 3.0);
+    assert_eq!(scaled_ratio(bar_chart.bars()[1].value(), bar_chart.max_value()), 1.0);
+
+    bar_chart.set_max(Some(12.0));
+    assert_eq!(ratio_eighths(scaled_ratio(3.0, bar_chart.max_value()), 5), 10);
+
+    // a bar's style overrides the chart's style.
+    let reverse = reverse_attributes_color_pair(default_attributes_color_pair());
+
+    assert_eq!(bar_chart.bar_style(&bar_chart.bars()[0], 1.0), default_attributes_color_pair());
+    bar_chart.set_color_scale(Some(ColorScale::new(vec!(reverse))));
+    assert_eq!(bar_chart.bar_style(&bar_chart.bars()[0], 1.0), reverse);
+    bar_chart.bars_mut()[0].set_style(Some(default_attributes_color_pair()));
+    assert_eq!(bar_chart.bar_style(&bar_chart.bars()[0], 1.0), default_attributes_color_pair());
+
+    assert!(std::panic::catch_unwind(|| BarChart::new(vec!(), BarDirection::Vertical).set_bar_width(0, 1)).is_err());
+}
@@ -0,0 +1,115 @@
+/*
+    src/widgets/colorscale.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use ncursesw::AttributesColorPairSet;
+use crate::{NCurseswWinError, normal, extend, funcs::default_attributes_color_pair};
+
+/// A scale of attributes and color pairs used to color a value by it's magnitude.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ColorScale {
+    colors: Vec<AttributesColorPairSet>
+}
+
+impl ColorScale {
+    /// Create a new color scale from the lowest to the highest attributes and color pair.
+    pub fn new(colors: Vec<AttributesColorPairSet>) -> Self {
+        Self { colors }
+    }
+
+    /// Create a new color scale of foreground colors, from lowest to highest, on a
+    /// background color allocating the color pairs using `normal::alloc_pair()`.
+    pub fn normal(foregrounds: &[normal::Color], background: normal::Color) -> result!(Self) {
+        let mut colors = Vec::with_capacity(foregrounds.len());
+
+        for &foreground in foregrounds {
+            let color_pair = normal::alloc_pair(normal::Colors::new(foreground, background))?;
+
+            colors.push(AttributesColorPairSet::Normal(normal::AttributesColorPair::new(normal::Attributes::default(), color_pair)));
+        }
+
+        Ok(Self::new(colors))
+    }
+
+    /// Create a new color scale of foreground colors, from lowest to highest, on a
+    /// background color allocating the color pairs using `extend::alloc_pair()`.
+    pub fn extend(foregrounds: &[extend::Color], background: extend::Color) -> result!(Self) {
+        let mut colors = Vec::with_capacity(foregrounds.len());
+
+        for &foreground in foregrounds {
+            let color_pair = extend::alloc_pair(extend::Colors::new(foreground, background))?;
+
+            colors.push(AttributesColorPairSet::Extend(extend::AttributesColorPair::new(extend::Attributes::default(), color_pair)));
+        }
+
+        Ok(Self::new(colors))
+    }
+
+    /// The attributes and color pairs of the scale from lowest to highest.
+    pub fn colors(&self) -> &[AttributesColorPairSet] {
+        &self.colors
+    }
+
+    /// The number of attributes and color pairs in the scale.
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    /// Does the scale have no attributes and color pairs.
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    /// The attributes and color pair of a ratio between 0.0 (lowest) and 1.0 (highest),
+    /// an empty scale returns the default attributes and color pair.
+    pub fn color(&self, ratio: f64) -> AttributesColorPairSet {
+        if self.colors.is_empty() {
+            return default_attributes_color_pair();
+        }
+
+        let ratio = if ratio.is_nan() { 0.0 } else { ratio.clamp(0.0, 1.0) };
+        let index = (ratio * self.colors.len() as f64) as usize;
+
+        self.colors[index.min(self.colors.len() - 1)]
+    }
+}
+
+#[test]
+fn color_scale_test() {
+    use crate::funcs::reverse_attributes_color_pair;
+
+    let low = default_attributes_color_pair();
+    let high = reverse_attributes_color_pair(low);
+
+    assert!(ColorScale::default().is_empty());
+    assert_eq!(ColorScale::default().color(0.5), low);
+
+    let color_scale = ColorScale::new(vec!(low, high));
+
+    assert_eq!(color_scale.len(), 2);
+    assert_eq!(color_scale.color(0.0), low);
+    assert_eq!(color_scale.color(0.49), low);
+    assert_eq!(color_scale.color(0.5), high);
+    assert_eq!(color_scale.color(1.0), high);
+    assert_eq!(color_scale.color(2.0), high);
+    assert_eq!(color_scale.color(-1.0), low);
+    assert_eq!(color_scale.color(f64::NAN), low);
+}
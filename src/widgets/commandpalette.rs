@@ -0,0 +1,444 @@
+/*
+    src/widgets/commandpalette.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{cmp, fmt};
+use ncursesw::AttributesColorPairSet;
+use crate::{
+    Origin, Size, NCurseswWinError, Window, CharacterResult, KeyBinding, CursorType, BoxDrawingType, cursor_set,
+    widgets::{Dialog, LineEdit, selection::Selection, funcs::{mvadd_cells, push_str_cells}},
+    funcs::{bold_attributes_color_pair, char_width, mvadd_str_columns, reverse_attributes_color_pair, str_width},
+    Cell, Justification
+};
+
+const ESCAPE: char = '\u{1b}';
+const DEFAULT_COLUMNS: u16 = 60;
+const DEFAULT_LINES: u16 = 10;
+const PROMPT: &str = "> ";
+
+// the scores of a matched character, at the start of a word and following the previous match.
+const MATCH_SCORE: i64 = 10;
+const WORD_START_SCORE: i64 = 15;
+const CONSECUTIVE_SCORE: i64 = 10;
+const MAX_GAP_PENALTY: usize = 10;
+
+/// The action of a command, actions needing the application's state can
+/// capture it in an `Rc<RefCell<_>>` or `Arc<Mutex<_>>`.
+pub type CommandAction = Box<dyn FnMut()>;
+
+/// A command with a name, description, key hint and action.
+pub struct Command {
+    name:        String,
+    description: String,
+    key_hint:    Option<String>,
+    action:      CommandAction
+}
+
+impl Command {
+    /// The name of the command.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The description of the command.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// The key binding that also runs the command, shown as a hint.
+    pub fn key_hint(&self) -> Option<&str> {
+        self.key_hint.as_deref()
+    }
+
+    /// Run the command's action.
+    pub fn run(&mut self) {
+        (self.action)();
+    }
+}
+
+impl fmt::Debug for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Command {{ name: {:?}, description: {:?}, key_hint: {:?} }}", self.name, self.description, self.key_hint)
+    }
+}
+
+/// A registry of the commands shown by a `CommandPalette`.
+#[derive(Debug, Default)]
+pub struct CommandRegistry {
+    commands: Vec<Command>
+}
+
+impl CommandRegistry {
+    /// Create a new empty command registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a command, replacing a command of the same name.
+    pub fn register(&mut self, name: &str, description: &str, key_hint: Option<&str>, action: CommandAction) {
+        let command = Command { name: name.to_string(), description: description.to_string(), key_hint: key_hint.map(str::to_string), action };
+
+        match self.commands.iter_mut().find(|command| command.name == name) {
+            Some(existing) => *existing = command,
+            None           => self.commands.push(command)
+        }
+    }
+
+    /// Remove a command, returns `false` if it wasn't registered.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let len = self.commands.len();
+
+        self.commands.retain(|command| command.name != name);
+
+        self.commands.len() != len
+    }
+
+    /// The commands in the order they were registered.
+    pub fn commands(&self) -> &[Command] {
+        &self.commands
+    }
+
+    /// The command of a name.
+    pub fn get(&self, name: &str) -> Option<&Command> {
+        self.commands.iter().find(|command| command.name == name)
+    }
+
+    /// Run the command of a name, returns `false` if it isn't registered.
+    pub fn run(&mut self, name: &str) -> bool {
+        match self.commands.iter_mut().find(|command| command.name == name) {
+            Some(command) => {
+                command.run();
+
+                true
+            },
+            None          => false
+        }
+    }
+
+    /// The number of commands.
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Are there no commands.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+}
+
+// the input line, ranked matches and selected match of a displayed palette.
+struct PaletteState {
+    line_edit: LineEdit,
+    matches:   Vec<(usize, Vec<usize>)>, // the index of the command and the indices of it's matched characters.
+    selection: Selection
+}
+
+/// A command palette that finds a command by fuzzy matching it's name.
+///
+/// The palette is displayed as a modal `Dialog` on a new top `Panel` with an
+/// input line above a list of the registered commands ranked by how well
+/// their names match the input, the typed characters must appear in the
+/// name in order but not necessarily together and matches at the start of
+/// words and of consecutive characters rank highest. The matched characters
+/// are highlighted and each command is shown with it's description and key
+/// hint. The up and down arrows and page up and down select a command, enter
+/// runs it and escape cancels the palette.
+///
+/// ```text
+/// let mut registry = CommandRegistry::new();
+///
+/// registry.register("File: Save", "Save the current file", Some("Ctrl-S"), Box::new(|| save()));
+///
+/// CommandPalette::new("Commands").run(&mut registry)?;
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CommandPalette {
+    dialog:      Dialog,
+    columns:     u16,
+    lines:       u16,
+    match_style: Option<AttributesColorPairSet>
+}
+
+impl CommandPalette {
+    /// Create a new command palette with a title.
+    pub fn new(title: &str) -> Self {
+        Self { dialog: Dialog::new(title, ""), columns: DEFAULT_COLUMNS, lines: DEFAULT_LINES, match_style: None }
+    }
+
+    /// Centre the palette on a parent window instead of the screen.
+    pub fn set_parent(&mut self, parent: &Window) -> result!(()) {
+        self.dialog.set_parent(parent)
+    }
+
+    /// Set the box drawing type of the palette's border.
+    pub fn set_border(&mut self, border: BoxDrawingType) {
+        self.dialog.set_border(border);
+    }
+
+    /// Set the attributes and color pair of the palette.
+    pub fn set_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.dialog.set_style(style);
+    }
+
+    /// Set the attributes and color pair of matched characters, by default
+    /// the palette's style in bold.
+    pub fn set_match_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.match_style = style;
+    }
+
+    /// Set the width of the palette's input line and list of commands.
+    pub fn set_columns(&mut self, columns: u16) {
+        self.columns = cmp::max(columns, str_width(PROMPT) + 1);
+    }
+
+    /// Set the number of commands listed at once.
+    pub fn set_lines(&mut self, lines: u16) {
+        self.lines = cmp::max(lines, 1);
+    }
+
+    /// Display the palette and run the chosen command, returns the name of
+    /// the command or `None` if the palette was cancelled.
+    pub fn run(&self, registry: &mut CommandRegistry) -> result!(Option<String>) {
+        Ok(match self.choose(registry)? {
+            Some(index) => {
+                let command = &mut registry.commands[index];
+
+                command.run();
+
+                Some(command.name.clone())
+            },
+            None        => None
+        })
+    }
+
+    /// Display the palette and choose a command without running it, returns
+    /// the index of the command in the registry or `None` if the palette was
+    /// cancelled.
+    pub fn choose(&self, registry: &CommandRegistry) -> result!(Option<usize>) {
+        let lines = self.lines;
+        let mut state = PaletteState { line_edit: LineEdit::new(), matches: rank(registry, ""), selection: Selection::new(registry.len()) };
+
+        let cursor = cursor_set(CursorType::Visible)?;
+        let result = self.dialog.run(&mut state, lines + 1, self.columns,
+            |state, window, origin, size, style| {
+                let page = usize::from(size.lines.saturating_sub(1));
+                let prompt_columns = str_width(PROMPT);
+
+                state.selection.ensure_visible(page);
+
+                if state.matches.is_empty() {
+                    mvadd_str_columns(window, Origin { y: origin.y + 1, x: origin.x }, "No matching commands", size.columns, style)?;
+                }
+
+                for (y, index) in (1..size.lines).zip(state.selection.visible(page)) {
+                    let row_style = if state.selection.selected() == Some(index) { reverse_attributes_color_pair(style) } else { style };
+                    let match_style = self.match_style.unwrap_or_else(|| bold_attributes_color_pair(row_style));
+                    let (command, positions) = &state.matches[index];
+                    let cells = command_cells(registry, &registry.commands[*command], positions, size.columns, row_style, match_style);
+
+                    mvadd_cells(window, Origin { y: origin.y + y, x: origin.x }, &cells, 0, size.columns)?;
+                }
+
+                // the input line is drawn last so it has the cursor.
+                mvadd_str_columns(window, origin, PROMPT, prompt_columns, style)?;
+                state.line_edit.set_style(Some(style));
+                state.line_edit.render(window, Origin { x: origin.x + prompt_columns, ..origin }, Size { lines: 1, columns: size.columns.saturating_sub(prompt_columns) })
+            },
+            |state, key| {
+                let selected = |state: &PaletteState| state.selection.selected().and_then(|index| state.matches.get(index)).map(|(command, _)| Some(*command));
+
+                Ok(match key {
+                    CharacterResult::Character(wch) if wch.as_char()? == ESCAPE                 => Some(None),
+                    CharacterResult::Character(wch) if matches!(wch.as_char()?, '\n' | '\r')    => selected(state),
+                    CharacterResult::Key(KeyBinding::Enter)                                     => selected(state),
+                    CharacterResult::Key(key_binding @ (KeyBinding::UpArrow | KeyBinding::DownArrow | KeyBinding::PreviousPage | KeyBinding::NextPage)) => {
+                        state.selection.handle_key(key_binding, usize::from(lines));
+
+                        None
+                    },
+                    key                                                                         => {
+                        let pattern = state.line_edit.text();
+
+                        state.line_edit.handle_key(key)?;
+
+                        if state.line_edit.text() != pattern {
+                            state.matches = rank(registry, &state.line_edit.text());
+                            state.selection = Selection::new(state.matches.len());
+                        }
+
+                        None
+                    }
+                })
+            }
+        );
+
+        cursor_set(cursor)?;
+
+        result
+    }
+}
+
+// a row of the palette of a command's name with it's matched characters highlighted, it's description
+// and it's key hint justified to the right.
+fn command_cells(
+    registry:    &CommandRegistry,
+    command:     &Command,
+    positions:   &[usize],
+    columns:     u16,
+    style:       AttributesColorPairSet,
+    match_style: AttributesColorPairSet) -> Vec<Cell>
+{
+    let hint_columns = registry.commands.iter().filter_map(|command| command.key_hint.as_deref()).map(str_width).max().unwrap_or(0);
+    let rest_columns = columns.saturating_sub(if hint_columns > 0 { hint_columns + 2 } else { 0 });
+    let name_columns = cmp::min(registry.commands.iter().map(|command| str_width(&command.name)).max().unwrap_or(0), rest_columns);
+    let mut cells = Vec::with_capacity(usize::from(columns));
+    let mut width = 0;
+
+    for (index, ch) in command.name.chars().enumerate() {
+        let ch_width = char_width(ch);
+
+        if width + ch_width > name_columns {
+            break;
+        }
+
+        let ch_style = if positions.contains(&index) { match_style } else { style };
+
+        match ch_width {
+            0 => { },
+            1 => cells.push(Cell::new(ch, ch_style)),
+            _ => {
+                cells.push(Cell::new(ch, ch_style));
+                cells.push(Cell::continuation(ch_style));
+            }
+        }
+
+        width += ch_width;
+    }
+
+    push_str_cells(&mut cells, "", name_columns - width, Justification::Left, style);
+
+    if rest_columns > name_columns + 2 {
+        push_str_cells(&mut cells, "  ", 2, Justification::Left, style);
+        push_str_cells(&mut cells, &command.description, rest_columns - name_columns - 2, Justification::Left, style);
+    } else {
+        push_str_cells(&mut cells, "", rest_columns - name_columns, Justification::Left, style);
+    }
+
+    if hint_columns > 0 {
+        push_str_cells(&mut cells, command.key_hint.as_deref().unwrap_or(""), columns - rest_columns, Justification::Right, style);
+    }
+
+    cells
+}
+
+// the commands matching a pattern from the best match with the indices of their matched characters.
+fn rank(registry: &CommandRegistry, pattern: &str) -> Vec<(usize, Vec<usize>)> {
+    let mut matches: Vec<(usize, i64, Vec<usize>)> = registry.commands.iter()
+        .enumerate()
+        .filter_map(|(index, command)| fuzzy_match(pattern, &command.name).map(|(score, positions)| (index, score, positions)))
+        .collect();
+
+    // the sort is stable so commands that score the same stay in the order they were registered.
+    matches.sort_by_key(|&(_, score, _)| cmp::Reverse(score));
+
+    matches.into_iter().map(|(index, _, positions)| (index, positions)).collect()
+}
+
+// the score and the indices of the matched characters of a pattern as a subsequence of a text
+// matching either case, each occurrence of the pattern's first character is tried as the start.
+fn fuzzy_match(pattern: &str, text: &str) -> Option<(i64, Vec<usize>)> {
+    let lower = |ch: char| ch.to_lowercase().next().unwrap_or(ch);
+    let pattern: Vec<char> = pattern.chars().map(lower).collect();
+    let text: Vec<char> = text.chars().collect();
+    let lower_text: Vec<char> = text.iter().map(|&ch| lower(ch)).collect();
+
+    let first = match pattern.first() {
+        Some(&first) => first,
+        None         => return Some((0, vec!()))
+    };
+
+    let mut best: Option<(i64, Vec<usize>)> = None;
+
+    for start in (0..lower_text.len()).filter(|&index| lower_text[index] == first) {
+        let mut positions = vec!(start);
+
+        for &ch in &pattern[1..] {
+            let next = positions[positions.len() - 1] + 1;
+
+            match (next..lower_text.len()).find(|&index| lower_text[index] == ch) {
+                Some(index) => positions.push(index),
+                None        => break
+            }
+        }
+
+        // if the rest of the pattern isn't found after this start it won't be after a later one.
+        if positions.len() < pattern.len() {
+            break;
+        }
+
+        let score = fuzzy_score(&text, &positions);
+
+        if best.as_ref().is_none_or(|(best_score, _)| score > *best_score) {
+            best = Some((score, positions));
+        }
+    }
+
+    best
+}
+
+fn fuzzy_score(text: &[char], positions: &[usize]) -> i64 {
+    let is_word_start = |index: usize| index == 0 || !text[index - 1].is_alphanumeric() || (text[index - 1].is_lowercase() && text[index].is_uppercase());
+    let mut score = -(cmp::min(positions.first().copied().unwrap_or(0), MAX_GAP_PENALTY) as i64);
+
+    for (n, &index) in positions.iter().enumerate() {
+        score += MATCH_SCORE;
+
+        if is_word_start(index) {
+            score += WORD_START_SCORE;
+        }
+
+        if n > 0 {
+            match index - positions[n - 1] - 1 {
+                0   => score += CONSECUTIVE_SCORE,
+                gap => score -= cmp::min(gap, MAX_GAP_PENALTY) as i64
+            }
+        }
+    }
+
+    score
+}
+
+#[test]
+fn command_palette_test() {
+    let mut registry = CommandRegistry::new();
+
+    registry.register("Debug: Open Console", "", None, Box::new(|| { }));
+    registry.register("Git: Commit", "", Some("Ctrl-K"), Box::new(|| { }));
+    registry.register("Format Document", "", None, Box::new(|| { }));
+
+    assert_eq!(fuzzy_match("gc", "Git: Commit"), Some((46, vec!(0, 5))));
+    assert_eq!(fuzzy_match("xyz", "Git: Commit"), None);
+    assert_eq!(fuzzy_match("", "Git: Commit"), Some((0, vec!())));
+    assert_eq!(rank(&registry, "oc").iter().map(|(index, _)| *index).collect::<Vec<usize>>(), vec!(0, 2));
+    assert_eq!(rank(&registry, "").len(), 3);
+    assert!(registry.run("Git: Commit"));
+    assert!(!registry.run("Git: Push"));
+}
@@ -0,0 +1,348 @@
+/*
+    src/widgets/dialog.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{cmp, fmt::{Display, Formatter}};
+use ncursesw::AttributesColorPairSet;
+use crate::{
+    Origin, Size, NCurseswWinError, Window, NCurseswWindow, BaseCanvas, HasYXAxis,
+    HasGetFunctions, HasGraphicFunctions, Panel, CharacterResult, WideChar, KeyBinding,
+    CursorType, BoxDrawingType, BoxDrawingTypeDetail, BoxDrawingGraphic, LINES, COLS, cursor_set,
+    doupdate, update_panels,
+    widgets::{LineEdit, LineEditEvent, selection::Selection, funcs::wrap_str},
+    funcs::{complex_box_char, default_attributes_color_pair, mvadd_str_columns, reverse_attributes_color_pair, str_width}
+};
+
+const ESCAPE: char = '\u{1b}';
+const MIN_COLUMNS: u16 = 20;
+const PROMPT_COLUMNS: u16 = 40;
+const MAX_CHOICE_LINES: u16 = 10;
+const BUTTON_SPACING: u16 = 2;
+
+/// The result of a confirm dialog.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ConfirmResult {
+    /// The yes button was chosen.
+    Yes,
+    /// The no button was chosen.
+    No,
+    /// The cancel button was chosen or escape was pressed.
+    Cancel
+}
+
+impl Display for ConfirmResult {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            ConfirmResult::Yes    => "Yes",
+            ConfirmResult::No     => "No",
+            ConfirmResult::Cancel => "Cancel"
+        })
+    }
+}
+
+/// A modal dialog with a title and message.
+///
+/// The dialog is displayed in a bordered window on a new top `Panel`,
+/// centred on the screen or a parent window, and reads keys until it's
+/// dismissed when the panel is removed and the panels underneath are
+/// restored. Escape cancels all dialogs.
+///
+/// ```text
+/// let mut dialog = Dialog::new("Delete", "Delete the selected files?");
+///
+/// if dialog.confirm()? == ConfirmResult::Yes {
+///     ...
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Dialog {
+    title:  String,
+    text:   String,
+    parent: Option<(Origin, Size)>,
+    border: BoxDrawingType,
+    style:  Option<AttributesColorPairSet>
+}
+
+impl Dialog {
+    /// Create a new dialog with a title and message.
+    pub fn new(title: &str, text: &str) -> Self {
+        Self {
+            title:  title.to_string(),
+            text:   text.to_string(),
+            parent: None,
+            border: BoxDrawingType::Light(BoxDrawingTypeDetail::Normal),
+            style:  None
+        }
+    }
+
+    /// Centre the dialog on a parent window instead of the screen.
+    pub fn set_parent(&mut self, parent: &Window) -> result!(()) {
+        self.parent = Some((parent.origin()?, parent.size()?));
+
+        Ok(())
+    }
+
+    /// Set the box drawing type of the dialog's border.
+    pub fn set_border(&mut self, border: BoxDrawingType) {
+        self.border = border;
+    }
+
+    /// Set the attributes and color pair of the dialog.
+    pub fn set_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.style = style;
+    }
+
+    /// Display the message until enter, space or escape is pressed.
+    pub fn message_box(&self) -> result!(()) {
+        let buttons = ["OK"];
+
+        self.run(&mut (), 1, button_row_width(&buttons),
+            |_, window, origin, size, style| draw_buttons(window, origin, size, &buttons, 0, style),
+            |_, key| Ok(match key {
+                CharacterResult::Key(KeyBinding::Enter)                            => Some(()),
+                CharacterResult::Character(wch) if is_char(wch, &[' ', '\n', ESCAPE]) => Some(()),
+                _                                                                  => None
+            })
+        )
+    }
+
+    /// Ask a question with yes, no and cancel buttons. The left and right
+    /// arrows or tab move between the buttons and enter presses the focused
+    /// button, `y` and `n` press yes and no.
+    pub fn confirm(&self) -> result!(ConfirmResult) {
+        let buttons = ["Yes", "No", "Cancel"];
+        let results = [ConfirmResult::Yes, ConfirmResult::No, ConfirmResult::Cancel];
+
+        self.run(&mut 0, 1, button_row_width(&buttons),
+            |focused, window, origin, size, style| draw_buttons(window, origin, size, &buttons, *focused, style),
+            |focused, key| Ok(match key {
+                CharacterResult::Key(KeyBinding::LeftArrow) |
+                CharacterResult::Key(KeyBinding::BackTab)                   => {
+                    *focused = (*focused + buttons.len() - 1) % buttons.len();
+
+                    None
+                },
+                CharacterResult::Key(KeyBinding::RightArrow)                => {
+                    *focused = (*focused + 1) % buttons.len();
+
+                    None
+                },
+                CharacterResult::Key(KeyBinding::Enter)                     => Some(results[*focused]),
+                CharacterResult::Character(wch) if is_char(wch, &['\t'])    => {
+                    *focused = (*focused + 1) % buttons.len();
+
+                    None
+                },
+                CharacterResult::Character(wch) if is_char(wch, &['\n', ' ']) => Some(results[*focused]),
+                CharacterResult::Character(wch) if is_char(wch, &['y', 'Y']) => Some(ConfirmResult::Yes),
+                CharacterResult::Character(wch) if is_char(wch, &['n', 'N']) => Some(ConfirmResult::No),
+                CharacterResult::Character(wch) if is_char(wch, &[ESCAPE])  => Some(ConfirmResult::Cancel),
+                _                                                           => None
+            })
+        )
+    }
+
+    /// Prompt for a line of text starting with a default, returns `None` if
+    /// the prompt was cancelled.
+    pub fn prompt(&self, default: &str) -> result!(Option<String>) {
+        let mut line_edit = LineEdit::new();
+
+        line_edit.set_text(default);
+
+        let cursor = cursor_set(CursorType::Visible)?;
+        let result = self.run(&mut line_edit, 1, PROMPT_COLUMNS,
+            |line_edit, window, origin, size, style| {
+                line_edit.set_style(Some(reverse_attributes_color_pair(style)));
+                line_edit.render(window, origin, size)
+            },
+            |line_edit, key| Ok(match key {
+                CharacterResult::Character(wch) if is_char(wch, &[ESCAPE]) => Some(None),
+                key                                                        => match line_edit.handle_key(key)? {
+                    LineEditEvent::Submitted(text) => Some(Some(text)),
+                    _                              => None
+                }
+            })
+        );
+
+        cursor_set(cursor)?;
+
+        result
+    }
+
+    /// Choose one of a list of items, returns the index of the chosen item
+    /// or `None` if the choice was cancelled.
+    pub fn choice(&self, items: &[&str]) -> result!(Option<usize>) {
+        let lines = u16::try_from(items.len()).unwrap_or(u16::MAX).clamp(1, MAX_CHOICE_LINES);
+        let columns = items.iter().map(|item| str_width(item)).max().unwrap_or(0);
+        let mut selection = Selection::new(items.len());
+
+        self.run(&mut selection, lines, columns,
+            |selection, window, origin, size, style| {
+                let page = usize::from(size.lines);
+
+                selection.ensure_visible(page);
+
+                for (y, index) in (0..size.lines).zip(selection.top()..) {
+                    let item_style = if selection.selected() == Some(index) { reverse_attributes_color_pair(style) } else { style };
+
+                    mvadd_str_columns(window, Origin { y: origin.y + y, x: origin.x }, items.get(index).unwrap_or(&""), size.columns, item_style)?;
+                }
+
+                Ok(())
+            },
+            |selection, key| Ok(match key {
+                CharacterResult::Key(KeyBinding::Enter)                       => selection.selected().map(Some),
+                CharacterResult::Key(key_binding)                             => {
+                    selection.handle_key(key_binding, usize::from(lines));
+
+                    None
+                },
+                CharacterResult::Character(wch) if is_char(wch, &['\n', ' ']) => selection.selected().map(Some),
+                CharacterResult::Character(wch) if is_char(wch, &[ESCAPE])    => Some(None),
+                _                                                             => None
+            })
+        )
+    }
+
+    // the origin and size of the screen or parent window the dialog is centred on.
+    pub(in crate::widgets) fn area(&self) -> result!((Origin, Size)) {
+        match self.parent {
+            Some(parent) => Ok(parent),
+            None         => Ok((Origin::default(), Size { lines: LINES()?, columns: COLS()? }))
+        }
+    }
+
+    // display the dialog with a body of a number of lines and columns below the message, the body is
+    // drawn and keys are handled until the handler returns a result.
+    pub(in crate::widgets) fn run<S, T, D, H>(&self, state: &mut S, body_lines: u16, body_columns: u16, draw: D, mut handle: H) -> result!(T)
+        where D: Fn(&mut S, &Window, Origin, Size, AttributesColorPairSet) -> result!(()),
+              H: FnMut(&mut S, CharacterResult<WideChar>) -> result!(Option<T>)
+    {
+        let (area_origin, area_size) = self.area()?;
+
+        let max_columns = cmp::max(area_size.columns, 4);
+        let text_columns = [str_width(&self.title) + 2, body_columns, MIN_COLUMNS]
+            .into_iter()
+            .chain(self.text.split('\n').map(str_width))
+            .max()
+            .map_or(MIN_COLUMNS, |columns| cmp::min(columns, max_columns - 4));
+        let text = if self.text.is_empty() { vec!() } else { wrap_str(&self.text, text_columns) };
+        let text_lines = u16::try_from(text.len())?;
+        let gap = u16::from(text_lines > 0 && body_lines > 0);
+        let size = Size {
+            lines:   cmp::min(text_lines + gap + body_lines + 2, cmp::max(area_size.lines, 3)),
+            columns: text_columns + 4
+        };
+        let origin = Origin {
+            y: area_origin.y + area_size.lines.saturating_sub(size.lines) / 2,
+            x: area_origin.x + area_size.columns.saturating_sub(size.columns) / 2
+        };
+        let body_origin = Origin { y: 1 + text_lines + gap, x: 2 };
+        let body_size = Size { lines: size.lines.saturating_sub(body_origin.y + 1), columns: text_columns };
+
+        let window = Window::new(size, origin)?;
+        let panel = Panel::new(&window)?;
+        let style = self.style.unwrap_or_else(default_attributes_color_pair);
+
+        window.keypad(true)?;
+
+        // the panel is always removed from the screen, even when the loop fails.
+        let result = (|| -> result!(T) {
+            loop {
+                window.erase()?;
+
+                for y in 0..size.lines {
+                    mvadd_str_columns(&window, Origin { y, x: 0 }, "", size.columns, style)?;
+                }
+
+                window.border_set(
+                    complex_box_char(self.border, BoxDrawingGraphic::VerticalLine, style)?,
+                    complex_box_char(self.border, BoxDrawingGraphic::VerticalLine, style)?,
+                    complex_box_char(self.border, BoxDrawingGraphic::HorizontalLine, style)?,
+                    complex_box_char(self.border, BoxDrawingGraphic::HorizontalLine, style)?,
+                    complex_box_char(self.border, BoxDrawingGraphic::UpperLeftCorner, style)?,
+                    complex_box_char(self.border, BoxDrawingGraphic::UpperRightCorner, style)?,
+                    complex_box_char(self.border, BoxDrawingGraphic::LowerLeftCorner, style)?,
+                    complex_box_char(self.border, BoxDrawingGraphic::LowerRightCorner, style)?
+                )?;
+
+                if !self.title.is_empty() {
+                    mvadd_str_columns(&window, Origin { y: 0, x: 2 }, &format!(" {} ", self.title), cmp::min(str_width(&self.title) + 2, text_columns), style)?;
+                }
+
+                for (y, line) in (1..size.lines.saturating_sub(1)).zip(text.iter()) {
+                    mvadd_str_columns(&window, Origin { y, x: 2 }, line, text_columns, style)?;
+                }
+
+                draw(state, &window, body_origin, body_size, style)?;
+
+                update_panels();
+                doupdate()?;
+
+                if let Some(result) = handle(state, window.get_wch()?)? {
+                    return Ok(result);
+                }
+            }
+        })();
+
+        drop(panel);
+        drop(window);
+
+        update_panels();
+        let refreshed = doupdate();
+        let result = result?;
+
+        refreshed?;
+
+        Ok(result)
+    }
+}
+
+fn is_char(wch: WideChar, chars: &[char]) -> bool {
+    chars.iter().any(|&ch| wch == WideChar::new(ch))
+}
+
+fn button_row_width(buttons: &[&str]) -> u16 {
+    buttons.iter().map(|button| str_width(button) + 4).sum::<u16>() + BUTTON_SPACING * u16::try_from(buttons.len().saturating_sub(1)).unwrap_or(0)
+}
+
+// draw a centred row of buttons with the focused button reversed.
+fn draw_buttons(
+    window:  &Window,
+    origin:  Origin,
+    size:    Size,
+    buttons: &[&str],
+    focused: usize,
+    style:   AttributesColorPairSet
+) -> result!(()) {
+    let mut x = origin.x + size.columns.saturating_sub(button_row_width(buttons)) / 2;
+
+    for (index, button) in buttons.iter().enumerate() {
+        let label = format!("[ {} ]", button);
+        let width = str_width(&label);
+        let button_style = if index == focused { reverse_attributes_color_pair(style) } else { style };
+
+        mvadd_str_columns(window, Origin { y: origin.y, x }, &label, width, button_style)?;
+        x += width + BUTTON_SPACING;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,514 @@
+/*
+    src/widgets/filepicker.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{
+    cmp, fs, mem, cell::RefCell, collections::BTreeSet, rc::Rc,
+    path::{Path, PathBuf}, time::{SystemTime, UNIX_EPOCH}
+};
+use ncursesw::AttributesColorPairSet;
+use crate::{
+    Origin, Size, NCurseswWinError, Window, CharacterResult, WideChar, KeyBinding, CursorType,
+    BoxDrawingType, BoxDrawingTypeDetail, Justification, cursor_set,
+    widgets::{Column, ColumnWidth, Dialog, LineEdit, Table},
+    funcs::{mvadd_str_columns, reverse_attributes_color_pair}
+};
+
+const ESCAPE: char = '\u{1b}';
+const TOGGLE_HIDDEN: char = '\u{14}';
+const MIN_LINES: u16 = 10;
+const MIN_COLUMNS: u16 = 40;
+const SIZE_COLUMNS: u16 = 8;
+const MODIFIED_COLUMNS: u16 = 16;
+const MARK: &str = "\u{2713}";
+
+/// What a `FilePicker` picks.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FilePickerMode {
+    /// Pick files, directories are browsed into.
+    Files,
+    /// Pick directories, only directories are listed.
+    Directories
+}
+
+/// A modal dialog browsing the local filesystem to pick files or directories.
+///
+/// Entries are listed with their size and modification time, directories
+/// first. The up and down arrows and page up/down move through the entries
+/// copying the selected entry into the path input, which is edited as a
+/// `LineEdit` with tab completion of paths. Enter on a directory browses
+/// into it and on a file picks it, a path containing `*` or `?` sets the
+/// glob filter (several globs are separated by `,`). When multi-select is
+/// enabled the insert key marks entries and enter picks the marked entries.
+/// `Ctrl-t` toggles hidden files and escape cancels. In directories mode
+/// enter with an empty path or `.` picks the current directory.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FilePicker {
+    dialog:       Dialog,
+    directory:    PathBuf,
+    mode:         FilePickerMode,
+    glob:         Option<String>,
+    show_hidden:  bool,
+    multi_select: bool
+}
+
+impl FilePicker {
+    /// Create a new file picker with a title starting in a directory.
+    pub fn new(title: &str, directory: &Path) -> Self {
+        Self {
+            dialog:       Dialog::new(title, ""),
+            directory:    directory.to_path_buf(),
+            mode:         FilePickerMode::Files,
+            glob:         None,
+            show_hidden:  false,
+            multi_select: false
+        }
+    }
+
+    /// Centre the file picker on a parent window instead of the screen.
+    pub fn set_parent(&mut self, parent: &Window) -> result!(()) {
+        self.dialog.set_parent(parent)
+    }
+
+    /// Set the box drawing type of the file picker's border.
+    pub fn set_border(&mut self, border: BoxDrawingType) {
+        self.dialog.set_border(border);
+    }
+
+    /// Set the attributes and color pair of the file picker.
+    pub fn set_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.dialog.set_style(style);
+    }
+
+    /// Set whether files or directories are picked.
+    pub fn set_mode(&mut self, mode: FilePickerMode) {
+        self.mode = mode;
+    }
+
+    /// Set the glob filter of the file names such as `"*.rs,*.toml"`.
+    pub fn set_glob(&mut self, glob: Option<&str>) {
+        self.glob = glob.map(|glob| glob.to_string());
+    }
+
+    /// Show hidden files, those starting with a `.`.
+    pub fn set_show_hidden(&mut self, show_hidden: bool) {
+        self.show_hidden = show_hidden;
+    }
+
+    /// Allow several entries to be picked.
+    pub fn set_multi_select(&mut self, multi_select: bool) {
+        self.multi_select = multi_select;
+    }
+
+    /// Display the file picker returning the picked paths or `None` if it
+    /// was cancelled.
+    pub fn pick(&self) -> result!(Option<Vec<PathBuf>>) {
+        let (_, area_size) = self.dialog.area()?;
+        let body_lines = cmp::max(area_size.lines * 3 / 4, MIN_LINES);
+        let body_columns = cmp::max(area_size.columns * 3 / 4, MIN_COLUMNS);
+        let mut browser = Browser::new(self);
+
+        browser.load();
+
+        let cursor = cursor_set(CursorType::Visible)?;
+        let result = self.dialog.run(&mut browser, body_lines, body_columns,
+            |browser, window, origin, size, style| browser.draw(window, origin, size, style),
+            |browser, key| browser.handle_key(key)
+        );
+
+        cursor_set(cursor)?;
+
+        result
+    }
+}
+
+// a directory entry.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct Entry {
+    name:     String,
+    path:     PathBuf,
+    is_dir:   bool,
+    size:     Option<u64>,
+    modified: Option<SystemTime>
+}
+
+// the state of a file picker while it's displayed.
+struct Browser {
+    directory:    Rc<RefCell<PathBuf>>,
+    mode:         FilePickerMode,
+    glob:         Option<String>,
+    show_hidden:  bool,
+    multi_select: bool,
+    entries:      Vec<Entry>,
+    marked:       BTreeSet<PathBuf>,
+    table:        Table,
+    line_edit:    LineEdit,
+    status:       Option<String>
+}
+
+impl Browser {
+    fn new(file_picker: &FilePicker) -> Self {
+        let directory = Rc::new(RefCell::new(fs::canonicalize(&file_picker.directory).unwrap_or_else(|_| file_picker.directory.clone())));
+        let completer_directory = Rc::clone(&directory);
+        let mut line_edit = LineEdit::new();
+        let mut size_column = Column::new("Size", ColumnWidth::Fixed(SIZE_COLUMNS));
+
+        size_column.set_justification(Justification::Right);
+
+        let mut table = Table::new(vec!(
+            Column::new("", ColumnWidth::Fixed(1)),
+            Column::new("Name", ColumnWidth::Fit),
+            size_column,
+            Column::new("Modified", ColumnWidth::Fixed(MODIFIED_COLUMNS))
+        ));
+
+        table.set_grid(Some(BoxDrawingType::Light(BoxDrawingTypeDetail::Normal)));
+        line_edit.set_completer(Some(Box::new(move |before: &str| complete_path(&completer_directory.borrow(), before))));
+
+        Self {
+            directory,
+            mode:         file_picker.mode,
+            glob:         file_picker.glob.clone(),
+            show_hidden:  file_picker.show_hidden,
+            multi_select: file_picker.multi_select,
+            entries:      vec!(),
+            marked:       BTreeSet::new(),
+            table,
+            line_edit,
+            status:       None
+        }
+    }
+
+    // read the entries of the directory.
+    fn load(&mut self) {
+        let directory = self.directory.borrow().clone();
+        let globs: Option<Vec<Vec<char>>> = self.glob.as_ref().map(|glob| glob.split(',').map(|glob| glob.trim().chars().collect()).collect());
+
+        self.entries.clear();
+        self.status = None;
+
+        if let Some(parent) = directory.parent() {
+            self.entries.push(Entry { name: "..".to_string(), path: parent.to_path_buf(), is_dir: true, size: None, modified: None });
+        }
+
+        match fs::read_dir(&directory) {
+            Ok(read_dir) => {
+                for dir_entry in read_dir.flatten() {
+                    let name = dir_entry.file_name().to_string_lossy().to_string();
+                    let path = dir_entry.path();
+                    let metadata = fs::metadata(&path).ok();
+                    let is_dir = metadata.as_ref().is_some_and(|metadata| metadata.is_dir());
+
+                    if (!self.show_hidden && name.starts_with('.')) || (!is_dir && self.mode == FilePickerMode::Directories) {
+                        continue;
+                    }
+
+                    let name_chars: Vec<char> = name.chars().collect();
+
+                    if !is_dir && !globs.as_ref().is_none_or(|globs| globs.iter().any(|glob| glob_match(glob, &name_chars))) {
+                        continue;
+                    }
+
+                    self.entries.push(Entry {
+                        name,
+                        path,
+                        is_dir,
+                        size:     metadata.as_ref().filter(|metadata| !metadata.is_dir()).map(|metadata| metadata.len()),
+                        modified: metadata.and_then(|metadata| metadata.modified().ok())
+                    });
+                }
+            },
+            Err(source)  => self.status = Some(source.to_string())
+        }
+
+        self.entries.sort_by(|lhs, rhs| {
+            (lhs.name != "..").cmp(&(rhs.name != ".."))
+                .then(rhs.is_dir.cmp(&lhs.is_dir))
+                .then_with(|| lhs.name.to_lowercase().cmp(&rhs.name.to_lowercase()))
+        });
+
+        self.update_rows();
+        self.table.set_selected(Some(0));
+        self.line_edit.set_text("");
+    }
+
+    fn update_rows(&mut self) {
+        let rows = self.entries.iter().map(|entry| vec!(
+            if self.marked.contains(&entry.path) { MARK.to_string() } else { String::new() },
+            if entry.is_dir { format!("{}/", entry.name) } else { entry.name.clone() },
+            entry.size.map_or_else(|| if entry.is_dir { "<DIR>".to_string() } else { String::new() }, format_size),
+            entry.modified.map_or_else(String::new, format_modified)
+        )).collect();
+        let selected = self.table.selected();
+
+        self.table.set_rows(rows);
+        self.table.set_selected(selected);
+    }
+
+    fn draw(&mut self, window: &Window, origin: Origin, size: Size, style: AttributesColorPairSet) -> result!(()) {
+        let name_columns = size.columns.saturating_sub(1 + SIZE_COLUMNS + MODIFIED_COLUMNS + 3);
+        let status = self.status.clone().unwrap_or_else(|| {
+            format!("{}  hidden files {}{}",
+                self.glob.as_deref().unwrap_or("*"),
+                if self.show_hidden { "shown" } else { "hidden" },
+                if self.marked.is_empty() { String::new() } else { format!("  {} marked", self.marked.len()) }
+            )
+        });
+
+        self.table.set_column_width(1, Some(name_columns));
+        self.table.set_style(Some(style));
+
+        mvadd_str_columns(window, origin, &self.directory.borrow().to_string_lossy(), size.columns, style)?;
+        mvadd_str_columns(window, Origin { y: origin.y + 1, x: origin.x }, "> ", 2, style)?;
+        mvadd_str_columns(window, Origin { y: origin.y + size.lines - 1, x: origin.x }, &status, size.columns, style)?;
+
+        self.table.render(window, Origin { y: origin.y + 2, x: origin.x }, Size { lines: size.lines.saturating_sub(3), columns: size.columns })?;
+
+        self.line_edit.set_style(Some(reverse_attributes_color_pair(style)));
+        self.line_edit.render(window, Origin { y: origin.y + 1, x: origin.x + 2 }, Size { lines: 1, columns: size.columns.saturating_sub(2) })
+    }
+
+    fn handle_key(&mut self, key: CharacterResult<WideChar>) -> result!(Option<Option<Vec<PathBuf>>>) {
+        match key {
+            CharacterResult::Key(KeyBinding::UpArrow)      |
+            CharacterResult::Key(KeyBinding::DownArrow)    |
+            CharacterResult::Key(KeyBinding::PreviousPage) |
+            CharacterResult::Key(KeyBinding::NextPage)        => {
+                self.table.handle_key(key);
+                self.copy_selected();
+            },
+            CharacterResult::Key(KeyBinding::InsertCharacter) => self.toggle_mark(),
+            CharacterResult::Key(KeyBinding::Enter)           => return Ok(self.enter()),
+            CharacterResult::Character(wch)                   => match wch.as_char()? {
+                ESCAPE        => return Ok(Some(None)),
+                TOGGLE_HIDDEN => {
+                    self.show_hidden = !self.show_hidden;
+                    self.load();
+                },
+                '\n' | '\r'   => return Ok(self.enter()),
+                _             => { self.line_edit.handle_key(key)?; }
+            },
+            _                                                 => { self.line_edit.handle_key(key)?; }
+        }
+
+        Ok(None)
+    }
+
+    // copy the selected entry into the path input.
+    fn copy_selected(&mut self) {
+        if let Some(entry) = self.table.selected().and_then(|selected| self.entries.get(selected)) {
+            let text = if entry.is_dir { format!("{}/", entry.name) } else { entry.name.clone() };
+
+            self.line_edit.set_text(&text);
+        }
+    }
+
+    fn toggle_mark(&mut self) {
+        if let Some(entry) = self.table.selected().and_then(|selected| self.entries.get(selected)) {
+            let markable = entry.name != ".." && (entry.is_dir == (self.mode == FilePickerMode::Directories));
+
+            if self.multi_select && markable && !self.marked.remove(&entry.path) {
+                self.marked.insert(entry.path.clone());
+            }
+
+            self.update_rows();
+            self.table.handle_key(CharacterResult::Key(KeyBinding::DownArrow));
+            self.copy_selected();
+        }
+    }
+
+    // act on the path input, returns the picked paths when done.
+    fn enter(&mut self) -> Option<Option<Vec<PathBuf>>> {
+        let text = self.line_edit.text();
+        let directory = self.directory.borrow().clone();
+
+        if text.contains('*') || text.contains('?') {
+            self.glob = Some(text);
+            self.load();
+
+            return None;
+        }
+
+        // marking an entry copies the next entry into the path input so the marks are picked first.
+        if !self.marked.is_empty() {
+            return Some(Some(mem::take(&mut self.marked).into_iter().collect()));
+        }
+
+        if self.mode == FilePickerMode::Directories && (text.is_empty() || text == ".") {
+            return Some(Some(vec!(directory)));
+        }
+
+        let path = directory.join(&text);
+
+        if path.is_dir() {
+            match fs::canonicalize(&path) {
+                Ok(path)    => {
+                    *self.directory.borrow_mut() = path;
+                    self.load();
+                },
+                Err(source) => self.status = Some(source.to_string())
+            }
+
+            None
+        } else if text.is_empty() {
+            None
+        } else if self.mode == FilePickerMode::Files {
+            Some(Some(vec!(path)))
+        } else {
+            self.status = Some(format!("{} is not a directory", path.display()));
+
+            None
+        }
+    }
+}
+
+// complete a path relative to a directory.
+fn complete_path(directory: &Path, before: &str) -> Vec<String> {
+    let (parent, prefix) = match before.rfind('/') {
+        Some(index) => (&before[..=index], &before[index + 1..]),
+        None        => ("", before)
+    };
+
+    let read_dir = match fs::read_dir(directory.join(parent)) {
+        Ok(read_dir) => read_dir,
+        Err(_)       => return vec!()
+    };
+
+    let mut candidates: Vec<String> = read_dir
+        .flatten()
+        .filter_map(|dir_entry| {
+            let name = dir_entry.file_name().to_string_lossy().to_string();
+
+            if name.starts_with(prefix) && (prefix.starts_with('.') || !name.starts_with('.')) {
+                let is_dir = fs::metadata(dir_entry.path()).is_ok_and(|metadata| metadata.is_dir());
+
+                Some(format!("{}{}{}", parent, name, if is_dir { "/" } else { "" }))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    candidates.sort();
+
+    candidates
+}
+
+// match a name against a glob of `*` and `?` wildcards, on a mismatch the last `*` is
+// retried consuming one more character of the name so the match is never exponential.
+fn glob_match(glob: &[char], name: &[char]) -> bool {
+    let (mut glob_index, mut name_index) = (0, 0);
+    // the glob index after the last `*` and the name index it's matched up to.
+    let mut star = None;
+
+    while name_index < name.len() {
+        match glob.get(glob_index) {
+            Some('*') => {
+                star = Some((glob_index + 1, name_index));
+                glob_index += 1;
+            },
+            Some(&ch) if ch == '?' || ch == name[name_index] => {
+                glob_index += 1;
+                name_index += 1;
+            },
+            _ => match star {
+                Some((star_glob_index, star_name_index)) => {
+                    star = Some((star_glob_index, star_name_index + 1));
+                    glob_index = star_glob_index;
+                    name_index = star_name_index + 1;
+                },
+                None => return false
+            }
+        }
+    }
+
+    glob[glob_index..].iter().all(|&ch| ch == '*')
+}
+
+fn format_size(size: u64) -> String {
+    const UNITS: [&str; 6] = ["", "K", "M", "G", "T", "P"];
+
+    let mut value = size as f64;
+    let mut unit = 0;
+
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        size.to_string()
+    } else {
+        format!("{:.1}{}", value, UNITS[unit])
+    }
+}
+
+// format a time as the local date and time.
+fn format_modified(modified: SystemTime) -> String {
+    let seconds = match modified.duration_since(UNIX_EPOCH).ok().and_then(|duration| libc::time_t::try_from(duration.as_secs()).ok()) {
+        Some(seconds) => seconds,
+        None          => return String::new()
+    };
+
+    let mut tm: libc::tm = unsafe { mem::zeroed() };
+
+    if unsafe { libc::localtime_r(&seconds, &mut tm) }.is_null() {
+        String::new()
+    } else {
+        format!("{:04}-{:02}-{:02} {:02}:{:02}", tm.tm_year + 1900, tm.tm_mon + 1, tm.tm_mday, tm.tm_hour, tm.tm_min)
+    }
+}
+
+#[test]
+fn file_picker_test() {
+    let glob = |glob: &str, name: &str| glob_match(&glob.chars().collect::<Vec<char>>(), &name.chars().collect::<Vec<char>>());
+
+    assert!(glob("*.rs", "lib.rs"));
+    assert!(glob("?ib.*", "lib.rs"));
+    assert!(!glob("*.rs", "Cargo.toml"));
+    assert!(glob("*", ""));
+    assert!(glob("a*b*c", "aXbYbZc"));
+    assert!(!glob("a*b?c", "aXbYbc"));
+    assert!(!glob("?", ""));
+    assert!(glob("**", "lib.rs"));
+    assert!(!glob(&"*a".repeat(32), &"a".repeat(31)));
+    assert_eq!(format_size(512), "512");
+    assert_eq!(format_size(1536), "1.5K");
+    assert!(complete_path(Path::new(env!("CARGO_MANIFEST_DIR")), "src/widgets/file").contains(&"src/widgets/filepicker.rs".to_string()));
+
+    // enter picks the marked directory rather than browsing into the next directory.
+    let mut file_picker = FilePicker::new("Pick", &Path::new(env!("CARGO_MANIFEST_DIR")).join("src"));
+
+    file_picker.set_mode(FilePickerMode::Directories);
+    file_picker.set_multi_select(true);
+
+    let mut browser = Browser::new(&file_picker);
+
+    browser.load();
+    browser.table.set_selected(Some(1));
+
+    let marked = browser.entries[1].path.clone();
+
+    assert!(browser.entries[1].is_dir && browser.entries[2].is_dir);
+    assert_eq!(browser.handle_key(CharacterResult::Key(KeyBinding::InsertCharacter)).unwrap(), None);
+    assert_eq!(browser.line_edit.text(), format!("{}/", browser.entries[2].name));
+    assert_eq!(browser.handle_key(CharacterResult::Key(KeyBinding::Enter)).unwrap(), Some(Some(vec!(marked))));
+}
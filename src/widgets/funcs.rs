@@ -0,0 +1,256 @@
+/*
+    src/widgets/funcs.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{cmp, mem};
+use ncursesw::AttributesColorPairSet;
+use crate::{
+    Origin, Size, Cell, ComplexString, NCurseswWinError, HasMvAddFunctions, Justification,
+    MouseEvents, MouseButtonState, MouseButton, MouseButtonEvent,
+    funcs::{char_width, complex_char, str_width, truncate_str}
+};
+
+// the lower block elements from empty to a full block in eighths.
+pub(in crate::widgets) const VERTICAL_EIGHTHS: [char; 9] = [
+    ' ', '\u{2581}', '\u{2582}', '\u{2583}', '\u{2584}', '\u{2585}', '\u{2586}', '\u{2587}', '\u{2588}'
+];
+
+// the left block elements from empty to a full block in eighths.
+pub(in crate::widgets) const HORIZONTAL_EIGHTHS: [char; 9] = [
+    ' ', '\u{258f}', '\u{258e}', '\u{258d}', '\u{258c}', '\u{258b}', '\u{258a}', '\u{2589}', '\u{2588}'
+];
+
+// the number of eighths of a number of cells filled by a ratio, rounded to the nearest eighth.
+pub(in crate::widgets) fn ratio_eighths(ratio: f64, cells: u16) -> usize {
+    (ratio * f64::from(cells) * 8.0).round() as usize
+}
+
+// the block element of the cell at an index into a bar filled to a number of eighths.
+pub(in crate::widgets) fn eighths_char(block_elements: &[char; 9], eighths: usize, cell: usize) -> char {
+    block_elements[eighths.saturating_sub(cell * 8).min(8)]
+}
+
+// the ratio of a value to a maximum clamped to 0.0 to 1.0.
+pub(in crate::widgets) fn scaled_ratio(value: f64, max: f64) -> f64 {
+    if max <= 0.0 || value.is_nan() {
+        0.0
+    } else {
+        (value / max).clamp(0.0, 1.0)
+    }
+}
+
+// format a value for a label using the minimum number of decimal places up to `precision`.
+pub(in crate::widgets) fn format_value(value: f64, precision: usize) -> String {
+    let formatted = format!("{:.*}", precision, value);
+
+    if formatted.contains('.') {
+        formatted.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        formatted
+    }
+}
+
+// the number of rows scrolled by a mouse wheel event.
+pub(in crate::widgets) const WHEEL_ROWS: isize = 3;
+
+// the rows to scroll for a mouse wheel event (button 4 up, button 5 down).
+pub(in crate::widgets) fn wheel_rows(events: MouseEvents) -> Option<isize> {
+    if events.is_button_state(MouseButtonState::new(MouseButton::Four, MouseButtonEvent::Pressed)) {
+        Some(-WHEEL_ROWS)
+    } else if events.is_button_state(MouseButtonState::new(MouseButton::Five, MouseButtonEvent::Pressed)) {
+        Some(WHEEL_ROWS)
+    } else {
+        None
+    }
+}
+
+// is the mouse event a button 1 press or click.
+pub(in crate::widgets) fn is_button_1(events: MouseEvents) -> bool {
+    events.is_button_state(MouseButtonState::new(MouseButton::One, MouseButtonEvent::Pressed)) ||
+    events.is_button_state(MouseButtonState::new(MouseButton::One, MouseButtonEvent::Clicked))
+}
+
+// the origin relative to a region of the screen if the origin is within the region.
+pub(in crate::widgets) fn region_origin(origin: Origin, region_origin: Origin, region_size: Size) -> Option<Origin> {
+    if origin.y >= region_origin.y && origin.x >= region_origin.x &&
+       origin.y < region_origin.y + region_size.lines && origin.x < region_origin.x + region_size.columns
+    {
+        Some(Origin { y: origin.y - region_origin.y, x: origin.x - region_origin.x })
+    } else {
+        None
+    }
+}
+
+// append a string to a line of cells truncated with an ellipsis and justified within a number of columns.
+pub(in crate::widgets) fn push_str_cells(
+    cells:           &mut Vec<Cell>,
+    str:             &str,
+    columns:         u16,
+    justification:   Justification,
+    attrs_colorpair: AttributesColorPairSet
+) {
+    let str = truncate_str(str, columns, true);
+    let padding = columns.saturating_sub(str_width(&str));
+    let before = match justification {
+        Justification::Left     => 0,
+        Justification::Centered => padding / 2,
+        Justification::Right    => padding
+    };
+
+    cells.extend((0..before).map(|_| Cell::new(' ', attrs_colorpair)));
+
+    for ch in str.chars() {
+        match char_width(ch) {
+            0 => { },
+            1 => cells.push(Cell::new(ch, attrs_colorpair)),
+            _ => {
+                cells.push(Cell::new(ch, attrs_colorpair));
+                cells.push(Cell::continuation(attrs_colorpair));
+            }
+        }
+    }
+
+    cells.extend((before..padding).map(|_| Cell::new(' ', attrs_colorpair)));
+}
+
+// write a number of columns of a line of cells starting at an offset into the line at origin, double
+// width characters split by the offset or the edge are replaced with a space and the line is clipped
+// to the window's edge.
+pub(in crate::widgets) fn mvadd_cells<W: HasMvAddFunctions>(
+    window:  &W,
+    origin:  Origin,
+    cells:   &[Cell],
+    offset:  usize,
+    columns: u16
+) -> result!(()) {
+    let window_size = window.size()?;
+
+    if origin.y >= window_size.lines || origin.x >= window_size.columns {
+        return Ok(());
+    }
+
+    let wchstr = cells_complex_string(cells, offset, cmp::min(columns, window_size.columns - origin.x))?;
+
+    if wchstr.is_empty() {
+        Ok(())
+    } else {
+        window.mvadd_wchnstr(origin, &wchstr, Some(u16::try_from(wchstr.len())?))
+    }
+}
+
+// the complex string of a number of columns of a line of cells starting at an offset into the line, double
+// width characters split by the offset or the end are replaced with a space.
+pub(in crate::widgets) fn cells_complex_string(cells: &[Cell], offset: usize, columns: u16) -> result!(ComplexString) {
+    let end = cmp::min(offset + usize::from(columns), cells.len());
+    let start = cmp::min(offset, end);
+    let mut wchstr = ComplexString::with_capacity(usize::from(columns));
+
+    for (index, cell) in cells[start..end].iter().enumerate().map(|(index, cell)| (start + index, cell)) {
+        if cell.is_continuation() {
+            if index == offset {
+                wchstr.push(&complex_char(' ', cell.attributes_color_pair())?);
+            }
+        } else if char_width(cell.character()) > 1 && index + 1 >= end {
+            wchstr.push(&complex_char(' ', cell.attributes_color_pair())?);
+        } else {
+            wchstr.push(&complex_char(cell.character(), cell.attributes_color_pair())?);
+        }
+    }
+
+    Ok(wchstr)
+}
+
+// word wrap a string into lines of a number of columns, words longer than a line are broken.
+pub(in crate::widgets) fn wrap_str(str: &str, columns: u16) -> Vec<String> {
+    let columns = cmp::max(columns, 1);
+    let mut lines = vec!();
+
+    for paragraph in str.split('\n') {
+        let mut line = String::new();
+        let mut width = 0;
+
+        for word in paragraph.split(' ') {
+            let word_width = str_width(word);
+
+            if width > 0 && width + 1 + word_width > columns {
+                lines.push(mem::take(&mut line));
+                width = 0;
+            }
+
+            if width > 0 {
+                line.push(' ');
+                width += 1;
+            }
+
+            for ch in word.chars() {
+                let ch_width = char_width(ch);
+
+                if width + ch_width > columns && width > 0 {
+                    lines.push(mem::take(&mut line));
+                    width = 0;
+                }
+
+                line.push(ch);
+                width += ch_width;
+            }
+        }
+
+        lines.push(line);
+    }
+
+    lines
+}
+
+#[test]
+fn widgets_funcs_test() {
+    assert_eq!(scaled_ratio(5.0, 10.0), 0.5);
+    assert_eq!(scaled_ratio(20.0, 10.0), 1.0);
+    assert_eq!(scaled_ratio(-5.0, 10.0), 0.0);
+    assert_eq!(scaled_ratio(5.0, 0.0), 0.0);
+    assert_eq!(scaled_ratio(0.0, 0.0), 0.0);
+    assert_eq!(scaled_ratio(5.0, -1.0), 0.0);
+    assert_eq!(scaled_ratio(f64::NAN, 10.0), 0.0);
+
+    assert_eq!(ratio_eighths(0.5, 3), 12);
+    assert_eq!(ratio_eighths(1.0 / 16.0, 1), 1);
+    assert_eq!(ratio_eighths(1.0 / 17.0, 1), 0);
+    assert_eq!(ratio_eighths(1.0, 0), 0);
+    assert_eq!(eighths_char(&HORIZONTAL_EIGHTHS, 12, 0), '\u{2588}');
+    assert_eq!(eighths_char(&HORIZONTAL_EIGHTHS, 12, 1), '\u{258c}');
+    assert_eq!(eighths_char(&HORIZONTAL_EIGHTHS, 12, 2), ' ');
+    assert_eq!(eighths_char(&VERTICAL_EIGHTHS, 1, 0), '\u{2581}');
+    assert_eq!(eighths_char(&VERTICAL_EIGHTHS, 0, 0), ' ');
+
+    assert_eq!(format_value(2.50, 2), "2.5");
+    assert_eq!(format_value(2.456, 2), "2.46");
+    assert_eq!(format_value(3.0, 2), "3");
+    assert_eq!(format_value(10.0, 2), "10");
+    assert_eq!(format_value(1234.0, 0), "1234");
+
+    assert_eq!(region_origin(Origin { y: 2, x: 2 }, Origin { y: 1, x: 1 }, Size { lines: 2, columns: 2 }), Some(Origin { y: 1, x: 1 }));
+    assert_eq!(region_origin(Origin { y: 2, x: 3 }, Origin { y: 1, x: 1 }, Size { lines: 2, columns: 2 }), None);
+    assert_eq!(region_origin(Origin { y: 0, x: 1 }, Origin { y: 1, x: 1 }, Size { lines: 2, columns: 2 }), None);
+
+    assert_eq!(wrap_str("hello world", 5), vec!("hello", "world"));
+    assert_eq!(wrap_str("abcdefg", 3), vec!("abc", "def", "g"));
+    assert_eq!(wrap_str("a\nb", 10), vec!("a", "b"));
+    assert_eq!(wrap_str("", 0), vec!(""));
+}
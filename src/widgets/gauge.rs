@@ -0,0 +1,144 @@
+/*
+    src/widgets/gauge.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use ncursesw::AttributesColorPairSet;
+use crate::{
+    Origin, Size, NCurseswWinError, HasMvAddFunctions,
+    widgets::{ColorScale, funcs::{ratio_eighths, eighths_char, HORIZONTAL_EIGHTHS}},
+    funcs::{
+        char_width, complex_char, default_attributes_color_pair, mvadd_complex_chars,
+        reverse_attributes_color_pair, str_width
+    }
+};
+
+/// A horizontal gauge showing a ratio as a filled bar with a centered label.
+///
+/// The bar is filled with the left block elements giving a resolution of
+/// eight steps per column, if no label is set the percentage is shown.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Gauge {
+    ratio:       f64,
+    label:       Option<String>,
+    style:       Option<AttributesColorPairSet>,
+    color_scale: Option<ColorScale>
+}
+
+impl Gauge {
+    /// Create a new gauge with a ratio between 0.0 and 1.0.
+    pub fn new(ratio: f64) -> Self {
+        Self { ratio: clamp_ratio(ratio), ..Self::default() }
+    }
+
+    /// The ratio of the gauge.
+    pub fn ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    /// Set the ratio of the gauge, this is clamped between 0.0 and 1.0.
+    pub fn set_ratio(&mut self, ratio: f64) {
+        self.ratio = clamp_ratio(ratio);
+    }
+
+    /// Set the label of the gauge, if `None` the percentage is used.
+    pub fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    /// Set the attributes and color pair of the gauge.
+    pub fn set_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.style = style;
+    }
+
+    /// Set the color scale, when set the gauge is colored by it's ratio.
+    pub fn set_color_scale(&mut self, color_scale: Option<ColorScale>) {
+        self.color_scale = color_scale;
+    }
+
+    /// Render the gauge into the region of a window at origin of size, the
+    /// label is drawn on the middle line of the region.
+    pub fn render<W: HasMvAddFunctions>(&self, window: &W, origin: Origin, size: Size) -> result!(()) {
+        assert_origin!("render", window.size()?, origin);
+
+        if size.lines == 0 || size.columns == 0 {
+            return Ok(());
+        }
+
+        let style = self.color_scale.as_ref().map_or_else(
+            || self.style.unwrap_or_else(default_attributes_color_pair),
+            |color_scale| color_scale.color(self.ratio)
+        );
+        let eighths = ratio_eighths(self.ratio, size.columns);
+        let label = self.label();
+        let label_column = usize::from(size.columns.saturating_sub(str_width(&label)) / 2);
+
+        for line in 0..size.lines {
+            let mut chars: Vec<(char, AttributesColorPairSet)> = (0..usize::from(size.columns))
+                .map(|column| (eighths_char(&HORIZONTAL_EIGHTHS, eighths, column), style))
+                .collect();
+
+            // the label is drawn reversed over the filled part of the gauge so it remains visible.
+            if line == size.lines / 2 {
+                let label_chars = label.chars().filter(|&ch| char_width(ch) == 1);
+
+                for (column, ch) in (label_column..chars.len()).zip(label_chars) {
+                    chars[column] = (ch, if column * 8 < eighths { reverse_attributes_color_pair(style) } else { style });
+                }
+            }
+
+            let chars = chars.into_iter().map(|(ch, style)| complex_char(ch, style)).collect::<Result<Vec<_>, _>>()?;
+
+            mvadd_complex_chars(window, Origin { y: origin.y + line, x: origin.x }, &chars)?;
+        }
+
+        Ok(())
+    }
+
+    // the label drawn on the gauge.
+    fn label(&self) -> String {
+        self.label.clone().unwrap_or_else(|| format!("{:.0}%", self.ratio * 100.0))
+    }
+}
+
+fn clamp_ratio(ratio: f64) -> f64 {
+    if ratio.is_nan() { 0.0 } else { ratio.clamp(0.0, 1.0) }
+}
+
+#[test]
+fn gauge_test() {
+    assert_eq!(Gauge::new(1.5).ratio(), 1.0);
+    assert_eq!(Gauge::new(-0.5).ratio(), 0.0);
+    assert_eq!(Gauge::new(f64::NAN).ratio(), 0.0);
+
+    let mut gauge = Gauge::new(0.25);
+
+    assert_eq!(gauge.label(), "25%");
+    assert_eq!(ratio_eighths(gauge.ratio(), 10), 20);
+    assert_eq!(eighths_char(&HORIZONTAL_EIGHTHS, 20, 1), '\u{2588}');
+    assert_eq!(eighths_char(&HORIZONTAL_EIGHTHS, 20, 2), '\u{258c}');
+    assert_eq!(eighths_char(&HORIZONTAL_EIGHTHS, 20, 3), ' ');
+    assert_eq!(ratio_eighths(gauge.ratio(), 0), 0);
+
+    gauge.set_ratio(1.0);
+    gauge.set_label(Some("done".to_string()));
+    assert_eq!(gauge.label(), "done");
+    assert_eq!(ratio_eighths(gauge.ratio(), 3), 24);
+}
@@ -0,0 +1,703 @@
+/*
+    src/widgets/hexview.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{cmp, fmt, collections::BTreeMap};
+use ncursesw::AttributesColorPairSet;
+use crate::{
+    Origin, Size, Cell, NCurseswWinError, Pad, IsPad, HasYXAxis, BaseCanvas, Mouse,
+    CharacterResult, WideChar, KeyBinding, Justification,
+    widgets::{
+        LineEdit, LineEditEvent, ScrollAxis, ScrollState, ScrollTarget,
+        funcs::{is_button_1, mvadd_cells, push_str_cells, region_origin, wheel_rows}
+    },
+    funcs::{bold_attributes_color_pair, default_attributes_color_pair, reverse_attributes_color_pair}
+};
+
+// the default number of bytes shown on a row.
+const DEFAULT_BYTES_PER_ROW: usize = 16;
+
+// the hex bytes are grouped with an extra space between groups of this many bytes.
+const GROUP_BYTES: usize = 8;
+
+/// The pane of a `HexView` the cursor is editing.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum HexPane {
+    /// The hex bytes, edited with hex digits.
+    Hex,
+    /// The ASCII characters, edited with printable ASCII characters.
+    Ascii
+}
+
+// a change to a byte kept in the undo and redo history.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct HexEdit {
+    offset: usize,
+    old:    u8,
+    new:    u8
+}
+
+/// A hex viewer and editor showing the offset, hex bytes and ASCII
+/// characters of a buffer.
+///
+/// Only the visible rows are rendered into a pad the size of the view so
+/// buffers of any size scroll smoothly. The cursor is shown in both the hex
+/// and ASCII panes, changed bytes are highlighted and can be undone and
+/// redone. The last line of the view is a status line showing the cursor's
+/// offset or the seek prompt. The keys are:
+///
+/// - arrow keys: move the cursor, `Tab`: switch between the hex and ASCII panes.
+/// - page down and page up: move a page, home and end: the start and end of the row.
+/// - hex digits in the hex pane and printable characters in the ASCII pane:
+///   change the byte under the cursor.
+/// - `Ctrl-z` and `Ctrl-y`: undo and redo a change.
+/// - `Ctrl-g`: seek to an offset typed in hex with a `0x` prefix or in
+///   decimal, `Ctrl-g` again cancels the prompt.
+pub struct HexView {
+    pad:            Pad,
+    data:           Vec<u8>,
+    original:       BTreeMap<usize, u8>,      // the original values of the changed bytes.
+    undo:           Vec<HexEdit>,
+    redo:           Vec<HexEdit>,
+    bytes_per_row:  usize,
+    cursor:         usize,
+    low_nibble:     bool,                     // the next hex digit changes the low nibble.
+    pane:           HexPane,
+    top:            usize,                    // the first visible row.
+    read_only:      bool,
+    prompt:         Option<LineEdit>,
+    message:        Option<String>,
+    style:          Option<AttributesColorPairSet>,
+    cursor_style:   Option<AttributesColorPairSet>,
+    modified_style: Option<AttributesColorPairSet>,
+    status_style:   Option<AttributesColorPairSet>
+}
+
+impl HexView {
+    /// Create a new hex view of a size including the status line showing a buffer.
+    pub fn new(size: Size, data: Vec<u8>) -> result!(Self) {
+        Ok(Self {
+            pad:            Pad::new(size)?,
+            data,
+            original:       BTreeMap::new(),
+            undo:           vec!(),
+            redo:           vec!(),
+            bytes_per_row:  DEFAULT_BYTES_PER_ROW,
+            cursor:         0,
+            low_nibble:     false,
+            pane:           HexPane::Hex,
+            top:            0,
+            read_only:      false,
+            prompt:         None,
+            message:        None,
+            style:          None,
+            cursor_style:   None,
+            modified_style: None,
+            status_style:   None
+        })
+    }
+
+    /// The size of the hex view including the status line.
+    pub fn size(&self) -> result!(Size) {
+        self.pad.size()
+    }
+
+    /// Resize the hex view.
+    pub fn resize(&mut self, size: Size) -> result!(()) {
+        self.pad.resize(size)?;
+        self.ensure_cursor_visible()
+    }
+
+    /// The buffer.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Take the buffer.
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+
+    /// Replace the buffer, clearing the changes and history.
+    pub fn set_data(&mut self, data: Vec<u8>) -> result!(()) {
+        self.data = data;
+        self.clear_modified();
+        self.undo.clear();
+        self.redo.clear();
+        self.seek(0)
+    }
+
+    /// The number of bytes shown on a row.
+    pub fn bytes_per_row(&self) -> usize {
+        self.bytes_per_row
+    }
+
+    /// Set the number of bytes shown on a row.
+    pub fn set_bytes_per_row(&mut self, bytes_per_row: usize) -> result!(()) {
+        self.bytes_per_row = cmp::max(bytes_per_row, 1);
+        self.top = 0;
+        self.ensure_cursor_visible()
+    }
+
+    /// The number of columns a row takes with a number of bytes per row.
+    pub fn row_columns(&self) -> u16 {
+        u16::try_from(RowLayout::new(self.offset_digits(), self.bytes_per_row).columns()).unwrap_or(u16::MAX)
+    }
+
+    /// Is editing disabled.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Set whether editing is disabled.
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    /// The offset of the byte under the cursor.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// The pane the cursor is editing.
+    pub fn pane(&self) -> HexPane {
+        self.pane
+    }
+
+    /// Set the pane the cursor is editing.
+    pub fn set_pane(&mut self, pane: HexPane) {
+        self.pane = pane;
+        self.low_nibble = false;
+    }
+
+    /// Move the cursor to an offset scrolling it into view, the offset is
+    /// clamped to the last byte.
+    pub fn seek(&mut self, offset: usize) -> result!(()) {
+        self.cursor = cmp::min(offset, self.data.len().saturating_sub(1));
+        self.low_nibble = false;
+        self.ensure_cursor_visible()
+    }
+
+    /// The first visible row.
+    pub fn top(&self) -> usize {
+        self.top
+    }
+
+    /// Scroll so a row is the first visible row.
+    pub fn scroll_to(&mut self, top: usize) -> result!(()) {
+        self.top = cmp::min(top, self.row_count().saturating_sub(self.page()?));
+
+        Ok(())
+    }
+
+    /// Change the byte at an offset recording the change so it can be undone,
+    /// returns `false` if the offset is past the end of the buffer.
+    pub fn set_byte(&mut self, offset: usize, value: u8) -> bool {
+        match self.data.get(offset) {
+            Some(&old) if old != value => {
+                self.apply(HexEdit { offset, old, new: value });
+                self.undo.push(HexEdit { offset, old, new: value });
+                self.redo.clear();
+
+                true
+            },
+            Some(_)                    => true,
+            None                       => false
+        }
+    }
+
+    /// Undo the last change moving the cursor to it, returns `false` if there
+    /// was nothing to undo.
+    pub fn undo(&mut self) -> result!(bool) {
+        match self.undo.pop() {
+            Some(edit) => {
+                self.apply(HexEdit { offset: edit.offset, old: edit.new, new: edit.old });
+                self.redo.push(edit);
+                self.seek(edit.offset)?;
+
+                Ok(true)
+            },
+            None       => Ok(false)
+        }
+    }
+
+    /// Redo the last undone change moving the cursor to it, returns `false`
+    /// if there was nothing to redo.
+    pub fn redo(&mut self) -> result!(bool) {
+        match self.redo.pop() {
+            Some(edit) => {
+                self.apply(edit);
+                self.undo.push(edit);
+                self.seek(edit.offset)?;
+
+                Ok(true)
+            },
+            None       => Ok(false)
+        }
+    }
+
+    /// Has the byte at an offset changed from it's original value.
+    pub fn is_modified(&self, offset: usize) -> bool {
+        self.original.contains_key(&offset)
+    }
+
+    /// The offsets and original values of the changed bytes.
+    pub fn modified(&self) -> impl Iterator<Item = (usize, u8)> + '_ {
+        self.original.iter().map(|(&offset, &value)| (offset, value))
+    }
+
+    /// Treat the current buffer as the original, for example once it's been
+    /// saved, the undo history is kept.
+    pub fn clear_modified(&mut self) {
+        self.original.clear();
+    }
+
+    /// Set the attributes and color pair of the text.
+    pub fn set_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.style = style;
+    }
+
+    /// Set the attributes and color pair of the cursor, by default the text style reversed.
+    pub fn set_cursor_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.cursor_style = style;
+    }
+
+    /// Set the attributes and color pair of changed bytes, by default the text style in bold.
+    pub fn set_modified_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.modified_style = style;
+    }
+
+    /// Set the attributes and color pair of the status line, by default the text style reversed.
+    pub fn set_status_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.status_style = style;
+    }
+
+    /// Handle a key returning `true` if the key was used by the hex view.
+    pub fn handle_key(&mut self, key: CharacterResult<WideChar>) -> result!(bool) {
+        if self.prompt.is_some() {
+            return self.handle_prompt_key(key);
+        }
+
+        self.message = None;
+
+        let row = self.bytes_per_row;
+        let page = self.page()? * row;
+
+        match key {
+            CharacterResult::Character(wch) => match wch.as_char()? {
+                '\t'   => self.set_pane(if self.pane == HexPane::Hex { HexPane::Ascii } else { HexPane::Hex }),
+                '\x1a' => { self.undo()?; },
+                '\x19' => { self.redo()?; },
+                '\x07' => self.prompt = Some(LineEdit::new()),
+                ch     => return self.edit(ch)
+            },
+            CharacterResult::Key(key_binding) => match key_binding {
+                KeyBinding::LeftArrow    => self.seek(self.cursor.saturating_sub(1))?,
+                KeyBinding::RightArrow   => self.seek(self.cursor.saturating_add(1))?,
+                KeyBinding::UpArrow      => if self.cursor >= row { self.seek(self.cursor - row)? },
+                KeyBinding::DownArrow    => if self.cursor + row < self.data.len() { self.seek(self.cursor + row)? },
+                KeyBinding::PreviousPage => self.seek(self.cursor.saturating_sub(page))?,
+                KeyBinding::NextPage     => self.seek(self.cursor.saturating_add(page))?,
+                KeyBinding::Home         => self.seek(self.cursor - self.cursor % row)?,
+                KeyBinding::End          => self.seek(self.cursor - self.cursor % row + row - 1)?,
+                _                        => return Ok(false)
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Handle a mouse event with the hex view displayed at the screen origin,
+    /// returns `true` if the event was used by the hex view.
+    pub fn handle_mouse(&mut self, mouse: &Mouse, screen_origin: Origin) -> result!(bool) {
+        let events = mouse.events();
+
+        let origin = match region_origin(mouse.origin()?.origin(), screen_origin, self.size()?) {
+            Some(origin) => origin,
+            None         => return Ok(false)
+        };
+
+        if let Some(rows) = wheel_rows(events) {
+            let top = if rows < 0 { self.top.saturating_sub(rows.unsigned_abs()) } else { self.top.saturating_add(rows as usize) };
+
+            self.scroll_to(top)?;
+
+            Ok(true)
+        } else if is_button_1(events) && usize::from(origin.y) < self.page()? {
+            let layout = RowLayout::new(self.offset_digits(), self.bytes_per_row);
+
+            if let Some((pane, index)) = layout.byte_at(usize::from(origin.x)) {
+                let offset = (self.top + usize::from(origin.y)) * self.bytes_per_row + index;
+
+                if offset < self.data.len() {
+                    self.pane = pane;
+                    self.seek(offset)?;
+                }
+            }
+
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Render the visible rows and the status line into the hex view's pad.
+    pub fn render(&mut self) -> result!(()) {
+        let size = self.pad.size()?;
+        let style = self.style.unwrap_or_else(default_attributes_color_pair);
+        let cursor_style = self.cursor_style.unwrap_or_else(|| reverse_attributes_color_pair(style));
+        let modified_style = self.modified_style.unwrap_or_else(|| bold_attributes_color_pair(style));
+        let status_style = self.status_style.unwrap_or_else(|| reverse_attributes_color_pair(style));
+        let layout = RowLayout::new(self.offset_digits(), self.bytes_per_row);
+
+        self.pad.erase()?;
+
+        for y in 0..size.lines.saturating_sub(1) {
+            let start = (self.top + usize::from(y)) * self.bytes_per_row;
+
+            if start >= self.data.len() && !(start == 0 && y == 0) {
+                break;
+            }
+
+            let mut cells = vec!();
+
+            push_str_cells(&mut cells, &format!("{:0width$x}", start, width = layout.offset_digits), layout.hex_start() as u16, Justification::Left, style);
+
+            for index in 0..self.bytes_per_row {
+                let offset = start + index;
+                let byte = self.data.get(offset).copied();
+                let byte_style = if self.is_modified(offset) { modified_style } else { style };
+
+                while cells.len() < layout.hex_column(index) {
+                    cells.push(Cell::new(' ', style));
+                }
+
+                let hex = byte.map_or_else(|| "  ".to_string(), |byte| format!("{:02x}", byte));
+
+                for (nibble, ch) in hex.chars().enumerate() {
+                    let under_cursor = offset == self.cursor && byte.is_some() &&
+                        (self.pane == HexPane::Ascii || (nibble == 1) == self.low_nibble);
+
+                    cells.push(Cell::new(ch, if under_cursor { cursor_style } else { byte_style }));
+                }
+            }
+
+            while cells.len() < layout.ascii_start() - 1 {
+                cells.push(Cell::new(' ', style));
+            }
+
+            cells.push(Cell::new('|', style));
+
+            for index in 0..self.bytes_per_row {
+                let offset = start + index;
+
+                match self.data.get(offset) {
+                    Some(&byte) => {
+                        let byte_style = if offset == self.cursor {
+                            cursor_style
+                        } else if self.is_modified(offset) {
+                            modified_style
+                        } else {
+                            style
+                        };
+
+                        cells.push(Cell::new(ascii_char(byte), byte_style));
+                    },
+                    None        => cells.push(Cell::new(' ', style))
+                }
+            }
+
+            cells.push(Cell::new('|', style));
+
+            mvadd_cells(&self.pad, Origin { y, x: 0 }, &cells, 0, size.columns)?;
+        }
+
+        if size.lines > 0 {
+            let origin = Origin { y: size.lines - 1, x: 0 };
+
+            match &mut self.prompt {
+                Some(line_edit) => {
+                    let mut cells = vec!();
+
+                    push_str_cells(&mut cells, "Seek to offset: ", size.columns, Justification::Left, style);
+                    mvadd_cells(&self.pad, origin, &cells, 0, size.columns)?;
+
+                    let columns = cmp::min(cells.len() as u16, size.columns);
+
+                    line_edit.render(&self.pad, Origin { x: columns, ..origin }, Size { lines: 1, columns: size.columns - columns })?;
+                },
+                None            => {
+                    let status = match &self.message {
+                        Some(message) => message.clone(),
+                        None          => {
+                            let mut status = format!("offset 0x{:x}/0x{:x}", self.cursor, self.data.len());
+
+                            if let Some(&byte) = self.data.get(self.cursor) {
+                                status.push_str(&format!(" = 0x{:02x} {}", byte, byte));
+                            }
+
+                            if !self.original.is_empty() {
+                                status.push_str(&format!(", {} modified", self.original.len()));
+                            }
+
+                            status.push_str(match self.pane {
+                                HexPane::Hex   => " [hex]",
+                                HexPane::Ascii => " [ascii]"
+                            });
+
+                            status
+                        }
+                    };
+                    let mut cells = vec!();
+
+                    push_str_cells(&mut cells, &status, size.columns, Justification::Left, status_style);
+                    mvadd_cells(&self.pad, origin, &cells, 0, size.columns)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Render the hex view and refresh it onto the screen at the screen origin.
+    pub fn refresh(&mut self, screen_origin: Origin) -> result!(()) {
+        self.render()?;
+
+        self.pad.prefresh(Origin::default(), screen_origin, self.screen_max(screen_origin)?)
+    }
+
+    /// Render the hex view and copy it to the virtual screen at the screen
+    /// origin, call `doupdate()` to update the physical screen.
+    pub fn noutrefresh(&mut self, screen_origin: Origin) -> result!(()) {
+        self.render()?;
+
+        self.pad.pnoutrefresh(Origin::default(), screen_origin, self.screen_max(screen_origin)?)
+    }
+
+    fn handle_prompt_key(&mut self, key: CharacterResult<WideChar>) -> result!(bool) {
+        let line_edit = match &mut self.prompt {
+            Some(line_edit) => line_edit,
+            None            => return Ok(false)
+        };
+
+        // Ctrl-g or Ctrl-c cancel the seek.
+        let cancel = match key {
+            CharacterResult::Character(wch) => matches!(wch.as_char()?, '\x07' | '\x03'),
+            CharacterResult::Key(_)         => false
+        };
+
+        if cancel {
+            self.prompt = None;
+        } else if let LineEditEvent::Submitted(offset) = line_edit.handle_key(key)? {
+            self.prompt = None;
+
+            match parse_offset(&offset) {
+                Some(offset) => self.seek(offset)?,
+                None         => self.message = Some(format!("Invalid offset: {}", offset))
+            }
+        }
+
+        Ok(true)
+    }
+
+    // change the byte under the cursor with a character typed in the current pane.
+    fn edit(&mut self, ch: char) -> result!(bool) {
+        if self.read_only || self.cursor >= self.data.len() {
+            return Ok(false);
+        }
+
+        let byte = self.data[self.cursor];
+
+        match self.pane {
+            HexPane::Hex   => {
+                let digit = match ch.to_digit(16) {
+                    Some(digit) => digit as u8,
+                    None        => return Ok(false)
+                };
+
+                if self.low_nibble {
+                    self.set_byte(self.cursor, (byte & 0xf0) | digit);
+                    self.seek(self.cursor + 1)?;
+                } else {
+                    self.set_byte(self.cursor, (byte & 0x0f) | (digit << 4));
+                    self.low_nibble = true;
+                }
+            },
+            HexPane::Ascii => {
+                if !(ch == ' ' || ch.is_ascii_graphic()) {
+                    return Ok(false);
+                }
+
+                self.set_byte(self.cursor, ch as u8);
+                self.seek(self.cursor + 1)?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    // set a byte keeping the original value of changed bytes.
+    fn apply(&mut self, edit: HexEdit) {
+        let original = *self.original.entry(edit.offset).or_insert(edit.old);
+
+        if original == edit.new {
+            self.original.remove(&edit.offset);
+        }
+
+        self.data[edit.offset] = edit.new;
+    }
+
+    fn ensure_cursor_visible(&mut self) -> result!(()) {
+        let page = cmp::max(self.page()?, 1);
+        let row = self.cursor / self.bytes_per_row;
+
+        if row < self.top {
+            self.top = row;
+        } else if row >= self.top + page {
+            self.top = row + 1 - page;
+        }
+
+        Ok(())
+    }
+
+    // the number of hex digits of the offsets.
+    fn offset_digits(&self) -> usize {
+        if u32::try_from(self.data.len()).is_ok() { 8 } else { 16 }
+    }
+
+    fn row_count(&self) -> usize {
+        self.data.len().div_ceil(self.bytes_per_row)
+    }
+
+    // the number of rows shown above the status line.
+    fn page(&self) -> result!(usize) {
+        Ok(usize::from(self.pad.size()?.lines.saturating_sub(1)))
+    }
+
+    fn screen_max(&self, screen_origin: Origin) -> result!(Origin) {
+        let size = self.pad.size()?;
+
+        Ok(Origin {
+            y: screen_origin.y + size.lines.saturating_sub(1),
+            x: screen_origin.x + size.columns.saturating_sub(1)
+        })
+    }
+}
+
+impl ScrollTarget for HexView {
+    fn scroll_state(&self, axis: ScrollAxis) -> result!(ScrollState) {
+        Ok(match axis {
+            ScrollAxis::Vertical   => ScrollState::new(self.row_count(), self.page()?, self.top),
+            ScrollAxis::Horizontal => {
+                let columns = usize::from(self.size()?.columns);
+
+                ScrollState::new(columns, columns, 0)
+            }
+        })
+    }
+
+    fn set_scroll_position(&mut self, axis: ScrollAxis, position: usize) -> result!(()) {
+        match axis {
+            ScrollAxis::Vertical   => self.scroll_to(position),
+            ScrollAxis::Horizontal => Ok(())
+        }
+    }
+}
+
+impl fmt::Debug for HexView {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "HexView {{ pad: {:?}, data: {} bytes, modified: {}, bytes_per_row: {}, cursor: {}, pane: {:?}, top: {}, read_only: {} }}", self.pad, self.data.len(), self.original.len(), self.bytes_per_row, self.cursor, self.pane, self.top, self.read_only)
+    }
+}
+
+// the columns of a row of offset, hex bytes and ASCII characters.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct RowLayout {
+    offset_digits: usize,
+    bytes_per_row: usize
+}
+
+impl RowLayout {
+    fn new(offset_digits: usize, bytes_per_row: usize) -> Self {
+        Self { offset_digits, bytes_per_row }
+    }
+
+    fn hex_start(&self) -> usize {
+        self.offset_digits + 2
+    }
+
+    fn hex_column(&self, index: usize) -> usize {
+        self.hex_start() + index * 3 + index / GROUP_BYTES
+    }
+
+    // the ASCII characters follow the hex bytes, a space and a bar.
+    fn ascii_start(&self) -> usize {
+        self.hex_start() + self.bytes_per_row * 3 + (self.bytes_per_row - 1) / GROUP_BYTES + 1
+    }
+
+    fn columns(&self) -> usize {
+        self.ascii_start() + self.bytes_per_row + 1
+    }
+
+    // the pane and index of the byte at a column.
+    fn byte_at(&self, column: usize) -> Option<(HexPane, usize)> {
+        if column >= self.ascii_start() {
+            let index = column - self.ascii_start();
+
+            (index < self.bytes_per_row).then_some((HexPane::Ascii, index))
+        } else {
+            (0..self.bytes_per_row).find(|&index| (self.hex_column(index)..self.hex_column(index) + 2).contains(&column)).map(|index| (HexPane::Hex, index))
+        }
+    }
+}
+
+// the character shown in the ASCII pane for a byte.
+fn ascii_char(byte: u8) -> char {
+    if byte == b' ' || byte.is_ascii_graphic() { char::from(byte) } else { '.' }
+}
+
+// parse an offset in hex with a `0x` prefix or in decimal.
+fn parse_offset(str: &str) -> Option<usize> {
+    let str = str.trim();
+
+    match str.strip_prefix("0x").or_else(|| str.strip_prefix("0X")) {
+        Some(hex) => usize::from_str_radix(hex, 16).ok(),
+        None      => str.parse().ok()
+    }
+}
+
+#[test]
+fn hex_view_test() {
+    let layout = RowLayout::new(8, 16);
+
+    assert_eq!(layout.hex_column(0), 10);
+    assert_eq!(layout.hex_column(8), 35);
+    assert_eq!(layout.ascii_start(), 60);
+    assert_eq!(layout.columns(), 77);
+    assert_eq!(layout.byte_at(11), Some((HexPane::Hex, 0)));
+    assert_eq!(layout.byte_at(12), None);
+    assert_eq!(layout.byte_at(61), Some((HexPane::Ascii, 1)));
+    assert_eq!(parse_offset("0x1f"), Some(31));
+    assert_eq!(parse_offset(" 42 "), Some(42));
+    assert_eq!(parse_offset("zz"), None);
+    assert_eq!(ascii_char(0x41), 'A');
+    assert_eq!(ascii_char(0x00), '.');
+}
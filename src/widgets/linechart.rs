@@ -0,0 +1,327 @@
+/*
+    src/widgets/linechart.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::cmp;
+use ncursesw::AttributesColorPairSet;
+use crate::{
+    Origin, Size, BoxDrawingType, BoxDrawingGraphic, BrailleCanvas, NCurseswWinError,
+    HasMvAddFunctions,
+    widgets::funcs::format_value,
+    funcs::{
+        complex_box_char, default_attributes_color_pair, mvadd_complex_chars,
+        mvadd_str_columns, str_width, truncate_str
+    }
+};
+
+/// A named series of (x, y) points of a `LineChart`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Series {
+    name:   String,
+    points: Vec<(f64, f64)>,
+    style:  Option<AttributesColorPairSet>
+}
+
+impl Series {
+    /// Create a new series of (x, y) points.
+    pub fn new<S: Into<String>>(name: S, points: Vec<(f64, f64)>) -> Self {
+        Self { name: name.into(), points, style: None }
+    }
+
+    /// The name of the series.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The (x, y) points of the series.
+    pub fn points(&self) -> &[(f64, f64)] {
+        &self.points
+    }
+
+    /// Set the (x, y) points of the series.
+    pub fn set_points(&mut self, points: Vec<(f64, f64)>) {
+        self.points = points;
+    }
+
+    /// Set the attributes and color pair the series is drawn with.
+    pub fn set_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.style = style;
+    }
+}
+
+/// A line chart of one or more series plotted on a `BrailleCanvas` with labeled axes.
+///
+/// The y axis and it's labels are drawn on the left hand side of the region and the
+/// x axis and it's labels along the bottom, the remainder of the region is the plot area
+/// which has a resolution of 2x4 points per character cell.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LineChart {
+    series:           Vec<Series>,
+    x_range:          Option<(f64, f64)>,
+    y_range:          Option<(f64, f64)>,
+    x_ticks:          u16,
+    y_ticks:          u16,
+    precision:        usize,
+    show_legend:      bool,
+    box_drawing_type: BoxDrawingType,
+    axis_style:       Option<AttributesColorPairSet>
+}
+
+impl LineChart {
+    /// Create a new line chart of series.
+    pub fn new(series: Vec<Series>) -> Self {
+        Self {
+            series,
+            x_range:          None,
+            y_range:          None,
+            x_ticks:          5,
+            y_ticks:          5,
+            precision:        2,
+            show_legend:      false,
+            box_drawing_type: BoxDrawingType::default(),
+            axis_style:       None
+        }
+    }
+
+    /// The series of the chart.
+    pub fn series(&self) -> &[Series] {
+        &self.series
+    }
+
+    /// The series of the chart as mutable.
+    pub fn series_mut(&mut self) -> &mut Vec<Series> {
+        &mut self.series
+    }
+
+    /// Set the (minimum, maximum) of the x axis, if `None` the range of the series is used.
+    pub fn set_x_range(&mut self, x_range: Option<(f64, f64)>) {
+        self.x_range = x_range;
+    }
+
+    /// Set the (minimum, maximum) of the y axis, if `None` the range of the series is used.
+    pub fn set_y_range(&mut self, y_range: Option<(f64, f64)>) {
+        self.y_range = y_range;
+    }
+
+    /// Set the number of labeled ticks on the x and y axis and the maximum decimal places of the labels.
+    pub fn set_ticks(&mut self, x_ticks: u16, y_ticks: u16, precision: usize) {
+        assert!(x_ticks >= 2 && y_ticks >= 2, "LineChart::set_ticks() : x_ticks < 2 || y_ticks < 2");
+
+        self.x_ticks = x_ticks;
+        self.y_ticks = y_ticks;
+        self.precision = precision;
+    }
+
+    /// Show a legend of the series names in the upper right of the plot area.
+    pub fn set_show_legend(&mut self, show_legend: bool) {
+        self.show_legend = show_legend;
+    }
+
+    /// Set the box drawing type and the attributes and color pair of the axes.
+    pub fn set_axis_style(&mut self, box_drawing_type: BoxDrawingType, axis_style: Option<AttributesColorPairSet>) {
+        self.box_drawing_type = box_drawing_type;
+        self.axis_style = axis_style;
+    }
+
+    /// Render the line chart into the region of a window at origin of size.
+    pub fn render<W: HasMvAddFunctions>(&self, window: &W, origin: Origin, size: Size) -> result!(()) {
+        assert_origin!("render", window.size()?, origin);
+
+        let (x_min, x_max) = self.x_range.unwrap_or_else(|| self.data_range(|point| point.0));
+        let (y_min, y_max) = self.y_range.unwrap_or_else(|| self.data_range(|point| point.1));
+        let y_labels: Vec<String> = tick_values(y_min, y_max, self.y_ticks).map(|value| format_value(value, self.precision)).collect();
+        let label_width = cmp::min(y_labels.iter().map(|label| str_width(label)).max().unwrap_or(0), size.columns / 3);
+
+        // the bottom two lines are the x axis and it's labels and the y axis is after the y labels.
+        let plot_size = Size { lines: size.lines.saturating_sub(2), columns: size.columns.saturating_sub(label_width + 1) };
+
+        if plot_size.lines == 0 || plot_size.columns == 0 {
+            return Ok(());
+        }
+
+        let axis_style = self.axis_style.unwrap_or_else(default_attributes_color_pair);
+        let axis_x = origin.x + label_width;
+        let axis_y = origin.y + plot_size.lines;
+        let plot_origin = Origin { y: origin.y, x: axis_x + 1 };
+
+        // the y axis and it's labels.
+        let y_tick_lines: Vec<u16> = (0..self.y_ticks).map(|tick| plot_size.lines - 1 - tick_offset(tick, self.y_ticks, plot_size.lines)).collect();
+
+        for line in 0..plot_size.lines {
+            let tick = y_tick_lines.iter().position(|&tick_line| tick_line == line);
+            let label = tick.map_or_else(String::new, |tick| truncate_str(&y_labels[tick], label_width, false));
+            let graphic = if tick.is_some() { BoxDrawingGraphic::RightTee } else { BoxDrawingGraphic::VerticalLine };
+
+            mvadd_str_columns(window, Origin { y: origin.y + line, x: origin.x }, &format!("{:>1$}", label, usize::from(label_width)), label_width, axis_style)?;
+            mvadd_complex_chars(window, Origin { y: origin.y + line, x: axis_x }, &[complex_box_char(self.box_drawing_type, graphic, axis_style)?])?;
+        }
+
+        // the x axis and it's labels.
+        let x_tick_columns: Vec<u16> = (0..self.x_ticks).map(|tick| tick_offset(tick, self.x_ticks, plot_size.columns)).collect();
+        let mut axis = vec!(complex_box_char(self.box_drawing_type, BoxDrawingGraphic::LowerLeftCorner, axis_style)?);
+
+        for column in 0..plot_size.columns {
+            let graphic = if x_tick_columns.contains(&column) { BoxDrawingGraphic::UpperTee } else { BoxDrawingGraphic::HorizontalLine };
+
+            axis.push(complex_box_char(self.box_drawing_type, graphic, axis_style)?);
+        }
+
+        mvadd_str_columns(window, Origin { y: axis_y, x: origin.x }, "", label_width, axis_style)?;
+        mvadd_complex_chars(window, Origin { y: axis_y, x: axis_x }, &axis)?;
+
+        if size.lines > plot_size.lines + 1 {
+            let labels_origin = Origin { y: axis_y + 1, x: origin.x };
+
+            mvadd_str_columns(window, labels_origin, "", size.columns, axis_style)?;
+
+            // labels are centered on their tick and are skipped if they would overlap the previous label.
+            let mut next_column = 0;
+
+            for (value, &tick_column) in tick_values(x_min, x_max, self.x_ticks).zip(x_tick_columns.iter()) {
+                let label = format_value(value, self.precision);
+                let width = str_width(&label);
+                let center = label_width + 1 + tick_column;
+                let column = cmp::max(center.saturating_sub(width / 2), next_column);
+
+                if column + width <= size.columns {
+                    mvadd_str_columns(window, Origin { y: labels_origin.y, x: origin.x + column }, &label, width, axis_style)?;
+
+                    next_column = column + width + 1;
+                }
+            }
+        }
+
+        // the plot area.
+        let mut canvas = BrailleCanvas::new(plot_size);
+        let pixel_size = canvas.pixel_size();
+        let to_pixel = |(x, y): (f64, f64)| -> Option<Origin> {
+            if x.is_nan() || y.is_nan() {
+                None
+            } else {
+                Some(Origin {
+                    y: scale_to_pixel(y_max - y, y_max - y_min, pixel_size.lines),
+                    x: scale_to_pixel(x - x_min, x_max - x_min, pixel_size.columns)
+                })
+            }
+        };
+
+        for series in &self.series {
+            canvas.set_pen(series.style);
+
+            let mut previous = None;
+
+            for &point in &series.points {
+                let pixel = to_pixel(point);
+
+                match (previous, pixel) {
+                    (Some(from), Some(to)) => canvas.line(from, to),
+                    (None, Some(to))       => canvas.set_pixel(to),
+                    _                      => { }
+                }
+
+                previous = pixel;
+            }
+        }
+
+        canvas.blit(window, plot_origin)?;
+
+        if self.show_legend {
+            let legend_width = cmp::min(self.series.iter().map(|series| str_width(&series.name) + 2).max().unwrap_or(0), plot_size.columns);
+            let legend_x = plot_origin.x + plot_size.columns - legend_width;
+
+            for (line, series) in self.series.iter().take(usize::from(plot_size.lines)).enumerate() {
+                let style = series.style.unwrap_or_else(default_attributes_color_pair);
+                let legend_origin = Origin { y: plot_origin.y + line as u16, x: legend_x };
+
+                mvadd_str_columns(window, legend_origin, &format!("\u{25cf} {}", series.name), legend_width, style)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // the (minimum, maximum) of the series points, a range is always returned.
+    fn data_range<F: Fn(&(f64, f64)) -> f64>(&self, axis: F) -> (f64, f64) {
+        let (min, max) = self.series
+            .iter()
+            .flat_map(|series| series.points.iter().map(&axis))
+            .filter(|value| !value.is_nan())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| (min.min(value), max.max(value)));
+
+        if min > max {
+            (0.0, 1.0)
+        } else if (max - min).abs() < f64::EPSILON {
+            (min - 1.0, max + 1.0)
+        } else {
+            (min, max)
+        }
+    }
+}
+
+// the values of the ticks evenly spaced from min to max.
+fn tick_values(min: f64, max: f64, ticks: u16) -> impl Iterator<Item = f64> {
+    (0..ticks).map(move |tick| min + (max - min) * f64::from(tick) / f64::from(ticks - 1))
+}
+
+// the offset of a tick when evenly spaced over a length.
+fn tick_offset(tick: u16, ticks: u16, length: u16) -> u16 {
+    (f64::from(tick) * f64::from(length - 1) / f64::from(ticks - 1)).round() as u16
+}
+
+// scale a value of a range to a pixel, values outside of the range are clamped to the edge.
+fn scale_to_pixel(value: f64, range: f64, pixels: u16) -> u16 {
+    if range <= 0.0 {
+        0
+    } else {
+        ((value / range).clamp(0.0, 1.0) * f64::from(pixels - 1)).round() as u16
+    }
+}
+
+#[test]
+fn line_chart_test() {
+    let mut line_chart = LineChart::new(vec!());
+
+    assert_eq!(line_chart.data_range(|point| point.0), (0.0, 1.0));
+
+    // a single value is given a range around it.
+    line_chart.series_mut().push(Series::new("a", vec!((1.0, 5.0), (1.0, 5.0))));
+    assert_eq!(line_chart.data_range(|point| point.0), (0.0, 2.0));
+    assert_eq!(line_chart.data_range(|point| point.1), (4.0, 6.0));
+
+    line_chart.series_mut()[0].set_points(vec!((f64::NAN, 1.0), (2.0, 3.0), (4.0, f64::NAN)));
+    assert_eq!(line_chart.data_range(|point| point.0), (2.0, 4.0));
+    assert_eq!(line_chart.data_range(|point| point.1), (1.0, 3.0));
+
+    let labels: Vec<String> = tick_values(0.0, 10.0, 5).map(|value| format_value(value, 2)).collect();
+
+    assert_eq!(labels, vec!("0", "2.5", "5", "7.5", "10"));
+    assert_eq!(tick_offset(0, 5, 9), 0);
+    assert_eq!(tick_offset(1, 5, 9), 2);
+    assert_eq!(tick_offset(4, 5, 9), 8);
+    assert_eq!(tick_offset(1, 3, 10), 5);
+
+    assert_eq!(scale_to_pixel(5.0, 10.0, 11), 5);
+    assert_eq!(scale_to_pixel(15.0, 10.0, 11), 10);
+    assert_eq!(scale_to_pixel(-1.0, 10.0, 11), 0);
+    assert_eq!(scale_to_pixel(5.0, 0.0, 11), 0);
+
+    assert!(std::panic::catch_unwind(|| LineChart::new(vec!()).set_ticks(1, 5, 2)).is_err());
+}
@@ -0,0 +1,564 @@
+/*
+    src/widgets/lineedit.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{cmp, fmt};
+use ncursesw::AttributesColorPairSet;
+use crate::{
+    Origin, Size, Cell, NCurseswWinError, HasMvAddFunctions, CharacterResult, WideChar, KeyBinding,
+    widgets::funcs::mvadd_cells,
+    funcs::{char_width, default_attributes_color_pair}
+};
+
+const ESCAPE: char = '\u{1b}';
+
+/// A filter of the characters that may be entered into a `LineEdit`.
+pub type LineEditFilter = Box<dyn Fn(char) -> bool>;
+
+/// A tab completion callback of a `LineEdit`.
+pub type LineEditCompleter = Box<dyn FnMut(&str) -> Vec<String>>;
+
+/// The result of a `LineEdit` handling a key.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LineEditEvent {
+    /// The key was not used by the line edit.
+    Ignored,
+    /// The key edited the text or moved the cursor.
+    Handled,
+    /// Enter was pressed, the text is added to the history and the line cleared.
+    Submitted(String)
+}
+
+/// A single line text input with Emacs style editing, history and completion.
+///
+/// The keys used are the left and right arrows, home and end, backspace and
+/// delete, the shifted left and right arrows or `Alt-b` and `Alt-f` for
+/// word-wise motions, `Ctrl-a`, `Ctrl-e`, `Ctrl-b`, `Ctrl-f`, `Ctrl-d` and
+/// `Ctrl-h` as their Emacs equivalents, `Ctrl-k`, `Ctrl-u`, `Ctrl-w` and
+/// `Alt-d` to kill text and `Ctrl-y` to yank it back, the up and down arrows
+/// to recall the history and tab to complete. The text is scrolled
+/// horizontally by display columns so double width characters are handled.
+pub struct LineEdit {
+    text:       Vec<char>,
+    cursor:     usize,
+    scroll:     u16,
+    max_length: Option<usize>,
+    mask:       Option<char>,
+    filter:     Option<LineEditFilter>,
+    kill:       String,
+    history:    Vec<String>,
+    recall:     Option<(usize, String)>, // the history index and the line being edited.
+    completer:  Option<LineEditCompleter>,
+    completion: Option<(Vec<String>, usize)>, // the candidates and the index of the applied candidate.
+    escape:     bool,
+    style:      Option<AttributesColorPairSet>
+}
+
+impl LineEdit {
+    /// Create a new empty line edit.
+    pub fn new() -> Self {
+        Self {
+            text:       vec!(),
+            cursor:     0,
+            scroll:     0,
+            max_length: None,
+            mask:       None,
+            filter:     None,
+            kill:       String::new(),
+            history:    vec!(),
+            recall:     None,
+            completer:  None,
+            completion: None,
+            escape:     false,
+            style:      None
+        }
+    }
+
+    /// The text of the line edit.
+    pub fn text(&self) -> String {
+        self.text.iter().collect()
+    }
+
+    /// Set the text of the line edit placing the cursor at the end, the text
+    /// is truncated to the maximum length.
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.chars().take(self.max_length.unwrap_or(usize::MAX)).collect();
+        self.cursor = self.text.len();
+        self.completion = None;
+    }
+
+    /// The cursor position in characters.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Set the cursor position in characters.
+    pub fn set_cursor(&mut self, cursor: usize) {
+        self.cursor = cmp::min(cursor, self.text.len());
+    }
+
+    /// Set the maximum number of characters, `None` is unlimited.
+    pub fn set_max_length(&mut self, max_length: Option<usize>) {
+        self.max_length = max_length;
+
+        if let Some(max_length) = max_length {
+            self.text.truncate(max_length);
+            self.set_cursor(self.cursor);
+        }
+    }
+
+    /// Set the character displayed in place of each character of the text
+    /// such as `'*'` for a password, `None` displays the text.
+    pub fn set_mask(&mut self, mask: Option<char>) {
+        self.mask = mask;
+    }
+
+    /// Set a filter of the characters that may be entered, `None` accepts
+    /// all printable characters.
+    pub fn set_filter(&mut self, filter: Option<LineEditFilter>) {
+        self.filter = filter;
+    }
+
+    /// The history of submitted lines, the most recent last.
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    /// Set the history of submitted lines, the most recent last.
+    pub fn set_history(&mut self, history: Vec<String>) {
+        self.history = history;
+        self.recall = None;
+    }
+
+    /// Set the tab completion callback, this is called with the text before
+    /// the cursor and returns the candidates to replace it with.
+    pub fn set_completer(&mut self, completer: Option<LineEditCompleter>) {
+        self.completer = completer;
+        self.completion = None;
+    }
+
+    /// Set the attributes and color pair of the line edit.
+    pub fn set_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.style = style;
+    }
+
+    /// Handle a key.
+    pub fn handle_key(&mut self, key: CharacterResult<WideChar>) -> result!(LineEditEvent) {
+        let escape = self.escape;
+
+        self.escape = false;
+
+        let event = match key {
+            CharacterResult::Key(key_binding) => self.handle_key_binding(key_binding),
+            CharacterResult::Character(wch)   => {
+                let ch = wch.as_char()?;
+
+                if escape {
+                    self.handle_meta(ch)
+                } else {
+                    self.handle_character(ch)
+                }
+            }
+        };
+
+        Ok(event)
+    }
+
+    /// Insert a string at the cursor, characters rejected by the filter or
+    /// beyond the maximum length are dropped.
+    pub fn insert_str(&mut self, str: &str) {
+        for ch in str.chars() {
+            self.insert(ch);
+        }
+    }
+
+    /// Move the cursor to the start of the previous word.
+    pub fn move_word_left(&mut self) {
+        self.cursor = self.word_left();
+    }
+
+    /// Move the cursor to the end of the next word.
+    pub fn move_word_right(&mut self) {
+        self.cursor = self.word_right();
+    }
+
+    /// Render the line edit on the first line of the region of a window at
+    /// origin of size, the window's cursor is placed at the edit cursor.
+    pub fn render<W: HasMvAddFunctions>(&mut self, window: &W, origin: Origin, size: Size) -> result!(()) {
+        assert_origin!("render", window.size()?, origin);
+
+        if size.lines == 0 || size.columns == 0 {
+            return Ok(());
+        }
+
+        let style = self.style.unwrap_or_else(default_attributes_color_pair);
+        let display: Vec<char> = self.text.iter().map(|&ch| self.mask.unwrap_or(ch)).collect();
+        let cursor_column = display[..self.cursor].iter().fold(0u16, |width, &ch| width.saturating_add(char_width(ch)));
+        let cursor_width = cmp::max(display.get(self.cursor).map_or(1, |&ch| char_width(ch)), 1);
+
+        if cursor_column < self.scroll {
+            self.scroll = cursor_column;
+        } else if cursor_column + cursor_width > self.scroll + size.columns {
+            self.scroll = (cursor_column + cursor_width).saturating_sub(size.columns);
+        }
+
+        let mut cells = Vec::with_capacity(display.len() + usize::from(size.columns));
+
+        for &ch in &display {
+            match char_width(ch) {
+                0 => { },
+                1 => cells.push(Cell::new(ch, style)),
+                _ => {
+                    cells.push(Cell::new(ch, style));
+                    cells.push(Cell::continuation(style));
+                }
+            }
+        }
+
+        let end = usize::from(self.scroll) + usize::from(size.columns);
+
+        if cells.len() < end {
+            cells.resize(end, Cell::new(' ', style));
+        }
+
+        mvadd_cells(window, origin, &cells, usize::from(self.scroll), size.columns)?;
+
+        window.set_cursor(Origin { y: origin.y, x: origin.x + cursor_column - self.scroll })
+    }
+
+    fn handle_key_binding(&mut self, key_binding: KeyBinding) -> LineEditEvent {
+        if key_binding != KeyBinding::UpArrow && key_binding != KeyBinding::DownArrow {
+            self.recall = None;
+        }
+
+        self.completion = None;
+
+        match key_binding {
+            KeyBinding::LeftArrow       => self.cursor = self.cursor.saturating_sub(1),
+            KeyBinding::RightArrow      => self.set_cursor(self.cursor + 1),
+            KeyBinding::ShiftLeftArrow  => self.move_word_left(),
+            KeyBinding::ShiftRightArrow => self.move_word_right(),
+            KeyBinding::Home            => self.cursor = 0,
+            KeyBinding::End             => self.cursor = self.text.len(),
+            KeyBinding::Backspace       => self.backspace(),
+            KeyBinding::DeleteCharacter => self.delete(),
+            KeyBinding::UpArrow         => self.recall_previous(),
+            KeyBinding::DownArrow       => self.recall_next(),
+            KeyBinding::Enter           => return self.submit(),
+            _                           => return LineEditEvent::Ignored
+        }
+
+        LineEditEvent::Handled
+    }
+
+    fn handle_character(&mut self, ch: char) -> LineEditEvent {
+        if ch != '\t' {
+            self.completion = None;
+        }
+
+        if ch != '\t' && ch != ESCAPE {
+            self.recall = None;
+        }
+
+        match ch {
+            '\u{1}'            => self.cursor = 0,
+            '\u{2}'            => self.cursor = self.cursor.saturating_sub(1),
+            '\u{4}'            => self.delete(),
+            '\u{5}'            => self.cursor = self.text.len(),
+            '\u{6}'            => self.set_cursor(self.cursor + 1),
+            '\u{8}' | '\u{7f}' => self.backspace(),
+            '\t'               => return self.complete(),
+            '\n' | '\r'        => return self.submit(),
+            '\u{b}'            => self.kill_range(self.cursor, self.text.len()),
+            '\u{15}'           => self.kill_range(0, self.cursor),
+            '\u{17}'           => self.kill_range(self.word_left(), self.cursor),
+            '\u{19}'           => self.yank(),
+            ESCAPE             => self.escape = true,
+            ch if ch.is_control() => return LineEditEvent::Ignored,
+            ch                 => self.insert(ch)
+        }
+
+        LineEditEvent::Handled
+    }
+
+    fn handle_meta(&mut self, ch: char) -> LineEditEvent {
+        self.completion = None;
+        self.recall = None;
+
+        match ch {
+            'b' => self.move_word_left(),
+            'f' => self.move_word_right(),
+            'd' => self.kill_range(self.cursor, self.word_right()),
+            _   => return LineEditEvent::Ignored
+        }
+
+        LineEditEvent::Handled
+    }
+
+    fn insert(&mut self, ch: char) {
+        let accepted = self.filter.as_ref().is_none_or(|filter| filter(ch));
+
+        if accepted && !ch.is_control() && self.max_length.is_none_or(|max_length| self.text.len() < max_length) {
+            self.text.insert(self.cursor, ch);
+            self.cursor += 1;
+        }
+    }
+
+    fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            self.text.remove(self.cursor);
+        }
+    }
+
+    fn delete(&mut self) {
+        if self.cursor < self.text.len() {
+            self.text.remove(self.cursor);
+        }
+    }
+
+    // the start of the word before the cursor.
+    fn word_left(&self) -> usize {
+        let mut cursor = self.cursor;
+
+        while cursor > 0 && !self.text[cursor - 1].is_alphanumeric() {
+            cursor -= 1;
+        }
+
+        while cursor > 0 && self.text[cursor - 1].is_alphanumeric() {
+            cursor -= 1;
+        }
+
+        cursor
+    }
+
+    // the end of the word after the cursor.
+    fn word_right(&self) -> usize {
+        let mut cursor = self.cursor;
+
+        while cursor < self.text.len() && !self.text[cursor].is_alphanumeric() {
+            cursor += 1;
+        }
+
+        while cursor < self.text.len() && self.text[cursor].is_alphanumeric() {
+            cursor += 1;
+        }
+
+        cursor
+    }
+
+    // remove the text between start and end to the kill buffer.
+    fn kill_range(&mut self, start: usize, end: usize) {
+        if start < end {
+            self.kill = self.text.drain(start..end).collect();
+            self.cursor = start;
+        }
+    }
+
+    fn yank(&mut self) {
+        let kill = self.kill.clone();
+
+        self.insert_str(&kill);
+    }
+
+    fn recall_previous(&mut self) {
+        let index = match self.recall {
+            Some((0, _))                    => return,
+            Some((index, _))                => index - 1,
+            None if self.history.is_empty() => return,
+            None                            => {
+                self.recall = Some((self.history.len(), self.text()));
+
+                self.history.len() - 1
+            }
+        };
+
+        if let Some((recall, _)) = self.recall.as_mut() {
+            *recall = index;
+        }
+
+        let line = self.history[index].clone();
+
+        self.set_text(&line);
+    }
+
+    fn recall_next(&mut self) {
+        if let Some((index, edited)) = self.recall.take() {
+            if index + 1 < self.history.len() {
+                let line = self.history[index + 1].clone();
+
+                self.recall = Some((index + 1, edited));
+                self.set_text(&line);
+            } else {
+                self.set_text(&edited);
+            }
+        }
+    }
+
+    fn submit(&mut self) -> LineEditEvent {
+        let text = self.text();
+
+        if !text.is_empty() && self.history.last() != Some(&text) {
+            self.history.push(text.clone());
+        }
+
+        self.recall = None;
+        self.completion = None;
+        self.text.clear();
+        self.cursor = 0;
+        self.scroll = 0;
+
+        LineEditEvent::Submitted(text)
+    }
+
+    // complete the text before the cursor, with several candidates the common prefix is completed
+    // and repeated tabs cycle through the candidates.
+    fn complete(&mut self) -> LineEditEvent {
+        let before: String = self.text[..self.cursor].iter().collect();
+
+        let (candidates, index) = match self.completion.take() {
+            Some((candidates, index)) => {
+                let index = (index + 1) % candidates.len();
+
+                (candidates, Some(index))
+            },
+            None                      => {
+                let candidates = match self.completer.as_mut() {
+                    Some(completer) => completer(&before),
+                    None            => return LineEditEvent::Ignored
+                };
+
+                if candidates.is_empty() {
+                    return LineEditEvent::Handled;
+                }
+
+                let prefix = common_prefix(&candidates);
+
+                if candidates.len() == 1 || prefix.chars().count() > before.chars().count() {
+                    self.replace_before_cursor(&prefix);
+
+                    return LineEditEvent::Handled;
+                }
+
+                (candidates, Some(0))
+            }
+        };
+
+        if let Some(index) = index {
+            let candidate = candidates[index].clone();
+
+            self.replace_before_cursor(&candidate);
+            self.completion = Some((candidates, index));
+        }
+
+        LineEditEvent::Handled
+    }
+
+    fn replace_before_cursor(&mut self, str: &str) {
+        let after = self.text.split_off(self.cursor);
+
+        self.text.clear();
+        self.cursor = 0;
+        self.insert_str(str);
+        self.text.extend(after);
+
+        if let Some(max_length) = self.max_length {
+            self.text.truncate(max_length);
+        }
+    }
+}
+
+impl Default for LineEdit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for LineEdit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "LineEdit {{ text: {:?}, cursor: {}, scroll: {}, max_length: {:?}, mask: {:?}, history: {:?} }}", self.text(), self.cursor, self.scroll, self.max_length, self.mask, self.history)
+    }
+}
+
+// the longest common prefix of strings.
+fn common_prefix(strs: &[String]) -> String {
+    let mut prefix: Vec<char> = strs.first().map_or_else(Vec::new, |str| str.chars().collect());
+
+    for str in strs.iter().skip(1) {
+        let length = prefix.iter().zip(str.chars()).take_while(|(lhs, rhs)| *lhs == rhs).count();
+
+        prefix.truncate(length);
+    }
+
+    prefix.into_iter().collect()
+}
+
+#[test]
+fn line_edit_test() -> Result<(), NCurseswWinError> {
+    let key = |key_binding| CharacterResult::Key(key_binding);
+    let ch = |ch| CharacterResult::Character(WideChar::new(ch));
+    let mut line_edit = LineEdit::new();
+
+    line_edit.insert_str("hello brave world");
+    line_edit.handle_key(ch('\u{17}'))?;
+    line_edit.handle_key(key(KeyBinding::Home))?;
+    line_edit.handle_key(ch(ESCAPE))?;
+    line_edit.handle_key(ch('f'))?;
+    line_edit.handle_key(ch('\u{b}'))?;
+    line_edit.handle_key(ch('\u{1}'))?;
+    line_edit.handle_key(ch('\u{19}'))?;
+
+    assert_eq!(line_edit.text(), " brave hello");
+    assert_eq!(line_edit.handle_key(key(KeyBinding::Enter))?, LineEditEvent::Submitted(" brave hello".to_string()));
+
+    line_edit.set_history(vec!("one".to_string(), "two".to_string()));
+    line_edit.insert_str("thr");
+    line_edit.handle_key(key(KeyBinding::UpArrow))?;
+    line_edit.handle_key(key(KeyBinding::UpArrow))?;
+
+    assert_eq!(line_edit.text(), "one");
+
+    line_edit.handle_key(key(KeyBinding::DownArrow))?;
+    line_edit.handle_key(key(KeyBinding::DownArrow))?;
+
+    assert_eq!(line_edit.text(), "thr");
+
+    line_edit.set_completer(Some(Box::new(|before: &str| {
+        ["three", "threshold", "two"].iter().filter(|word| word.starts_with(before)).map(|word| word.to_string()).collect()
+    })));
+    line_edit.handle_key(ch('\t'))?;
+
+    assert_eq!(line_edit.text(), "thre");
+
+    line_edit.handle_key(ch('\t'))?;
+    line_edit.handle_key(ch('\t'))?;
+
+    assert_eq!(line_edit.text(), "threshold");
+
+    line_edit.set_text("");
+    line_edit.set_max_length(Some(3));
+    line_edit.set_filter(Some(Box::new(|ch: char| ch.is_ascii_digit())));
+    line_edit.insert_str("1a2b34");
+
+    assert_eq!(line_edit.text(), "123");
+
+    Ok(())
+}
@@ -0,0 +1,286 @@
+/*
+    src/widgets/listview.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::fmt;
+use ncursesw::AttributesColorPairSet;
+use crate::{
+    Origin, Size, NCurseswWinError, Pad, IsPad, HasYXAxis, BaseCanvas, Mouse,
+    CharacterResult, WideChar, KeyBinding,
+    widgets::{ScrollTarget, ScrollAxis, ScrollState, selection::Selection, funcs::{wheel_rows, is_button_1, region_origin}},
+    funcs::{default_attributes_color_pair, reverse_attributes_color_pair, mvadd_str_columns}
+};
+
+/// A row of a `ListView` as returned by it's row renderer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ListRow {
+    text:  String,
+    style: Option<AttributesColorPairSet>
+}
+
+impl ListRow {
+    /// Create a new row using the list view's style.
+    pub fn new(text: &str) -> Self {
+        Self { text: text.to_string(), style: None }
+    }
+
+    /// Create a new row with it's own attributes and color pair.
+    pub fn with_style(text: &str, style: AttributesColorPairSet) -> Self {
+        Self { text: text.to_string(), style: Some(style) }
+    }
+
+    /// The text of the row.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The attributes and color pair of the row.
+    pub fn style(&self) -> Option<AttributesColorPairSet> {
+        self.style
+    }
+}
+
+impl From<String> for ListRow {
+    fn from(text: String) -> Self {
+        Self { text, style: None }
+    }
+}
+
+impl From<&str> for ListRow {
+    fn from(text: &str) -> Self {
+        Self::new(text)
+    }
+}
+
+/// A virtualised scrolling list of rows.
+///
+/// The list view only holds a viewport sized `Pad` and calls it's renderer
+/// for the rows that are visible, so a list of any number of rows uses the
+/// same amount of memory. The selected row is moved with the up and down
+/// arrows, page up/down, home and end keys, the mouse wheel scrolls the list
+/// and a button 1 click selects a row.
+pub struct ListView {
+    pad:            Pad,
+    selection:      Selection,
+    renderer:       Box<dyn FnMut(usize) -> ListRow>,
+    style:          Option<AttributesColorPairSet>,
+    selected_style: Option<AttributesColorPairSet>
+}
+
+impl ListView {
+    /// Create a new list view of a viewport size with a number of rows, the
+    /// renderer is called with the index of each visible row.
+    pub fn new<F>(size: Size, row_count: usize, renderer: F) -> result!(Self)
+        where F: FnMut(usize) -> ListRow + 'static
+    {
+        Ok(Self {
+            pad:            Pad::new(size)?,
+            selection:      Selection::new(row_count),
+            renderer:       Box::new(renderer),
+            style:          None,
+            selected_style: None
+        })
+    }
+
+    /// The viewport size of the list view.
+    pub fn size(&self) -> result!(Size) {
+        self.pad.size()
+    }
+
+    /// Resize the viewport of the list view.
+    pub fn resize(&mut self, size: Size) -> result!(()) {
+        self.pad.resize(size)?;
+
+        let page = self.page()?;
+
+        self.selection.set_count(self.selection.count(), page);
+
+        Ok(())
+    }
+
+    /// The number of rows.
+    pub fn row_count(&self) -> usize {
+        self.selection.count()
+    }
+
+    /// Set the number of rows, the selected row is clamped to the new count.
+    pub fn set_row_count(&mut self, row_count: usize) -> result!(()) {
+        let page = self.page()?;
+
+        self.selection.set_count(row_count, page);
+
+        Ok(())
+    }
+
+    /// The index of the selected row, `None` if the list is empty.
+    pub fn selected(&self) -> Option<usize> {
+        self.selection.selected()
+    }
+
+    /// Select a row scrolling it into view.
+    pub fn set_selected(&mut self, selected: Option<usize>) -> result!(()) {
+        let page = self.page()?;
+
+        self.selection.select(selected, page);
+
+        Ok(())
+    }
+
+    /// The index of the first visible row.
+    pub fn top(&self) -> usize {
+        self.selection.top()
+    }
+
+    /// Scroll so the row at index is the first visible row.
+    pub fn scroll_to(&mut self, top: usize) -> result!(()) {
+        let page = self.page()?;
+
+        self.selection.set_top(top, page);
+
+        Ok(())
+    }
+
+    /// Set the attributes and color pair of rows without their own style.
+    pub fn set_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.style = style;
+    }
+
+    /// Set the attributes and color pair of the selected row, if `None` the
+    /// row's style is reversed.
+    pub fn set_selected_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.selected_style = style;
+    }
+
+    /// Handle a key returning `true` if the key was used by the list view.
+    pub fn handle_key(&mut self, key: CharacterResult<WideChar>) -> result!(bool) {
+        match key {
+            CharacterResult::Key(key_binding) => {
+                let page = self.page()?;
+
+                Ok(key_binding != KeyBinding::MouseEvent && self.selection.handle_key(key_binding, page))
+            },
+            CharacterResult::Character(_)     => Ok(false)
+        }
+    }
+
+    /// Handle a mouse event with the list view displayed at the screen origin,
+    /// returns `true` if the event was used by the list view.
+    pub fn handle_mouse(&mut self, mouse: &Mouse, screen_origin: Origin) -> result!(bool) {
+        let page = self.page()?;
+        let events = mouse.events();
+
+        let origin = match region_origin(mouse.origin()?.origin(), screen_origin, self.size()?) {
+            Some(origin) => origin,
+            None         => return Ok(false)
+        };
+
+        if let Some(rows) = wheel_rows(events) {
+            self.selection.scroll_by(rows, page);
+
+            Ok(true)
+        } else if is_button_1(events) {
+            let row = self.selection.top() + usize::from(origin.y);
+
+            if row < self.selection.count() {
+                self.selection.select(Some(row), page);
+            }
+
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Render the visible rows into the list view's pad.
+    pub fn render(&mut self) -> result!(()) {
+        let size = self.pad.size()?;
+        let style = self.style.unwrap_or_else(default_attributes_color_pair);
+        let selected = self.selection.selected();
+
+        self.pad.erase()?;
+
+        for (y, index) in self.selection.visible(usize::from(size.lines)).enumerate() {
+            let row = (self.renderer)(index);
+            let row_style = row.style.unwrap_or(style);
+            let row_style = if selected == Some(index) {
+                self.selected_style.unwrap_or_else(|| reverse_attributes_color_pair(row_style))
+            } else {
+                row_style
+            };
+
+            mvadd_str_columns(&self.pad, Origin { y: u16::try_from(y)?, x: 0 }, &row.text, size.columns, row_style)?;
+        }
+
+        Ok(())
+    }
+
+    /// Render the list view and refresh it onto the screen at the screen origin.
+    pub fn refresh(&mut self, screen_origin: Origin) -> result!(()) {
+        self.render()?;
+
+        self.pad.prefresh(Origin::default(), screen_origin, self.screen_max(screen_origin)?)
+    }
+
+    /// Render the list view and copy it to the virtual screen at the screen
+    /// origin, call `doupdate()` to update the physical screen.
+    pub fn noutrefresh(&mut self, screen_origin: Origin) -> result!(()) {
+        self.render()?;
+
+        self.pad.pnoutrefresh(Origin::default(), screen_origin, self.screen_max(screen_origin)?)
+    }
+
+    fn page(&self) -> result!(usize) {
+        Ok(usize::from(self.pad.size()?.lines))
+    }
+
+    fn screen_max(&self, screen_origin: Origin) -> result!(Origin) {
+        let size = self.pad.size()?;
+
+        Ok(Origin {
+            y: screen_origin.y + size.lines.saturating_sub(1),
+            x: screen_origin.x + size.columns.saturating_sub(1)
+        })
+    }
+}
+
+impl ScrollTarget for ListView {
+    fn scroll_state(&self, axis: ScrollAxis) -> result!(ScrollState) {
+        let size = self.size()?;
+
+        Ok(match axis {
+            ScrollAxis::Vertical   => ScrollState::new(self.row_count(), usize::from(size.lines), self.top()),
+            ScrollAxis::Horizontal => ScrollState::new(usize::from(size.columns), usize::from(size.columns), 0)
+        })
+    }
+
+    fn set_scroll_position(&mut self, axis: ScrollAxis, position: usize) -> result!(()) {
+        match axis {
+            ScrollAxis::Vertical   => self.scroll_to(position),
+            ScrollAxis::Horizontal => Ok(())
+        }
+    }
+}
+
+impl fmt::Debug for ListView {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ListView {{ pad: {:?}, selection: {:?}, style: {:?}, selected_style: {:?} }}", self.pad, self.selection, self.style, self.selected_style)
+    }
+}
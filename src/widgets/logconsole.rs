@@ -0,0 +1,736 @@
+/*
+    src/widgets/logconsole.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{
+    cmp, fmt,
+    collections::VecDeque,
+    time::{SystemTime, UNIX_EPOCH},
+    sync::{Arc, atomic::{AtomicU64, Ordering}, mpsc::{self, Receiver, SyncSender}}
+};
+use ncursesw::{AttributesColorPairSet, ColorPairColors, ColorsType};
+use crate::{
+    Origin, Size, NCurseswWinError, Pad, IsPad, HasYXAxis, BaseCanvas, Mouse, CharacterResult, WideChar,
+    KeyBinding, Justification, normal, extend,
+    widgets::{LineEdit, LineEditEvent, funcs::{mvadd_cells, push_str_cells, region_origin, wheel_rows}},
+    funcs::{
+        bold_attributes_color_pair, dim_attributes_color_pair, default_attributes_color_pair,
+        reverse_attributes_color_pair, color_started
+    }
+};
+
+// the most records waiting to be taken by `LogConsole::poll()`.
+const MAX_PENDING: usize = 4096;
+
+/// The level of a log record, from the most to the least severe.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogLevel {
+    /// Errors.
+    Error,
+    /// Warnings.
+    Warn,
+    /// Information.
+    Info,
+    /// Debugging information.
+    Debug,
+    /// Very detailed debugging information.
+    Trace
+}
+
+impl LogLevel {
+    // all the levels in order of severity.
+    const LEVELS: [LogLevel; 5] = [LogLevel::Error, LogLevel::Warn, LogLevel::Info, LogLevel::Debug, LogLevel::Trace];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[cfg(feature = "log")]
+impl From<log::Level> for LogLevel {
+    fn from(level: log::Level) -> Self {
+        match level {
+            log::Level::Error => LogLevel::Error,
+            log::Level::Warn  => LogLevel::Warn,
+            log::Level::Info  => LogLevel::Info,
+            log::Level::Debug => LogLevel::Debug,
+            log::Level::Trace => LogLevel::Trace
+        }
+    }
+}
+
+#[cfg(feature = "log")]
+impl From<LogLevel> for log::LevelFilter {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Error => log::LevelFilter::Error,
+            LogLevel::Warn  => log::LevelFilter::Warn,
+            LogLevel::Info  => log::LevelFilter::Info,
+            LogLevel::Debug => log::LevelFilter::Debug,
+            LogLevel::Trace => log::LevelFilter::Trace
+        }
+    }
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(match self {
+            LogLevel::Error => "ERROR",
+            LogLevel::Warn  => "WARN",
+            LogLevel::Info  => "INFO",
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Trace => "TRACE"
+        })
+    }
+}
+
+/// A log record shown by a `LogConsole`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogRecord {
+    /// The level of the record.
+    pub level:     LogLevel,
+    /// The target of the record, typically the module it was logged from.
+    pub target:    String,
+    /// The message of the record.
+    pub message:   String,
+    /// The time the record was created.
+    pub timestamp: SystemTime
+}
+
+impl LogRecord {
+    /// Create a new log record timestamped now.
+    pub fn new(level: LogLevel, target: &str, message: &str) -> Self {
+        Self { level, target: target.to_string(), message: message.to_string(), timestamp: SystemTime::now() }
+    }
+}
+
+impl fmt::Display for LogRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {:<5} ", time_of_day(self.timestamp), self.level)?;
+
+        if !self.target.is_empty() {
+            write!(f, "{}: ", self.target)?;
+        }
+
+        // keep a record on one line.
+        for ch in self.message.chars() {
+            write!(f, "{}", if ch.is_control() { ' ' } else { ch })?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A sink sending log records to a `LogConsole` from any thread.
+///
+/// The sink is modelled on the `log` crate, records less severe than the
+/// sink's maximum level are discarded before they are sent. Records sent
+/// while the console has too many records waiting to be polled are counted
+/// as dropped by the console, and records sent after the console has been
+/// dropped are silently discarded.
+///
+/// With the `log` feature the sink implements `log::Log`, `install()` makes
+/// it the `log` crate's logger.
+#[derive(Clone, Debug)]
+pub struct LogSink {
+    sender:    SyncSender<LogRecord>,
+    lost:      Arc<AtomicU64>, // the records not sent as the channel was full.
+    max_level: LogLevel
+}
+
+impl LogSink {
+    /// The sink with a maximum level, records less severe are discarded.
+    pub fn with_max_level(self, max_level: LogLevel) -> Self {
+        Self { max_level, ..self }
+    }
+
+    /// The maximum level of records sent by the sink.
+    pub fn max_level(&self) -> LogLevel {
+        self.max_level
+    }
+
+    /// Would a record of a level be sent.
+    pub fn enabled(&self, level: LogLevel) -> bool {
+        level <= self.max_level
+    }
+
+    /// Send a record if it's level is enabled, this never blocks.
+    pub fn send(&self, record: LogRecord) {
+        if self.enabled(record.level) && self.sender.try_send(record).is_err() {
+            self.lost.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Log a message of a level from a target.
+    pub fn log(&self, level: LogLevel, target: &str, message: &str) {
+        if self.enabled(level) {
+            self.send(LogRecord::new(level, target, message));
+        }
+    }
+
+    /// Log an error message from a target.
+    pub fn error(&self, target: &str, message: &str) {
+        self.log(LogLevel::Error, target, message);
+    }
+
+    /// Log a warning message from a target.
+    pub fn warn(&self, target: &str, message: &str) {
+        self.log(LogLevel::Warn, target, message);
+    }
+
+    /// Log an information message from a target.
+    pub fn info(&self, target: &str, message: &str) {
+        self.log(LogLevel::Info, target, message);
+    }
+
+    /// Log a debugging message from a target.
+    pub fn debug(&self, target: &str, message: &str) {
+        self.log(LogLevel::Debug, target, message);
+    }
+
+    /// Log a trace message from a target.
+    pub fn trace(&self, target: &str, message: &str) {
+        self.log(LogLevel::Trace, target, message);
+    }
+
+    /// Install the sink as the `log` crate's logger, setting the `log`
+    /// crate's maximum level to the sink's.
+    #[cfg(feature = "log")]
+    pub fn install(self) -> Result<(), log::SetLoggerError> {
+        log::set_max_level(self.max_level.into());
+        log::set_boxed_logger(Box::new(self))
+    }
+}
+
+#[cfg(feature = "log")]
+impl log::Log for LogSink {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.enabled(metadata.level().into())
+    }
+
+    fn log(&self, record: &log::Record) {
+        let level = record.level().into();
+
+        if self.enabled(level) {
+            self.send(LogRecord::new(level, record.target(), &record.args().to_string()));
+        }
+    }
+
+    fn flush(&self) { }
+}
+
+/// A tail style console showing log records sent from any thread through a
+/// `LogSink`.
+///
+/// Records are kept in a ring buffer of a bounded capacity, the oldest
+/// records being dropped first. The console keeps the newest record in view
+/// unless it has been scrolled back, scrolling to the end resumes following
+/// the newest record. The last line of the console is a status line showing
+/// the filter or the filter prompt. The keys are:
+///
+/// - `j`, down arrow: forward one record, `k`, up arrow: back one record.
+/// - page down and page up: forward and back a page.
+/// - `g`, home: the oldest record, `G`, end: the newest record.
+/// - `1` to `5`: show records from `ERROR` up to `TRACE`, `l` cycles the level.
+/// - `/`: filter records with a target or message containing some text, a
+///   filter of lower case letters matches either case and an empty filter
+///   shows all records. `Ctrl-g` cancels the prompt.
+pub struct LogConsole {
+    pad:          Pad,
+    sender:       SyncSender<LogRecord>,
+    receiver:     Receiver<LogRecord>,
+    lost:         Arc<AtomicU64>,
+    records:      VecDeque<(u64, LogRecord)>, // records and their sequence numbers.
+    next_seq:     u64,
+    capacity:     usize,
+    dropped:      u64,
+    top:          Option<u64>,                // the first visible record, `None` when following.
+    level:        LogLevel,
+    filter:       Option<String>,
+    ignore_case:  bool,                       // the filter has no upper case letters.
+    prompt:       Option<LineEdit>,
+    style:        Option<AttributesColorPairSet>,
+    level_styles: [Option<AttributesColorPairSet>; 5],
+    status_style: Option<AttributesColorPairSet>
+}
+
+impl LogConsole {
+    /// Create a new log console of a size including the status line keeping
+    /// up to a capacity of records, up to the capacity or 4096 records sent
+    /// through the console's sinks wait to be taken by `poll()`.
+    pub fn new(size: Size, capacity: usize) -> result!(Self) {
+        let capacity = cmp::max(capacity, 1);
+        let (sender, receiver) = mpsc::sync_channel(cmp::min(capacity, MAX_PENDING));
+
+        Ok(Self {
+            pad:          Pad::new(size)?,
+            sender,
+            receiver,
+            lost:         Arc::new(AtomicU64::new(0)),
+            records:      VecDeque::with_capacity(cmp::min(capacity, 1024)),
+            next_seq:     0,
+            capacity,
+            dropped:      0,
+            top:          None,
+            level:        LogLevel::Trace,
+            filter:       None,
+            ignore_case:  true,
+            prompt:       None,
+            style:        None,
+            level_styles: [None; 5],
+            status_style: None
+        })
+    }
+
+    /// A sink sending records to the console, the sink can be cloned and
+    /// sent to other threads.
+    pub fn sink(&self) -> LogSink {
+        LogSink { sender: self.sender.clone(), lost: Arc::clone(&self.lost), max_level: LogLevel::Trace }
+    }
+
+    /// The size of the console including the status line.
+    pub fn size(&self) -> result!(Size) {
+        self.pad.size()
+    }
+
+    /// Resize the console.
+    pub fn resize(&mut self, size: Size) -> result!(()) {
+        self.pad.resize(size)
+    }
+
+    /// The records kept by the console from the oldest.
+    pub fn records(&self) -> impl Iterator<Item = &LogRecord> {
+        self.records.iter().map(|(_, record)| record)
+    }
+
+    /// The maximum number of records kept.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Set the maximum number of records kept, dropping the oldest records.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = cmp::max(capacity, 1);
+        self.trim();
+    }
+
+    /// The number of records dropped as the capacity was exceeded or too
+    /// many records were waiting to be polled.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Remove all records.
+    pub fn clear(&mut self) {
+        self.records.clear();
+        self.top = None;
+    }
+
+    /// Add a record.
+    pub fn push(&mut self, record: LogRecord) {
+        self.records.push_back((self.next_seq, record));
+        self.next_seq += 1;
+        self.trim();
+    }
+
+    /// Take any records sent through the console's sinks, returning `true` if there were any.
+    pub fn poll(&mut self) -> bool {
+        let mut received = false;
+
+        while let Ok(record) = self.receiver.try_recv() {
+            self.push(record);
+            received = true;
+        }
+
+        self.dropped += self.lost.swap(0, Ordering::Relaxed);
+
+        received
+    }
+
+    /// The least severe level of the records shown.
+    pub fn level(&self) -> LogLevel {
+        self.level
+    }
+
+    /// Show records of a level and those more severe.
+    pub fn set_level(&mut self, level: LogLevel) {
+        self.level = level;
+    }
+
+    /// The text the records shown contain.
+    pub fn filter(&self) -> Option<&str> {
+        self.filter.as_deref()
+    }
+
+    /// Show only records with a target or message containing some text, a
+    /// filter without upper case letters matches either case.
+    pub fn set_filter(&mut self, filter: Option<&str>) {
+        self.filter = filter.filter(|filter| !filter.is_empty()).map(str::to_string);
+        self.ignore_case = self.filter.as_deref().is_none_or(|filter| !filter.chars().any(char::is_uppercase));
+    }
+
+    /// Is the console keeping the newest record in view.
+    pub fn is_following(&self) -> bool {
+        self.top.is_none()
+    }
+
+    /// Scroll to the newest record and keep it in view as records arrive.
+    pub fn follow(&mut self) {
+        self.top = None;
+    }
+
+    /// Scroll by a number of records, a negative number scrolls back.
+    pub fn scroll_by(&mut self, rows: isize) -> result!(()) {
+        let shown = self.shown();
+        let page = self.page()?;
+        let last = shown.len().saturating_sub(page);
+        let start = self.start(&shown, page);
+        let start = if rows < 0 { start.saturating_sub(rows.unsigned_abs()) } else { cmp::min(start.saturating_add(rows as usize), last) };
+
+        self.top = if start >= last { None } else { Some(shown[start]) };
+
+        Ok(())
+    }
+
+    /// Set the attributes and color pair of the text.
+    pub fn set_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.style = style;
+    }
+
+    /// Set the attributes and color pair of records of a level, by default
+    /// once colors have been started errors are red, warnings yellow,
+    /// debugging records cyan and trace records blue on the text's background
+    /// allocating the color pairs using `alloc_pair()`, otherwise errors and
+    /// warnings are bold and debugging and trace records are dim.
+    pub fn set_level_style(&mut self, level: LogLevel, style: Option<AttributesColorPairSet>) {
+        self.level_styles[level.index()] = style;
+    }
+
+    /// Set the attributes and color pair of the status line, by default the text style reversed.
+    pub fn set_status_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.status_style = style;
+    }
+
+    /// Handle a key returning `true` if the key was used by the console.
+    pub fn handle_key(&mut self, key: CharacterResult<WideChar>) -> result!(bool) {
+        if self.prompt.is_some() {
+            return self.handle_prompt_key(key);
+        }
+
+        let page = self.page()? as isize;
+
+        match key {
+            CharacterResult::Character(wch) => match wch.as_char()? {
+                'j'              => self.scroll_by(1)?,
+                'k'              => self.scroll_by(-1)?,
+                'g'              => self.scroll_by(isize::MIN)?,
+                'G'              => self.follow(),
+                'l'              => self.level = LogLevel::LEVELS[(self.level.index() + LogLevel::LEVELS.len() - 1) % LogLevel::LEVELS.len()],
+                digit @ '1'..='5' => self.level = LogLevel::LEVELS[digit as usize - '1' as usize],
+                '/'              => {
+                    let mut line_edit = LineEdit::new();
+
+                    line_edit.set_text(self.filter.as_deref().unwrap_or(""));
+                    self.prompt = Some(line_edit);
+                },
+                _                => return Ok(false)
+            },
+            CharacterResult::Key(key_binding) => match key_binding {
+                KeyBinding::DownArrow    => self.scroll_by(1)?,
+                KeyBinding::UpArrow      => self.scroll_by(-1)?,
+                KeyBinding::NextPage     => self.scroll_by(page)?,
+                KeyBinding::PreviousPage => self.scroll_by(-page)?,
+                KeyBinding::Home         => self.scroll_by(isize::MIN)?,
+                KeyBinding::End          => self.follow(),
+                _                        => return Ok(false)
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Handle a mouse event with the console displayed at the screen origin,
+    /// returns `true` if the event was used by the console.
+    pub fn handle_mouse(&mut self, mouse: &Mouse, screen_origin: Origin) -> result!(bool) {
+        if region_origin(mouse.origin()?.origin(), screen_origin, self.size()?).is_none() {
+            return Ok(false);
+        }
+
+        match wheel_rows(mouse.events()) {
+            Some(rows) => {
+                self.scroll_by(rows)?;
+
+                Ok(true)
+            },
+            None       => Ok(false)
+        }
+    }
+
+    /// Take any records sent through the console's sinks and render the
+    /// visible records and the status line into the console's pad.
+    pub fn render(&mut self) -> result!(()) {
+        self.poll();
+
+        let size = self.pad.size()?;
+        let style = self.style.unwrap_or_else(default_attributes_color_pair);
+        let status_style = self.status_style.unwrap_or_else(|| reverse_attributes_color_pair(style));
+        let shown = self.shown();
+        let page = self.page()?;
+        let start = self.start(&shown, page);
+        let level_styles = LogLevel::LEVELS.map(|level| self.level_styles[level.index()].unwrap_or_else(|| default_level_style(level, style)));
+
+        // the records are in sequence order so the first visible one is found by it's offset.
+        let first = self.records.front().map_or(0, |(seq, _)| *seq);
+
+        self.pad.erase()?;
+
+        for (y, seq) in shown.iter().skip(start).take(page).enumerate() {
+            let record = &self.records[(seq - first) as usize].1;
+            let mut cells = vec!();
+
+            push_str_cells(&mut cells, &record.to_string(), size.columns, Justification::Left, level_styles[record.level.index()]);
+            mvadd_cells(&self.pad, Origin { y: y as u16, x: 0 }, &cells, 0, size.columns)?;
+        }
+
+        if size.lines > 0 {
+            let origin = Origin { y: size.lines - 1, x: 0 };
+
+            match &mut self.prompt {
+                Some(line_edit) => {
+                    let prompt = "filter: ";
+                    let mut cells = vec!();
+
+                    push_str_cells(&mut cells, prompt, size.columns, Justification::Left, style);
+                    mvadd_cells(&self.pad, origin, &cells, 0, size.columns)?;
+
+                    let columns = cmp::min(cells.len() as u16, size.columns);
+
+                    line_edit.render(&self.pad, Origin { x: columns, ..origin }, Size { lines: 1, columns: size.columns - columns })?;
+                },
+                None            => {
+                    let mut status = format!("{}/{} records, level {}", shown.len(), self.records.len(), self.level);
+
+                    if let Some(filter) = &self.filter {
+                        status.push_str(&format!(", filter \"{}\"", filter));
+                    }
+
+                    if self.dropped > 0 {
+                        status.push_str(&format!(", {} dropped", self.dropped));
+                    }
+
+                    if self.top.is_some() {
+                        status.push_str(" [scrolled]");
+                    }
+
+                    let mut cells = vec!();
+
+                    push_str_cells(&mut cells, &status, size.columns, Justification::Left, status_style);
+                    mvadd_cells(&self.pad, origin, &cells, 0, size.columns)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Render the console and refresh it onto the screen at the screen origin.
+    pub fn refresh(&mut self, screen_origin: Origin) -> result!(()) {
+        self.render()?;
+
+        self.pad.prefresh(Origin::default(), screen_origin, self.screen_max(screen_origin)?)
+    }
+
+    /// Render the console and copy it to the virtual screen at the screen
+    /// origin, call `doupdate()` to update the physical screen.
+    pub fn noutrefresh(&mut self, screen_origin: Origin) -> result!(()) {
+        self.render()?;
+
+        self.pad.pnoutrefresh(Origin::default(), screen_origin, self.screen_max(screen_origin)?)
+    }
+
+    fn handle_prompt_key(&mut self, key: CharacterResult<WideChar>) -> result!(bool) {
+        let line_edit = match &mut self.prompt {
+            Some(line_edit) => line_edit,
+            None            => return Ok(false)
+        };
+
+        // Ctrl-g or Ctrl-c cancel the filter.
+        let cancel = match key {
+            CharacterResult::Character(wch) => matches!(wch.as_char()?, '\x07' | '\x03'),
+            CharacterResult::Key(_)         => false
+        };
+
+        if cancel {
+            self.prompt = None;
+        } else if let LineEditEvent::Submitted(filter) = line_edit.handle_key(key)? {
+            self.prompt = None;
+            self.set_filter(Some(&filter));
+        }
+
+        Ok(true)
+    }
+
+    // drop the oldest records past the capacity.
+    fn trim(&mut self) {
+        while self.records.len() > self.capacity {
+            self.records.pop_front();
+            self.dropped += 1;
+        }
+    }
+
+    // the sequence numbers of the records passing the level and text filters.
+    fn shown(&self) -> Vec<u64> {
+        self.records.iter()
+            .filter(|(_, record)| {
+                record.level <= self.level && self.filter.as_deref().is_none_or(|filter| {
+                    contains(&record.target, filter, self.ignore_case) || contains(&record.message, filter, self.ignore_case)
+                })
+            })
+            .map(|(seq, _)| *seq)
+            .collect()
+    }
+
+    // the index of the first visible record of those shown.
+    fn start(&self, shown: &[u64], page: usize) -> usize {
+        let last = shown.len().saturating_sub(page);
+
+        match self.top {
+            Some(top) => cmp::min(shown.partition_point(|&seq| seq < top), last),
+            None      => last
+        }
+    }
+
+    // the number of records shown above the status line.
+    fn page(&self) -> result!(usize) {
+        Ok(usize::from(self.pad.size()?.lines.saturating_sub(1)))
+    }
+
+    fn screen_max(&self, screen_origin: Origin) -> result!(Origin) {
+        let size = self.pad.size()?;
+
+        Ok(Origin {
+            y: screen_origin.y + size.lines.saturating_sub(1),
+            x: screen_origin.x + size.columns.saturating_sub(1)
+        })
+    }
+}
+
+impl fmt::Debug for LogConsole {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "LogConsole {{ pad: {:?}, records: {}, capacity: {}, dropped: {}, top: {:?}, level: {:?}, filter: {:?} }}", self.pad, self.records.len(), self.capacity, self.dropped, self.top, self.level, self.filter)
+    }
+}
+
+// the style of a level when the caller hasn't specified one.
+fn default_level_style(level: LogLevel, style: AttributesColorPairSet) -> AttributesColorPairSet {
+    let colored = if color_started() { level_color_pair(level, style) } else { None };
+
+    colored.unwrap_or_else(|| match level {
+        LogLevel::Error | LogLevel::Warn  => bold_attributes_color_pair(style),
+        LogLevel::Info                    => style,
+        LogLevel::Debug | LogLevel::Trace => dim_attributes_color_pair(style)
+    })
+}
+
+// the style with the color of a level on the style's background, `None` if a color pair can't be allocated.
+fn level_color_pair(level: LogLevel, style: AttributesColorPairSet) -> Option<AttributesColorPairSet> {
+    match style {
+        AttributesColorPairSet::Normal(attrs_colorpair) => {
+            let foreground = match level {
+                LogLevel::Error => normal::ColorPalette::Red,
+                LogLevel::Warn  => normal::ColorPalette::Yellow,
+                LogLevel::Info  => return Some(style),
+                LogLevel::Debug => normal::ColorPalette::Cyan,
+                LogLevel::Trace => normal::ColorPalette::Blue
+            };
+            let background = attrs_colorpair.color_pair().colors().ok()?.background();
+            let color_pair = normal::alloc_pair(normal::Colors::new(normal::Color::new(foreground), background)).ok()?;
+
+            Some(AttributesColorPairSet::Normal(normal::AttributesColorPair::new(attrs_colorpair.attributes(), color_pair)))
+        },
+        AttributesColorPairSet::Extend(attrs_colorpair) => {
+            let foreground = match level {
+                LogLevel::Error => extend::ColorPalette::Red,
+                LogLevel::Warn  => extend::ColorPalette::Yellow,
+                LogLevel::Info  => return Some(style),
+                LogLevel::Debug => extend::ColorPalette::Cyan,
+                LogLevel::Trace => extend::ColorPalette::Blue
+            };
+            let background = attrs_colorpair.color_pair().colors().ok()?.background();
+            let color_pair = extend::alloc_pair(extend::Colors::new(extend::Color::new(foreground), background)).ok()?;
+
+            Some(AttributesColorPairSet::Extend(extend::AttributesColorPair::new(attrs_colorpair.attributes(), color_pair)))
+        }
+    }
+}
+
+// does a line contain a filter, ignoring the case of the line without allocating when the filter is lower case.
+fn contains(line: &str, filter: &str, ignore_case: bool) -> bool {
+    if !ignore_case {
+        return line.contains(filter);
+    }
+
+    line.char_indices().any(|(index, _)| {
+        let mut chars = line[index..].chars().flat_map(char::to_lowercase);
+
+        filter.chars().all(|ch| chars.next() == Some(ch))
+    })
+}
+
+// the UTC time of day of a timestamp as `HH:MM:SS.mmm`.
+fn time_of_day(timestamp: SystemTime) -> String {
+    let millis = timestamp.duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_millis()) % 86_400_000;
+
+    format!("{:02}:{:02}:{:02}.{:03}", millis / 3_600_000, millis / 60_000 % 60, millis / 1000 % 60, millis % 1000)
+}
+
+#[test]
+fn log_console_test() {
+    use std::time::Duration;
+
+    let timestamp = UNIX_EPOCH + Duration::from_millis(86_400_000 + 3_723_456);
+    let record = LogRecord { level: LogLevel::Warn, target: "app".to_string(), message: "disk\nfull".to_string(), timestamp };
+
+    assert_eq!(time_of_day(timestamp), "01:02:03.456");
+    assert_eq!(record.to_string(), "01:02:03.456 WARN  app: disk full");
+    assert!(contains("Disk Full", "full", true));
+    assert!(contains("ÜBER", "über", true));
+    assert!(!contains("Disk full", "Full", false));
+    assert!(!contains("Disk", "disks", true));
+    assert!(LogLevel::Error < LogLevel::Trace);
+
+    let (sender, receiver) = mpsc::sync_channel(1);
+    let sink = LogSink { sender, lost: Arc::new(AtomicU64::new(0)), max_level: LogLevel::Info };
+
+    sink.debug("app", "ignored");
+    sink.info("app", "sent");
+    sink.warn("app", "lost");
+    assert_eq!(receiver.try_recv().map(|record| record.message), Ok("sent".to_string()));
+    assert!(receiver.try_recv().is_err());
+    assert_eq!(sink.lost.load(Ordering::Relaxed), 1);
+
+    #[cfg(feature = "log")]
+    {
+        assert_eq!(LogLevel::from(log::Level::Warn), LogLevel::Warn);
+        assert!(log::Log::enabled(&sink, &log::Metadata::builder().level(log::Level::Info).build()));
+        assert!(!log::Log::enabled(&sink, &log::Metadata::builder().level(log::Level::Debug).build()));
+    }
+}
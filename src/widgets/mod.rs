@@ -0,0 +1,52 @@
+/*
+    src/widgets/mod.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+mod barchart;
+mod colorscale;
+mod commandpalette;
+mod dialog;
+mod filepicker;
+mod funcs;
+mod gauge;
+mod hexview;
+mod linechart;
+mod lineedit;
+mod listview;
+mod logconsole;
+mod pager;
+mod scrollbackwindow;
+mod scrollbar;
+mod selection;
+mod sparkline;
+mod splitpane;
+mod statusbar;
+mod table;
+mod textarea;
+mod toasts;
+mod treeview;
+
+pub use self::{
+    barchart::*, colorscale::*, commandpalette::*, dialog::*, filepicker::*, gauge::*, hexview::*,
+    linechart::*, lineedit::*, listview::*, logconsole::*, pager::*, scrollbackwindow::*,
+    scrollbar::*, sparkline::*, splitpane::*, statusbar::*, table::*, textarea::*, toasts::*,
+    treeview::*
+};
@@ -0,0 +1,789 @@
+/*
+    src/widgets/pager.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{
+    cmp, fmt, thread, fs::File, path::Path, time::Duration,
+    io::{BufRead, BufReader, ErrorKind},
+    sync::{Arc, atomic::{AtomicBool, Ordering}, mpsc::{self, Receiver}}
+};
+use ncursesw::AttributesColorPairSet;
+use crate::{
+    Origin, Size, Cell, NCurseswWinError, Pad, IsPad, HasYXAxis, BaseCanvas,
+    Mouse, CharacterResult, WideChar, KeyBinding, Justification,
+    widgets::{LineEdit, LineEditEvent, funcs::{mvadd_cells, push_str_cells, region_origin, wheel_rows}},
+    funcs::{TAB_SIZE, char_width, default_attributes_color_pair, reverse_attributes_color_pair}
+};
+
+// how often the reader checks for more data at the end of a source.
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(200);
+
+// the most lines taken from the reader each time the pager is rendered.
+const POLL_LINES: usize = 65536;
+
+// a line of the source and whether it's complete or the partial last line.
+type SourceLine = (String, bool);
+
+// the search pattern and direction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Search {
+    pattern: String,
+    forward: bool
+}
+
+/// A pager comparable to `less` showing text from a string, a file or any
+/// `BufRead` source.
+///
+/// A file or `BufRead` source is read on a separate thread and streamed in as
+/// the pager is rendered, when the end of the source is reached it's checked
+/// for more data so a growing file is followed. The last line of the pager
+/// is a status line showing the visible lines and percentage or the search
+/// prompt. The keys are:
+///
+/// - `j`, `Enter`, down arrow: forward one line, `k`, up arrow: back one line.
+/// - `Space`, `f`, page down: forward a page, `b`, page up: back a page.
+/// - `d` and `u`: forward and back half a page.
+/// - `g`, `<`, home: the first line, `G`, `>`, end: the last line, a number
+///   typed before `g` or `G` goes to that line.
+/// - left and right arrows: scroll horizontally when lines are not wrapped.
+/// - `/` and `?`: search forward and backward, `n` and `N`: repeat the
+///   search in the same and opposite direction, a pattern of lower case
+///   letters matches either case. `Ctrl-g` cancels the prompt.
+/// - `F`: toggle follow mode, keeping the last line in view as data arrives.
+/// - `S`: toggle wrapping of long lines.
+///
+/// Other keys such as `q` are not used so the caller can act on them.
+pub struct Pager {
+    pad:          Pad,
+    lines:        Vec<String>,
+    partial:      bool,                       // the last line is incomplete.
+    receiver:     Option<Receiver<SourceLine>>,
+    alive:        Arc<AtomicBool>,            // cleared to stop the reader thread.
+    top:          usize,                      // the first visible line.
+    top_row:      usize,                      // the first visible row of a wrapped first line.
+    scroll_x:     usize,
+    wrap:         bool,
+    follow:       bool,
+    search:       Option<Search>,
+    prompt:       Option<(bool, LineEdit)>,   // the search prompt and it's direction.
+    count:        Option<usize>,              // a number typed before a command.
+    message:      Option<String>,
+    style:        Option<AttributesColorPairSet>,
+    match_style:  Option<AttributesColorPairSet>,
+    status_style: Option<AttributesColorPairSet>
+}
+
+impl Pager {
+    /// Create a new empty pager of a size including the status line.
+    pub fn new(size: Size) -> result!(Self) {
+        Ok(Self {
+            pad:          Pad::new(size)?,
+            lines:        vec!(),
+            partial:      false,
+            receiver:     None,
+            alive:        Arc::new(AtomicBool::new(true)),
+            top:          0,
+            top_row:      0,
+            scroll_x:     0,
+            wrap:         false,
+            follow:       false,
+            search:       None,
+            prompt:       None,
+            count:        None,
+            message:      None,
+            style:        None,
+            match_style:  None,
+            status_style: None
+        })
+    }
+
+    /// Create a new pager of a size showing a string.
+    pub fn from_string(size: Size, str: &str) -> result!(Self) {
+        let mut pager = Self::new(size)?;
+
+        pager.lines = str.lines().map(display_line).collect();
+
+        Ok(pager)
+    }
+
+    /// Create a new pager of a size streaming a file.
+    pub fn from_file<P: AsRef<Path>>(size: Size, path: P) -> result!(Self) {
+        let file = File::open(path)?;
+
+        Self::from_reader(size, BufReader::new(file))
+    }
+
+    /// Create a new pager of a size streaming a `BufRead` source.
+    pub fn from_reader<R: BufRead + Send + 'static>(size: Size, reader: R) -> result!(Self) {
+        let mut pager = Self::new(size)?;
+
+        pager.receiver = Some(spawn_reader(reader, Arc::clone(&pager.alive)));
+
+        Ok(pager)
+    }
+
+    /// The size of the pager including the status line.
+    pub fn size(&self) -> result!(Size) {
+        self.pad.size()
+    }
+
+    /// Resize the pager.
+    pub fn resize(&mut self, size: Size) -> result!(()) {
+        self.pad.resize(size)?;
+        self.top_row = 0;
+        self.clamp()
+    }
+
+    /// The lines read so far.
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// The index of the first visible line.
+    pub fn top(&self) -> usize {
+        self.top
+    }
+
+    /// Are long lines wrapped.
+    pub fn is_wrap(&self) -> bool {
+        self.wrap
+    }
+
+    /// Set whether long lines are wrapped or scrolled horizontally.
+    pub fn set_wrap(&mut self, wrap: bool) -> result!(()) {
+        self.wrap = wrap;
+        self.top_row = 0;
+        self.scroll_x = 0;
+        self.clamp()
+    }
+
+    /// Is the pager following the end of the source.
+    pub fn is_follow(&self) -> bool {
+        self.follow
+    }
+
+    /// Set whether the pager keeps the last line in view as data arrives.
+    pub fn set_follow(&mut self, follow: bool) -> result!(()) {
+        self.follow = follow;
+
+        if follow {
+            self.scroll_to_end()?;
+        }
+
+        Ok(())
+    }
+
+    /// Set the attributes and color pair of the text.
+    pub fn set_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.style = style;
+    }
+
+    /// Set the attributes and color pair of search matches, by default the text style reversed.
+    pub fn set_match_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.match_style = style;
+    }
+
+    /// Set the attributes and color pair of the status line, by default the text style reversed.
+    pub fn set_status_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.status_style = style;
+    }
+
+    /// Take any lines read from the source, returning `true` if there were any.
+    pub fn poll(&mut self) -> result!(bool) {
+        let received: Vec<SourceLine> = match &self.receiver {
+            Some(receiver) => receiver.try_iter().take(POLL_LINES).collect(),
+            None           => return Ok(false)
+        };
+
+        if received.is_empty() {
+            return Ok(false);
+        }
+
+        for (line, complete) in received {
+            if self.partial {
+                self.lines.pop();
+            }
+
+            self.lines.push(display_line(&line));
+            self.partial = !complete;
+        }
+
+        if self.follow {
+            self.scroll_to_end()?;
+        }
+
+        Ok(true)
+    }
+
+    /// Go to a line, the first line being 0.
+    pub fn goto_line(&mut self, line: usize) -> result!(()) {
+        self.top = line;
+        self.top_row = 0;
+        self.clamp()
+    }
+
+    /// Go to the last page of lines.
+    pub fn scroll_to_end(&mut self) -> result!(()) {
+        (self.top, self.top_row) = self.last_page_top()?;
+
+        Ok(())
+    }
+
+    /// Scroll by a number of rows, a negative number scrolls back.
+    pub fn scroll_by(&mut self, rows: isize) -> result!(()) {
+        let columns = self.pad.size()?.columns;
+
+        if rows < 0 {
+            for _ in 0..rows.unsigned_abs() {
+                if self.top_row > 0 {
+                    self.top_row -= 1;
+                } else if self.top > 0 {
+                    self.top -= 1;
+                    self.top_row = self.row_count(self.top, columns) - 1;
+                } else {
+                    break;
+                }
+            }
+
+            Ok(())
+        } else {
+            let last = self.last_page_top()?;
+
+            for _ in 0..rows {
+                if (self.top, self.top_row) >= last {
+                    break;
+                } else if self.top_row + 1 < self.row_count(self.top, columns) {
+                    self.top_row += 1;
+                } else {
+                    self.top += 1;
+                    self.top_row = 0;
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Search for a pattern from the line after the first visible line, or
+    /// before it when searching backward, returning `true` if it was found.
+    pub fn search(&mut self, pattern: &str, forward: bool) -> result!(bool) {
+        self.search = if pattern.is_empty() { None } else { Some(Search { pattern: pattern.to_string(), forward }) };
+        self.find(forward)
+    }
+
+    /// The percentage of the lines up to the last visible line.
+    pub fn percentage(&self) -> result!(usize) {
+        if self.lines.is_empty() {
+            return Ok(100);
+        }
+
+        let (last, _) = self.last_visible()?;
+
+        Ok((last + 1) * 100 / self.lines.len())
+    }
+
+    /// Handle a key returning `true` if the key was used by the pager.
+    pub fn handle_key(&mut self, key: CharacterResult<WideChar>) -> result!(bool) {
+        if self.prompt.is_some() {
+            return self.handle_prompt_key(key);
+        }
+
+        self.message = None;
+
+        let page = self.page()? as isize;
+        let count = self.count.take();
+
+        match key {
+            CharacterResult::Character(wch) => match wch.as_char()? {
+                digit @ '0'..='9' => {
+                    let digit = digit.to_digit(10).unwrap_or(0) as usize;
+
+                    self.count = Some(count.unwrap_or(0).saturating_mul(10).saturating_add(digit));
+                },
+                'j' | '\n' | '\r' => self.scroll_forward(count.map_or(1, |count| count as isize))?,
+                'k'               => self.scroll_back(count.map_or(1, |count| count as isize))?,
+                ' ' | 'f'         => self.scroll_forward(page)?,
+                'b'               => self.scroll_back(page)?,
+                'd'               => self.scroll_forward(cmp::max(page / 2, 1))?,
+                'u'               => self.scroll_back(cmp::max(page / 2, 1))?,
+                'g' | '<'         => self.goto(count.map_or(0, |line| line.saturating_sub(1)))?,
+                'G' | '>'         => match count {
+                    Some(line) => self.goto(line.saturating_sub(1))?,
+                    None       => self.scroll_to_end()?
+                },
+                '/'               => self.prompt = Some((true, LineEdit::new())),
+                '?'               => self.prompt = Some((false, LineEdit::new())),
+                'n'               => self.repeat_search(true)?,
+                'N'               => self.repeat_search(false)?,
+                'F'               => self.set_follow(!self.follow)?,
+                'S'               => self.set_wrap(!self.wrap)?,
+                _                 => return Ok(false)
+            },
+            CharacterResult::Key(key_binding) => match key_binding {
+                KeyBinding::DownArrow    => self.scroll_forward(1)?,
+                KeyBinding::UpArrow      => self.scroll_back(1)?,
+                KeyBinding::NextPage     => self.scroll_forward(page)?,
+                KeyBinding::PreviousPage => self.scroll_back(page)?,
+                KeyBinding::Home         => self.goto(0)?,
+                KeyBinding::End          => self.scroll_to_end()?,
+                KeyBinding::LeftArrow    => self.scroll_horizontally(false)?,
+                KeyBinding::RightArrow   => self.scroll_horizontally(true)?,
+                _                        => return Ok(false)
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Handle a mouse event with the pager displayed at the screen origin,
+    /// returns `true` if the event was used by the pager.
+    pub fn handle_mouse(&mut self, mouse: &Mouse, screen_origin: Origin) -> result!(bool) {
+        if region_origin(mouse.origin()?.origin(), screen_origin, self.size()?).is_none() {
+            return Ok(false);
+        }
+
+        match wheel_rows(mouse.events()) {
+            Some(rows) => {
+                if rows < 0 {
+                    self.follow = false;
+                }
+
+                self.scroll_by(rows)?;
+
+                Ok(true)
+            },
+            None       => Ok(false)
+        }
+    }
+
+    /// Take any lines read from the source and render the visible lines and
+    /// the status line into the pager's pad.
+    pub fn render(&mut self) -> result!(()) {
+        self.poll()?;
+
+        let size = self.pad.size()?;
+        let style = self.style.unwrap_or_else(default_attributes_color_pair);
+        let match_style = self.match_style.unwrap_or_else(|| reverse_attributes_color_pair(style));
+        let status_style = self.status_style.unwrap_or_else(|| reverse_attributes_color_pair(style));
+        let offset = if self.wrap { 0 } else { self.scroll_x };
+        let (mut line, mut row) = (self.top, self.top_row);
+
+        self.pad.erase()?;
+
+        for y in 0..size.lines.saturating_sub(1) {
+            let origin = Origin { y, x: 0 };
+
+            let text = match self.lines.get(line) {
+                Some(text) => text,
+                None       => {
+                    mvadd_cells(&self.pad, origin, &[Cell::new('~', style)], 0, size.columns)?;
+
+                    continue;
+                }
+            };
+
+            let breaks = row_breaks(text, if self.wrap { Some(size.columns) } else { None });
+            let end = breaks.get(row + 1).copied().unwrap_or(text.len());
+            let matches = self.matches(text);
+            let mut cells = Vec::with_capacity(end - breaks[row]);
+
+            for (index, ch) in text[breaks[row]..end].char_indices() {
+                let index = breaks[row] + index;
+                let cell_style = if matches.iter().any(|range| range.contains(&index)) { match_style } else { style };
+
+                match char_width(ch) {
+                    0 => { },
+                    1 => cells.push(Cell::new(ch, cell_style)),
+                    _ => {
+                        cells.push(Cell::new(ch, cell_style));
+                        cells.push(Cell::continuation(cell_style));
+                    }
+                }
+            }
+
+            mvadd_cells(&self.pad, origin, &cells, offset, size.columns)?;
+
+            if row + 1 < breaks.len() {
+                row += 1;
+            } else {
+                line += 1;
+                row = 0;
+            }
+        }
+
+        if size.lines > 0 {
+            let origin = Origin { y: size.lines - 1, x: 0 };
+
+            match &mut self.prompt {
+                Some((forward, line_edit)) => {
+                    let prompt = if *forward { "/" } else { "?" };
+
+                    mvadd_cells(&self.pad, origin, &[Cell::new(prompt.chars().next().unwrap_or('/'), style)], 0, size.columns)?;
+                    line_edit.render(&self.pad, Origin { x: 1, ..origin }, Size { lines: 1, columns: size.columns.saturating_sub(1) })?;
+                },
+                None                       => {
+                    let status = match &self.message {
+                        Some(message) => message.clone(),
+                        None          => self.status()?
+                    };
+                    let mut cells = vec!();
+
+                    push_str_cells(&mut cells, &status, size.columns, Justification::Left, status_style);
+                    mvadd_cells(&self.pad, origin, &cells, 0, size.columns)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Render the pager and refresh it onto the screen at the screen origin.
+    pub fn refresh(&mut self, screen_origin: Origin) -> result!(()) {
+        self.render()?;
+
+        self.pad.prefresh(Origin::default(), screen_origin, self.screen_max(screen_origin)?)
+    }
+
+    /// Render the pager and copy it to the virtual screen at the screen
+    /// origin, call `doupdate()` to update the physical screen.
+    pub fn noutrefresh(&mut self, screen_origin: Origin) -> result!(()) {
+        self.render()?;
+
+        self.pad.pnoutrefresh(Origin::default(), screen_origin, self.screen_max(screen_origin)?)
+    }
+
+    fn handle_prompt_key(&mut self, key: CharacterResult<WideChar>) -> result!(bool) {
+        let (forward, line_edit) = match &mut self.prompt {
+            Some((forward, line_edit)) => (*forward, line_edit),
+            None                       => return Ok(false)
+        };
+
+        // Ctrl-g, Ctrl-c or backspace on an empty prompt cancel the search.
+        let cancel = match key {
+            CharacterResult::Character(wch)          => matches!(wch.as_char()?, '\x07' | '\x03') || (wch.as_char()? == '\x7f' && line_edit.text().is_empty()),
+            CharacterResult::Key(KeyBinding::Backspace) => line_edit.text().is_empty(),
+            CharacterResult::Key(_)                  => false
+        };
+
+        if cancel {
+            self.prompt = None;
+        } else if let LineEditEvent::Submitted(pattern) = line_edit.handle_key(key)? {
+            self.prompt = None;
+
+            // an empty pattern repeats the last search in the new direction.
+            let pattern = match (pattern.is_empty(), &self.search) {
+                (true, Some(search)) => search.pattern.clone(),
+                _                    => pattern
+            };
+
+            self.search(&pattern, forward)?;
+        }
+
+        Ok(true)
+    }
+
+    fn scroll_forward(&mut self, rows: isize) -> result!(()) {
+        self.scroll_by(rows)
+    }
+
+    fn scroll_back(&mut self, rows: isize) -> result!(()) {
+        self.follow = false;
+        self.scroll_by(-rows)
+    }
+
+    fn goto(&mut self, line: usize) -> result!(()) {
+        self.follow = false;
+        self.goto_line(line)
+    }
+
+    fn scroll_horizontally(&mut self, right: bool) -> result!(()) {
+        if !self.wrap {
+            let step = cmp::max(usize::from(self.pad.size()?.columns) / 2, 1);
+
+            self.scroll_x = if right { self.scroll_x + step } else { self.scroll_x.saturating_sub(step) };
+        }
+
+        Ok(())
+    }
+
+    fn repeat_search(&mut self, same_direction: bool) -> result!(()) {
+        match &self.search {
+            Some(search) => {
+                let forward = search.forward == same_direction;
+
+                self.find(forward)?;
+            },
+            None         => self.message = Some("No previous search".to_string())
+        }
+
+        Ok(())
+    }
+
+    // find the next line matching the search from the first visible line.
+    fn find(&mut self, forward: bool) -> result!(bool) {
+        let search = match &self.search {
+            Some(search) => search.clone(),
+            None         => return Ok(false)
+        };
+
+        let found = if forward {
+            (self.top + 1..self.lines.len()).find(|&line| !self.matches(&self.lines[line]).is_empty())
+        } else {
+            (0..self.top).rev().find(|&line| !self.matches(&self.lines[line]).is_empty())
+        };
+
+        match found {
+            Some(line) => {
+                self.goto(line)?;
+
+                Ok(true)
+            },
+            None       => {
+                self.message = Some(format!("Pattern not found: {}", search.pattern));
+
+                Ok(false)
+            }
+        }
+    }
+
+    // the byte ranges of the matches of the search in a line.
+    fn matches(&self, line: &str) -> Vec<std::ops::Range<usize>> {
+        match &self.search {
+            Some(search) => find_matches(line, &search.pattern),
+            None         => vec!()
+        }
+    }
+
+    fn status(&self) -> result!(String) {
+        let (last, _) = self.last_visible()?;
+        let mut status = if self.lines.is_empty() {
+            "(empty)".to_string()
+        } else {
+            format!("lines {}-{}/{} {}%", self.top + 1, last + 1, self.lines.len(), self.percentage()?)
+        };
+
+        if self.last_page_top()? <= (self.top, self.top_row) {
+            status.push_str(" (END)");
+        }
+
+        if self.follow {
+            status.push_str(" [following]");
+        }
+
+        Ok(status)
+    }
+
+    // the number of lines of text shown above the status line.
+    fn page(&self) -> result!(usize) {
+        Ok(usize::from(self.pad.size()?.lines.saturating_sub(1)))
+    }
+
+    // the number of rows a line is displayed on.
+    fn row_count(&self, line: usize, columns: u16) -> usize {
+        match self.lines.get(line) {
+            Some(text) if self.wrap => row_breaks(text, Some(columns)).len(),
+            _                       => 1
+        }
+    }
+
+    // the line and row of the last visible row.
+    fn last_visible(&self) -> result!((usize, usize)) {
+        let columns = self.pad.size()?.columns;
+        let (mut line, mut row) = (self.top, self.top_row);
+
+        for _ in 1..cmp::max(self.page()?, 1) {
+            if row + 1 < self.row_count(line, columns) {
+                row += 1;
+            } else if line + 1 < self.lines.len() {
+                line += 1;
+                row = 0;
+            } else {
+                break;
+            }
+        }
+
+        Ok((cmp::min(line, self.lines.len().saturating_sub(1)), row))
+    }
+
+    // the first line and row of the last page of rows.
+    fn last_page_top(&self) -> result!((usize, usize)) {
+        let columns = self.pad.size()?.columns;
+        let mut rows = self.page()?;
+        let mut line = self.lines.len();
+
+        while line > 0 {
+            let count = self.row_count(line - 1, columns);
+
+            if count >= rows {
+                return Ok((line - 1, count - rows));
+            }
+
+            rows -= count;
+            line -= 1;
+        }
+
+        Ok((0, 0))
+    }
+
+    // clamp the first visible line and row to the last page.
+    fn clamp(&mut self) -> result!(()) {
+        let last = self.last_page_top()?;
+
+        if (self.top, self.top_row) > last {
+            (self.top, self.top_row) = last;
+        }
+
+        Ok(())
+    }
+
+    fn screen_max(&self, screen_origin: Origin) -> result!(Origin) {
+        let size = self.pad.size()?;
+
+        Ok(Origin {
+            y: screen_origin.y + size.lines.saturating_sub(1),
+            x: screen_origin.x + size.columns.saturating_sub(1)
+        })
+    }
+}
+
+impl Drop for Pager {
+    fn drop(&mut self) {
+        self.alive.store(false, Ordering::Relaxed);
+    }
+}
+
+impl fmt::Debug for Pager {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Pager {{ pad: {:?}, lines: {}, top: {}, top_row: {}, wrap: {}, follow: {}, search: {:?} }}", self.pad, self.lines.len(), self.top, self.top_row, self.wrap, self.follow, self.search)
+    }
+}
+
+// read lines from a source on a thread, at the end of the source it's
+// checked for more data until the pager is dropped.
+fn spawn_reader<R: BufRead + Send + 'static>(mut reader: R, alive: Arc<AtomicBool>) -> Receiver<SourceLine> {
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        let mut buffer = vec!();
+
+        while alive.load(Ordering::Relaxed) {
+            match reader.read_until(b'\n', &mut buffer) {
+                Ok(0)                                              => thread::sleep(FOLLOW_INTERVAL),
+                Ok(_)                                              => {
+                    let complete = buffer.last() == Some(&b'\n');
+                    let line = String::from_utf8_lossy(&buffer).trim_end_matches(['\n', '\r']).to_string();
+
+                    if sender.send((line, complete)).is_err() {
+                        break;
+                    }
+
+                    // an incomplete last line is sent again when the rest of it arrives.
+                    if complete {
+                        buffer.clear();
+                    }
+                },
+                Err(source) if source.kind() == ErrorKind::Interrupted => { },
+                Err(_)                                             => break
+            }
+        }
+    });
+
+    receiver
+}
+
+// a line with tabs expanded and control characters shown as `^X`.
+fn display_line(line: &str) -> String {
+    let mut display = String::with_capacity(line.len());
+    let mut width = 0;
+
+    for ch in line.trim_end_matches('\r').chars() {
+        match ch {
+            '\t'                                  => {
+                let spaces = usize::from(TAB_SIZE) - width % usize::from(TAB_SIZE);
+
+                display.extend(std::iter::repeat_n(' ', spaces));
+                width += spaces;
+            },
+            _ if ch.is_control() && (ch as u32) < 0x80 => {
+                display.push('^');
+                display.push(char::from((ch as u8) ^ 0x40));
+                width += 2;
+            },
+            _                                     => {
+                display.push(ch);
+                width += usize::from(char_width(ch));
+            }
+        }
+    }
+
+    display
+}
+
+// the byte offsets of the start of each row of a line wrapped at a number of columns.
+fn row_breaks(line: &str, columns: Option<u16>) -> Vec<usize> {
+    let mut breaks = vec!(0);
+
+    if let Some(columns) = columns {
+        let columns = cmp::max(columns, 1);
+        let mut width = 0;
+
+        for (index, ch) in line.char_indices() {
+            let ch_width = char_width(ch);
+
+            if width + ch_width > columns && width > 0 {
+                breaks.push(index);
+                width = 0;
+            }
+
+            width += ch_width;
+        }
+    }
+
+    breaks
+}
+
+// the byte ranges of a pattern in a line, a pattern without upper case
+// letters matches either case.
+fn find_matches(line: &str, pattern: &str) -> Vec<std::ops::Range<usize>> {
+    if pattern.is_empty() {
+        return vec!();
+    }
+
+    if pattern.chars().any(|ch| ch.is_uppercase()) {
+        line.match_indices(pattern).map(|(index, str)| index..index + str.len()).collect()
+    } else {
+        // ascii lower casing keeps the byte offsets of the line.
+        line.to_ascii_lowercase().match_indices(&pattern.to_ascii_lowercase()).map(|(index, str)| index..index + str.len()).collect()
+    }
+}
+
+#[test]
+fn pager_test() {
+    assert!(matches!(
+        Pager::from_file(Size { lines: 1, columns: 1 }, "/nonexistent/pager.txt"),
+        Err(NCurseswWinError::IOError { source }) if source.kind() == ErrorKind::NotFound
+    ));
+    assert_eq!(display_line("a\tb\x01"), "a       b^A");
+    assert_eq!(row_breaks("abcdefgh", Some(3)), vec!(0, 3, 6));
+    assert_eq!(row_breaks("abcdefgh", None), vec!(0));
+    assert_eq!(find_matches("Foo foo FOO", "foo"), vec!(0..3, 4..7, 8..11));
+    assert_eq!(find_matches("Foo foo FOO", "Foo"), vec!(0..3));
+}
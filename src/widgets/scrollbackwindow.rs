@@ -0,0 +1,388 @@
+/*
+    src/widgets/scrollbackwindow.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{cmp, fmt, mem, collections::VecDeque};
+use ncursesw::ComplexString;
+use crate::{
+    Origin, Size, NCurseswWinError, Pad, IsPad, HasYXAxis, BaseCanvas, Scrollable,
+    HasAddFunctions, HasMvAddFunctions, HasMvInFunctions, Mouse, CharacterResult,
+    WideChar, KeyBinding,
+    widgets::{ScrollTarget, ScrollAxis, ScrollState, funcs::{wheel_rows, region_origin}},
+    funcs::{TAB_SIZE, char_width}
+};
+
+/// A scrolling window that keeps the lines scrolled off it's top.
+///
+/// Output is written to a scrolling pad of the window's size with `addstr()`,
+/// before a line scrolls off the top it's read back with `in_wchnstr()` and
+/// kept in a ring buffer of styled lines up to a capacity. The window shows
+/// live output until it's scrolled back through the history with the
+/// `ScrollBackward` and `ScrollForward` keys (the shifted up and down arrows
+/// on most terminals), page up/down or the mouse wheel, home shows the oldest
+/// line and end returns to live output. While scrolled back the view stays on
+/// the same lines as further output arrives.
+pub struct ScrollbackWindow {
+    live:     Pad,                      // the live output.
+    view:     Pad,                      // the view of the history when scrolled back.
+    history:  VecDeque<ComplexString>,  // the lines scrolled off the top, oldest first.
+    capacity: usize,                    // the maximum number of history lines.
+    offset:   usize                     // the number of lines scrolled back from live output.
+}
+
+impl ScrollbackWindow {
+    /// Create a new scrollback window of a size keeping up to a capacity of history lines.
+    pub fn new(size: Size, capacity: usize) -> result!(Self) {
+        let live = Pad::new(size)?;
+
+        live.scrollok(true)?;
+
+        Ok(Self { live, view: Pad::new(size)?, history: VecDeque::new(), capacity, offset: 0 })
+    }
+
+    /// The size of the window.
+    pub fn size(&self) -> result!(Size) {
+        self.live.size()
+    }
+
+    /// Resize the window, lines and columns of live output outside the new size are lost.
+    pub fn resize(&mut self, size: Size) -> result!(()) {
+        self.live.resize(size)?;
+        self.view.resize(size)?;
+        self.offset = cmp::min(self.offset, self.history.len());
+
+        Ok(())
+    }
+
+    /// The pad the live output is written to, anything written directly to
+    /// the pad that scrolls it is not kept in the history.
+    pub fn pad(&self) -> &Pad {
+        &self.live
+    }
+
+    /// The lines scrolled off the top of the window, oldest first.
+    pub fn history(&self) -> &VecDeque<ComplexString> {
+        &self.history
+    }
+
+    /// The maximum number of history lines.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Set the maximum number of history lines, dropping the oldest lines over the capacity.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.trim_history();
+    }
+
+    /// Discard the history.
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+        self.offset = 0;
+    }
+
+    /// The number of lines scrolled back from live output.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Is the window showing live output.
+    pub fn is_live(&self) -> bool {
+        self.offset == 0
+    }
+
+    /// Scroll back a number of lines from live output, clamped to the history.
+    pub fn scroll_to(&mut self, offset: usize) {
+        self.offset = cmp::min(offset, self.history.len());
+    }
+
+    /// Scroll by a number of lines, a negative number scrolls back through the history.
+    pub fn scroll_by(&mut self, lines: isize) {
+        let offset = if lines < 0 {
+            self.offset.saturating_add(lines.unsigned_abs())
+        } else {
+            self.offset.saturating_sub(lines as usize)
+        };
+
+        self.scroll_to(offset);
+    }
+
+    /// Return to live output.
+    pub fn scroll_to_live(&mut self) {
+        self.offset = 0;
+    }
+
+    /// Write a string to the window at the cursor keeping any lines scrolled
+    /// off the top in the history.
+    pub fn addstr(&mut self, str: &str) -> result!(()) {
+        let size = self.live.size()?;
+        let mut cursor = self.live.cursor()?;
+        let mut segment = String::new();
+
+        // write the string in segments up to a character that scrolls the pad,
+        // that character is written so the top line is read before it's lost.
+        for ch in str.chars() {
+            let (next, scrolls) = advance(cursor, ch, size);
+
+            if scrolls == 0 {
+                segment.push(ch);
+                cursor = next;
+            } else {
+                if !segment.is_empty() {
+                    self.live.addstr(mem::take(&mut segment))?;
+                }
+
+                self.add_scrolling_char(ch, cursor, size)?;
+                cursor = self.live.cursor()?;
+            }
+        }
+
+        if !segment.is_empty() {
+            self.live.addstr(segment)?;
+        }
+
+        Ok(())
+    }
+
+    /// Handle a key returning `true` if the key was used by the window.
+    pub fn handle_key(&mut self, key: CharacterResult<WideChar>) -> result!(bool) {
+        let page = cmp::max(self.live.size()?.lines, 1) as isize;
+
+        match key {
+            CharacterResult::Key(KeyBinding::ScrollBackward) => self.scroll_by(-1),
+            CharacterResult::Key(KeyBinding::ScrollForward)  => self.scroll_by(1),
+            CharacterResult::Key(KeyBinding::PreviousPage)   => self.scroll_by(-page),
+            CharacterResult::Key(KeyBinding::NextPage)       => self.scroll_by(page),
+            CharacterResult::Key(KeyBinding::Home)           => self.scroll_to(usize::MAX),
+            CharacterResult::Key(KeyBinding::End)            => self.scroll_to_live(),
+            _                                                => return Ok(false)
+        }
+
+        Ok(true)
+    }
+
+    /// Handle a mouse event with the window displayed at the screen origin,
+    /// returns `true` if the event was used by the window.
+    pub fn handle_mouse(&mut self, mouse: &Mouse, screen_origin: Origin) -> result!(bool) {
+        if region_origin(mouse.origin()?.origin(), screen_origin, self.size()?).is_none() {
+            return Ok(false);
+        }
+
+        match wheel_rows(mouse.events()) {
+            Some(rows) => {
+                self.scroll_by(rows);
+
+                Ok(true)
+            },
+            None       => Ok(false)
+        }
+    }
+
+    /// Refresh the window onto the screen at the screen origin.
+    pub fn refresh(&mut self, screen_origin: Origin) -> result!(()) {
+        let pad = self.render()?;
+
+        pad.prefresh(Origin::default(), screen_origin, self.screen_max(screen_origin)?)
+    }
+
+    /// Copy the window to the virtual screen at the screen origin, call
+    /// `doupdate()` to update the physical screen.
+    pub fn noutrefresh(&mut self, screen_origin: Origin) -> result!(()) {
+        let pad = self.render()?;
+
+        pad.pnoutrefresh(Origin::default(), screen_origin, self.screen_max(screen_origin)?)
+    }
+
+    // render the view when scrolled back returning the pad to be shown.
+    fn render(&self) -> result!(&Pad) {
+        if self.offset == 0 {
+            return Ok(&self.live);
+        }
+
+        let size = self.live.size()?;
+        let first = self.history.len() - self.offset;
+
+        self.view.erase()?;
+
+        for y in 0..size.lines {
+            let index = first + usize::from(y);
+            let origin = Origin { y, x: 0 };
+
+            if let Some(line) = self.history.get(index) {
+                self.view.mvadd_wchnstr(origin, line, Some(cmp::min(u16::try_from(line.len()).unwrap_or(u16::MAX), size.columns)))?;
+            } else {
+                let live_line = u16::try_from(index - self.history.len())?;
+
+                self.view.mvadd_wchnstr(origin, &self.live.mvin_wchnstr(Origin { y: live_line, x: 0 }, size.columns)?, None)?;
+            }
+        }
+
+        Ok(&self.view)
+    }
+
+    // write a character that scrolls the pad keeping the top line in the history.
+    fn add_scrolling_char(&mut self, ch: char, cursor: Origin, size: Size) -> result!(()) {
+        let width = if ch.is_control() { 2 } else { char_width(ch) };
+
+        if ch == '\n' {
+            self.live.clrtoeol()?;
+            self.scroll_line(size)
+        } else if ch != '\t' && cursor.x + width > size.columns {
+            // the character wraps to the next line before it's written.
+            self.scroll_line(size)?;
+            self.addstr(&ch.to_string())
+        } else {
+            // the character is written in the last column, without scrolling
+            // enabled ncurses writes it and fails to wrap to the next line.
+            self.live.scrollok(false)?;
+            let _ = self.live.addstr(ch.to_string());
+            self.live.scrollok(true)?;
+
+            self.scroll_line(size)
+        }
+    }
+
+    // keep the top line of the live pad and scroll it off leaving the cursor
+    // at the start of the last line.
+    fn scroll_line(&mut self, size: Size) -> result!(()) {
+        self.history.push_back(self.live.mvin_wchnstr(Origin::default(), size.columns)?);
+
+        // keep the view on the same lines while scrolled back.
+        if self.offset > 0 {
+            self.offset += 1;
+        }
+
+        self.trim_history();
+
+        self.live.scrl(1)?;
+        self.live.set_cursor(Origin { y: size.lines.saturating_sub(1), x: 0 })
+    }
+
+    fn trim_history(&mut self) {
+        while self.history.len() > self.capacity {
+            self.history.pop_front();
+        }
+
+        self.offset = cmp::min(self.offset, self.history.len());
+    }
+
+    fn screen_max(&self, screen_origin: Origin) -> result!(Origin) {
+        let size = self.live.size()?;
+
+        Ok(Origin {
+            y: screen_origin.y + size.lines.saturating_sub(1),
+            x: screen_origin.x + size.columns.saturating_sub(1)
+        })
+    }
+}
+
+impl ScrollTarget for ScrollbackWindow {
+    fn scroll_state(&self, axis: ScrollAxis) -> result!(ScrollState) {
+        let size = self.size()?;
+
+        Ok(match axis {
+            ScrollAxis::Vertical   => ScrollState::new(self.history.len() + usize::from(size.lines), usize::from(size.lines), self.history.len() - self.offset),
+            ScrollAxis::Horizontal => ScrollState::new(usize::from(size.columns), usize::from(size.columns), 0)
+        })
+    }
+
+    fn set_scroll_position(&mut self, axis: ScrollAxis, position: usize) -> result!(()) {
+        if axis == ScrollAxis::Vertical {
+            self.scroll_to(self.history.len().saturating_sub(position));
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for ScrollbackWindow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ScrollbackWindow {{ live: {:?}, view: {:?}, history: {}, capacity: {}, offset: {} }}", self.live, self.view, self.history.len(), self.capacity, self.offset)
+    }
+}
+
+// advance the cursor of a scrolling window of a size over a character as
+// `waddch()` would, returning the new cursor and the number of times the
+// window scrolls.
+fn advance(cursor: Origin, ch: char, size: Size) -> (Origin, u16) {
+    let mut cursor = cursor;
+    let mut scrolls = 0;
+
+    let mut new_line = |cursor: &mut Origin| {
+        cursor.x = 0;
+
+        if cursor.y + 1 >= size.lines {
+            scrolls += 1;
+        } else {
+            cursor.y += 1;
+        }
+    };
+
+    match ch {
+        '\n'   => new_line(&mut cursor),
+        '\r'   => cursor.x = 0,
+        '\x08' => cursor.x = cursor.x.saturating_sub(1),
+        '\t'   => {
+            // a tab is written as spaces to the next tab stop.
+            loop {
+                cursor.x += 1;
+
+                if cursor.x >= size.columns {
+                    new_line(&mut cursor);
+
+                    break;
+                } else if cursor.x.is_multiple_of(TAB_SIZE) {
+                    break;
+                }
+            }
+        },
+        _      => {
+            // control characters are written as ^X.
+            let width = if ch.is_control() { 2 } else { char_width(ch) };
+
+            if width > 0 {
+                if cursor.x + width > size.columns {
+                    new_line(&mut cursor);
+                }
+
+                cursor.x += width;
+
+                if cursor.x >= size.columns {
+                    new_line(&mut cursor);
+                }
+            }
+        }
+    }
+
+    (cursor, scrolls)
+}
+
+#[test]
+fn scrollback_window_test() {
+    let size = Size { lines: 3, columns: 10 };
+
+    assert_eq!(advance(Origin { y: 1, x: 4 }, '\n', size), (Origin { y: 2, x: 0 }, 0));
+    assert_eq!(advance(Origin { y: 2, x: 4 }, '\n', size), (Origin { y: 2, x: 0 }, 1));
+    assert_eq!(advance(Origin { y: 2, x: 9 }, 'a', size), (Origin { y: 2, x: 0 }, 1));
+    assert_eq!(advance(Origin { y: 0, x: 9 }, '漢', size), (Origin { y: 1, x: 2 }, 0));
+    assert_eq!(advance(Origin { y: 0, x: 3 }, '\t', size), (Origin { y: 0, x: 8 }, 0));
+}
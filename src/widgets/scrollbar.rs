@@ -0,0 +1,325 @@
+/*
+    src/widgets/scrollbar.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::cmp;
+use ncursesw::{AttributesColorPairSet, ComplexChar};
+use crate::{
+    Origin, Size, NCurseswWinError, Window, HasYXAxis, PadViewport, Mouse, MouseButton,
+    MouseButtonEvent, MouseButtonState, BoxDrawingType, BoxDrawingTypeDetail, BoxDrawingGraphic,
+    widgets::funcs::region_origin,
+    funcs::{complex_box_char, complex_char, default_attributes_color_pair, mvadd_complex_chars}
+};
+
+// the track and thumb characters of block scrollbars.
+const BLOCK_TRACK: char = '░';
+const BLOCK_THUMB: char = '█';
+
+/// The axis a `Scrollbar` scrolls along.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ScrollAxis {
+    /// Scroll through lines with a vertical scrollbar.
+    Vertical,
+    /// Scroll through columns with a horizontal scrollbar.
+    Horizontal
+}
+
+/// The characters a `Scrollbar` is drawn with.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ScrollbarSymbols {
+    /// A track of box drawing lines of a type with a heavy line thumb.
+    Line(BoxDrawingType),
+    /// A track of shaded blocks with a full block thumb.
+    Block
+}
+
+/// The scroll state along an axis of content shown in a viewport.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ScrollState {
+    /// The length of the content.
+    pub content:  usize,
+    /// The length of the viewport.
+    pub viewport: usize,
+    /// The position of the start of the viewport within the content.
+    pub position: usize
+}
+
+impl ScrollState {
+    /// Create a new scroll state.
+    pub fn new(content: usize, viewport: usize, position: usize) -> Self {
+        Self { content, viewport, position }
+    }
+
+    /// The largest position that shows a full viewport of content.
+    pub fn max_position(&self) -> usize {
+        self.content.saturating_sub(self.viewport)
+    }
+}
+
+/// Content that can be scrolled by a `Scrollbar`.
+pub trait ScrollTarget {
+    /// The scroll state along an axis.
+    fn scroll_state(&self, axis: ScrollAxis) -> result!(ScrollState);
+
+    /// Scroll to a position along an axis.
+    fn set_scroll_position(&mut self, axis: ScrollAxis, position: usize) -> result!(());
+}
+
+/// A scroll state is a scroll target along both axis for any window with a known content length.
+impl ScrollTarget for ScrollState {
+    fn scroll_state(&self, _: ScrollAxis) -> result!(ScrollState) {
+        Ok(*self)
+    }
+
+    fn set_scroll_position(&mut self, _: ScrollAxis, position: usize) -> result!(()) {
+        self.position = cmp::min(position, self.max_position());
+
+        Ok(())
+    }
+}
+
+impl ScrollTarget for PadViewport {
+    fn scroll_state(&self, axis: ScrollAxis) -> result!(ScrollState) {
+        let content = self.content_size()?;
+        let size = self.size();
+        let scroll = self.scroll();
+
+        Ok(match axis {
+            ScrollAxis::Vertical   => ScrollState::new(usize::from(content.lines), usize::from(size.lines), usize::from(scroll.y)),
+            ScrollAxis::Horizontal => ScrollState::new(usize::from(content.columns), usize::from(size.columns), usize::from(scroll.x))
+        })
+    }
+
+    fn set_scroll_position(&mut self, axis: ScrollAxis, position: usize) -> result!(()) {
+        let position = u16::try_from(position).unwrap_or(u16::MAX);
+        let scroll = self.scroll();
+
+        self.scroll_to(match axis {
+            ScrollAxis::Vertical   => Origin { y: position, ..scroll },
+            ScrollAxis::Horizontal => Origin { x: position, ..scroll }
+        }).map(|_| ())
+    }
+}
+
+/// A vertical or horizontal scrollbar with a thumb proportional to the
+/// visible part of the content of a `ScrollTarget`.
+///
+/// `render()` only draws the scrollbar when the scroll state of the target
+/// or it's position has changed since it was last drawn, so it can be
+/// called on every loop of an event loop. A button 1 press on the track
+/// before or after the thumb scrolls by a page and dragging the thumb
+/// scrolls the target.
+#[derive(Clone, Debug)]
+pub struct Scrollbar {
+    axis:        ScrollAxis,
+    symbols:     ScrollbarSymbols,
+    style:       Option<AttributesColorPairSet>,
+    thumb_style: Option<AttributesColorPairSet>,
+    drawn:       Option<(Origin, u16, ScrollState)>, // the screen origin, length and state last drawn.
+    drag:        Option<u16>                          // the offset of the mouse into the thumb being dragged.
+}
+
+impl Scrollbar {
+    /// Create a new scrollbar along an axis.
+    pub fn new(axis: ScrollAxis) -> Self {
+        Self {
+            axis,
+            symbols:     ScrollbarSymbols::Line(BoxDrawingType::Light(BoxDrawingTypeDetail::Normal)),
+            style:       None,
+            thumb_style: None,
+            drawn:       None,
+            drag:        None
+        }
+    }
+
+    /// The axis of the scrollbar.
+    pub fn axis(&self) -> ScrollAxis {
+        self.axis
+    }
+
+    /// Set the characters the scrollbar is drawn with.
+    pub fn set_symbols(&mut self, symbols: ScrollbarSymbols) {
+        self.symbols = symbols;
+        self.invalidate();
+    }
+
+    /// Set the attributes and color pair of the track.
+    pub fn set_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.style = style;
+        self.invalidate();
+    }
+
+    /// Set the attributes and color pair of the thumb.
+    pub fn set_thumb_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.thumb_style = style;
+        self.invalidate();
+    }
+
+    /// Draw the scrollbar on the next `render()` even if nothing has changed,
+    /// for example after the window has been cleared.
+    pub fn invalidate(&mut self) {
+        self.drawn = None;
+    }
+
+    /// Draw the scrollbar of a length at an origin of a window if the
+    /// target's scroll state has changed, returning `true` if it was drawn.
+    pub fn render<T>(&mut self, window: &Window, origin: Origin, length: u16, target: &T) -> result!(bool)
+        where T: ScrollTarget + ?Sized
+    {
+        let state = target.scroll_state(self.axis)?;
+        let window_origin = window.origin()?;
+        let screen_origin = Origin { y: window_origin.y + origin.y, x: window_origin.x + origin.x };
+
+        if self.drawn == Some((screen_origin, length, state)) {
+            return Ok(false);
+        }
+
+        let style = self.style.unwrap_or_else(default_attributes_color_pair);
+        let thumb_style = self.thumb_style.unwrap_or(style);
+        let (track, thumb) = match self.symbols {
+            ScrollbarSymbols::Line(box_drawing_type) => {
+                let graphic = match self.axis {
+                    ScrollAxis::Vertical   => BoxDrawingGraphic::VerticalLine,
+                    ScrollAxis::Horizontal => BoxDrawingGraphic::HorizontalLine
+                };
+
+                (
+                    complex_box_char(box_drawing_type, graphic, style)?,
+                    complex_box_char(BoxDrawingType::Heavy(BoxDrawingTypeDetail::Normal), graphic, thumb_style)?
+                )
+            },
+            ScrollbarSymbols::Block                  => (complex_char(BLOCK_TRACK, style)?, complex_char(BLOCK_THUMB, thumb_style)?)
+        };
+
+        let (thumb_start, thumb_length) = thumb_geometry(length, state);
+        let chars: Vec<ComplexChar> = (0..length).map(|i| {
+            if i >= thumb_start && i < thumb_start + thumb_length { thumb } else { track }
+        }).collect();
+
+        match self.axis {
+            ScrollAxis::Vertical   => {
+                for (y, ch) in chars.into_iter().enumerate() {
+                    mvadd_complex_chars(window, Origin { y: origin.y + u16::try_from(y)?, x: origin.x }, &[ch])?;
+                }
+            },
+            ScrollAxis::Horizontal => mvadd_complex_chars(window, origin, &chars)?
+        }
+
+        self.drawn = Some((screen_origin, length, state));
+
+        Ok(true)
+    }
+
+    /// Handle a mouse event on the scrollbar as last drawn, scrolling the
+    /// target and returning `true` if the event was used by the scrollbar.
+    pub fn handle_mouse<T>(&mut self, mouse: &Mouse, target: &mut T) -> result!(bool)
+        where T: ScrollTarget + ?Sized
+    {
+        let (screen_origin, length, _) = match self.drawn {
+            Some(drawn) => drawn,
+            None        => return Ok(false)
+        };
+        let events = mouse.events();
+        let pressed = events.is_button_state(MouseButtonState::new(MouseButton::One, MouseButtonEvent::Pressed));
+        let released = events.is_button_state(MouseButtonState::new(MouseButton::One, MouseButtonEvent::Released));
+        let state = target.scroll_state(self.axis)?;
+        let (thumb_start, thumb_length) = thumb_geometry(length, state);
+
+        let size = match self.axis {
+            ScrollAxis::Vertical   => Size { lines: length, columns: 1 },
+            ScrollAxis::Horizontal => Size { lines: 1, columns: length }
+        };
+        let mouse_origin = mouse.origin()?.origin();
+
+        // the offset along the track, while dragging the mouse may leave the track.
+        let offset = match region_origin(mouse_origin, screen_origin, size) {
+            Some(origin)               => match self.axis {
+                ScrollAxis::Vertical   => origin.y,
+                ScrollAxis::Horizontal => origin.x
+            },
+            None if self.drag.is_some() => match self.axis {
+                ScrollAxis::Vertical   => mouse_origin.y.saturating_sub(screen_origin.y),
+                ScrollAxis::Horizontal => mouse_origin.x.saturating_sub(screen_origin.x)
+            },
+            None                       => return Ok(false)
+        };
+
+        if let Some(grab) = self.drag {
+            if released {
+                self.drag = None;
+            }
+
+            target.set_scroll_position(self.axis, thumb_position(length, state, offset.saturating_sub(grab)))?;
+        } else if pressed {
+            if offset < thumb_start {
+                target.set_scroll_position(self.axis, state.position.saturating_sub(state.viewport))?;
+            } else if offset >= thumb_start + thumb_length {
+                target.set_scroll_position(self.axis, cmp::min(state.position + state.viewport, state.max_position()))?;
+            } else {
+                self.drag = Some(offset - thumb_start);
+            }
+        } else {
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+}
+
+// the start and length of the thumb on a track of a length.
+fn thumb_geometry(length: u16, state: ScrollState) -> (u16, u16) {
+    if state.content <= state.viewport || length == 0 {
+        return (0, length);
+    }
+
+    let track = length as u64;
+    let thumb = cmp::min(cmp::max((track * state.viewport as u64 + state.content as u64 / 2) / state.content as u64, 1), track);
+    let max_position = state.max_position() as u64;
+    let start = ((track - thumb) * cmp::min(state.position as u64, max_position) + max_position / 2) / max_position;
+
+    (start as u16, thumb as u16)
+}
+
+// the scroll position of a thumb starting at an offset of a track of a length.
+fn thumb_position(length: u16, state: ScrollState, start: u16) -> usize {
+    let (_, thumb) = thumb_geometry(length, state);
+    let travel = u64::from(length.saturating_sub(thumb));
+
+    if travel == 0 {
+        return 0;
+    }
+
+    let max_position = state.max_position() as u64;
+
+    ((cmp::min(u64::from(start), travel) * max_position + travel / 2) / travel) as usize
+}
+
+#[test]
+fn scrollbar_test() {
+    let state = ScrollState::new(100, 20, 0);
+
+    assert_eq!(thumb_geometry(10, state), (0, 2));
+    assert_eq!(thumb_geometry(10, ScrollState { position: 80, ..state }), (8, 2));
+    assert_eq!(thumb_geometry(10, ScrollState { position: 40, ..state }), (4, 2));
+    assert_eq!(thumb_geometry(10, ScrollState::new(5, 20, 0)), (0, 10));
+    assert_eq!(thumb_position(10, state, 4), 40);
+    assert_eq!(thumb_position(10, state, 9), 80);
+}
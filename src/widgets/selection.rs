@@ -0,0 +1,151 @@
+/*
+    src/widgets/selection.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::cmp;
+use crate::KeyBinding;
+
+// the selected index and the index of the first visible row of a scrolling list of rows.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub(in crate::widgets) struct Selection {
+    count:    usize,         // the number of rows.
+    selected: Option<usize>, // the selected row.
+    top:      usize          // the first visible row.
+}
+
+impl Selection {
+    pub(in crate::widgets) fn new(count: usize) -> Self {
+        Self { count, selected: if count > 0 { Some(0) } else { None }, top: 0 }
+    }
+
+    pub(in crate::widgets) fn count(&self) -> usize {
+        self.count
+    }
+
+    // set the number of rows, the selected and top rows are clamped to the new count.
+    pub(in crate::widgets) fn set_count(&mut self, count: usize, page: usize) {
+        self.count = count;
+        self.selected = if count == 0 {
+            None
+        } else {
+            Some(self.selected.map_or_else(|| 0, |selected| cmp::min(selected, count - 1)))
+        };
+        self.set_top(self.top, page);
+        self.ensure_visible(page);
+    }
+
+    pub(in crate::widgets) fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    // select a row scrolling it into view.
+    pub(in crate::widgets) fn select(&mut self, selected: Option<usize>, page: usize) {
+        self.selected = selected.filter(|_| self.count > 0).map(|selected| cmp::min(selected, self.count - 1));
+        self.ensure_visible(page);
+    }
+
+    pub(in crate::widgets) fn top(&self) -> usize {
+        self.top
+    }
+
+    // set the first visible row, this is clamped so that a page of rows is visible if possible.
+    pub(in crate::widgets) fn set_top(&mut self, top: usize, page: usize) {
+        self.top = cmp::min(top, self.count.saturating_sub(page));
+    }
+
+    // scroll the visible rows by a number of rows without changing the selected row.
+    pub(in crate::widgets) fn scroll_by(&mut self, rows: isize, page: usize) {
+        self.set_top(offset(self.top, rows), page);
+    }
+
+    // move the selected row by a number of rows.
+    pub(in crate::widgets) fn move_by(&mut self, rows: isize, page: usize) {
+        if self.count > 0 {
+            self.select(Some(self.selected.map_or_else(|| 0, |selected| offset(selected, rows))), page);
+        }
+    }
+
+    // the visible range of rows of a page.
+    pub(in crate::widgets) fn visible(&self, page: usize) -> std::ops::Range<usize> {
+        self.top..cmp::min(self.top + page, self.count)
+    }
+
+    // scroll so that the selected row is visible.
+    pub(in crate::widgets) fn ensure_visible(&mut self, page: usize) {
+        if let Some(selected) = self.selected {
+            if selected < self.top {
+                self.top = selected;
+            } else if page > 0 && selected >= self.top + page {
+                self.top = selected + 1 - page;
+            }
+        }
+    }
+
+    // move the selection with the up/down arrows, page up/down, home and end keys.
+    pub(in crate::widgets) fn handle_key(&mut self, key: KeyBinding, page: usize) -> bool {
+        let page_rows = cmp::max(page, 1) as isize;
+
+        match key {
+            KeyBinding::UpArrow      => self.move_by(-1, page),
+            KeyBinding::DownArrow    => self.move_by(1, page),
+            KeyBinding::PreviousPage => self.move_by(-page_rows, page),
+            KeyBinding::NextPage     => self.move_by(page_rows, page),
+            KeyBinding::Home         => self.select(Some(0), page),
+            KeyBinding::End          => self.select(Some(usize::MAX), page),
+            _                        => return false
+        }
+
+        true
+    }
+}
+
+fn offset(index: usize, rows: isize) -> usize {
+    if rows < 0 {
+        index.saturating_sub(rows.unsigned_abs())
+    } else {
+        index.saturating_add(rows as usize)
+    }
+}
+
+#[test]
+fn selection_test() {
+    let mut selection = Selection::new(100);
+
+    assert_eq!(selection.selected(), Some(0));
+    assert!(selection.handle_key(KeyBinding::NextPage, 10));
+    assert_eq!((selection.selected(), selection.top()), (Some(10), 1));
+    assert!(selection.handle_key(KeyBinding::End, 10));
+    assert_eq!((selection.selected(), selection.top()), (Some(99), 90));
+    assert_eq!(selection.visible(10), 90..100);
+
+    selection.scroll_by(-95, 10);
+
+    assert_eq!((selection.selected(), selection.top()), (Some(99), 0));
+
+    selection.set_count(5, 10);
+
+    assert_eq!((selection.selected(), selection.top()), (Some(4), 0));
+    assert!(!selection.handle_key(KeyBinding::Enter, 10));
+
+    selection.set_count(0, 10);
+
+    assert_eq!(selection.selected(), None);
+}
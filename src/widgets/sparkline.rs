@@ -0,0 +1,160 @@
+/*
+    src/widgets/sparkline.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use ncursesw::AttributesColorPairSet;
+use crate::{
+    Origin, Size, NCurseswWinError, HasMvAddFunctions,
+    widgets::{ColorScale, funcs::{scaled_ratio, ratio_eighths, eighths_char, VERTICAL_EIGHTHS}},
+    funcs::{complex_char, default_attributes_color_pair, mvadd_complex_chars}
+};
+
+/// A sparkline chart drawn with the lower block elements (`▁▂▃▄▅▆▇█`).
+///
+/// The most recent values (the end of the data) are drawn on the right hand side of
+/// the region, each value occupies one column and the lines of the region are stacked
+/// to give a higher resolution.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Sparkline {
+    data:        Vec<f64>,
+    max:         Option<f64>,
+    style:       Option<AttributesColorPairSet>,
+    color_scale: Option<ColorScale>
+}
+
+impl Sparkline {
+    /// Create a new sparkline of the data.
+    pub fn new(data: Vec<f64>) -> Self {
+        Self { data, ..Self::default() }
+    }
+
+    /// The data of the sparkline.
+    pub fn data(&self) -> &[f64] {
+        &self.data
+    }
+
+    /// Set the data of the sparkline.
+    pub fn set_data(&mut self, data: Vec<f64>) {
+        self.data = data;
+    }
+
+    /// Append a value to the end of the data, if the data exceeds `capacity`
+    /// values then the oldest values are removed.
+    pub fn push(&mut self, value: f64, capacity: usize) {
+        self.data.push(value);
+
+        if self.data.len() > capacity {
+            self.data.drain(..self.data.len() - capacity);
+        }
+    }
+
+    /// Set the value drawn as a full column, if `None` the maximum of the visible data is used.
+    pub fn set_max(&mut self, max: Option<f64>) {
+        self.max = max;
+    }
+
+    /// Set the attributes and color pair of the sparkline.
+    pub fn set_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.style = style;
+    }
+
+    /// Set the color scale, when set each column is colored by it's value.
+    pub fn set_color_scale(&mut self, color_scale: Option<ColorScale>) {
+        self.color_scale = color_scale;
+    }
+
+    /// Render the sparkline into the region of a window at origin of size.
+    pub fn render<W: HasMvAddFunctions>(&self, window: &W, origin: Origin, size: Size) -> result!(()) {
+        assert_origin!("render", window.size()?, origin);
+
+        if size.lines == 0 || size.columns == 0 {
+            return Ok(());
+        }
+
+        let visible = self.visible_data(size.columns);
+        let max = self.max_value(visible);
+        let style = self.style.unwrap_or_else(default_attributes_color_pair);
+        let offset = usize::from(size.columns) - visible.len();
+
+        for line in 0..size.lines {
+            // the number of lines below this line.
+            let cell = usize::from(size.lines - line - 1);
+            let mut chars = Vec::with_capacity(usize::from(size.columns));
+
+            for column in 0..usize::from(size.columns) {
+                let (ch, style) = if column < offset {
+                    (' ', style)
+                } else {
+                    let ratio = scaled_ratio(visible[column - offset], max);
+                    let eighths = ratio_eighths(ratio, size.lines);
+                    let style = self.color_scale.as_ref().map_or_else(|| style, |color_scale| color_scale.color(ratio));
+
+                    (eighths_char(&VERTICAL_EIGHTHS, eighths, cell), style)
+                };
+
+                chars.push(complex_char(ch, style)?);
+            }
+
+            mvadd_complex_chars(window, Origin { y: origin.y + line, x: origin.x }, &chars)?;
+        }
+
+        Ok(())
+    }
+
+    // the most recent values that fit in a number of columns.
+    fn visible_data(&self, columns: u16) -> &[f64] {
+        &self.data[self.data.len().saturating_sub(usize::from(columns))..]
+    }
+
+    // the value drawn as a full column.
+    fn max_value(&self, visible: &[f64]) -> f64 {
+        self.max.unwrap_or_else(|| visible.iter().copied().fold(0.0, f64::max))
+    }
+}
+
+#[test]
+fn sparkline_test() {
+    let mut sparkline = Sparkline::new(vec!(0.0; 4));
+
+    // all zero data draws empty columns.
+    assert!(sparkline.visible_data(0).is_empty());
+    assert_eq!(sparkline.visible_data(2).len(), 2);
+    assert_eq!(sparkline.visible_data(10).len(), 4);
+    assert_eq!(sparkline.max_value(sparkline.visible_data(4)), 0.0);
+    assert_eq!(ratio_eighths(scaled_ratio(0.0, 0.0), 2), 0);
+
+    sparkline.push(6.0, 3);
+    assert_eq!(sparkline.data(), &[0.0, 0.0, 6.0]);
+    assert_eq!(sparkline.max_value(sparkline.visible_data(3)), 6.0);
+    assert_eq!(sparkline.max_value(sparkline.visible_data(2)), 6.0);
+
+    // equal values are drawn as full columns.
+    sparkline.set_data(vec!(2.0, 2.0));
+    let eighths = ratio_eighths(scaled_ratio(2.0, sparkline.max_value(sparkline.visible_data(2))), 2);
+    assert_eq!(eighths, 16);
+    assert_eq!(eighths_char(&VERTICAL_EIGHTHS, eighths, 1), '\u{2588}');
+
+    sparkline.set_max(Some(8.0));
+    let eighths = ratio_eighths(scaled_ratio(2.0, sparkline.max_value(sparkline.visible_data(2))), 2);
+    assert_eq!(eighths, 4);
+    assert_eq!(eighths_char(&VERTICAL_EIGHTHS, eighths, 0), '\u{2584}');
+    assert_eq!(eighths_char(&VERTICAL_EIGHTHS, eighths, 1), ' ');
+}
@@ -0,0 +1,373 @@
+/*
+    src/widgets/splitpane.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::cmp;
+use ncursesw::AttributesColorPairSet;
+use crate::{
+    Origin, Size, NCurseswWinError, Window, NCurseswWindow, Derivable, HasYXAxis, Mouse,
+    MouseButton, MouseButtonEvent, MouseButtonState, CharacterResult, WideChar, KeyBinding,
+    BoxDrawingType, BoxDrawingTypeDetail, BoxDrawingGraphic,
+    widgets::funcs::region_origin,
+    funcs::{complex_box_char, default_attributes_color_pair, mvadd_complex_chars}
+};
+
+/// The direction a `SplitPane` divides it's region.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SplitDirection {
+    /// The panes are side by side with a vertical divider.
+    Horizontal,
+    /// The panes are one above the other with a horizontal divider.
+    Vertical
+}
+
+/// The minimum and maximum length of a pane of a `SplitPane`, in columns
+/// for a horizontal split and lines for a vertical split.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PaneLimits {
+    pub min: u16,
+    pub max: Option<u16>
+}
+
+/// A region divided into two panes by a divider.
+///
+/// The panes are either derived windows of a parent window or independent
+/// windows, they are recreated when the divider is moved or the region is
+/// resized so the windows returned by `first()` and `second()` should not be
+/// retained. The divider is dragged with mouse button 1 or moved with the
+/// shifted left and right arrows for a horizontal split and the
+/// `ScrollBackward` and `ScrollForward` keys (the shifted up and down arrows
+/// on most terminals) for a vertical split.
+#[derive(Debug)]
+pub struct SplitPane {
+    parent:        Option<Window>,
+    direction:     SplitDirection,
+    origin:        Origin,
+    size:          Size,
+    position:      u16,  // the length of the first pane.
+    ratio:         f64,  // the ratio of the first pane kept when the region is resized.
+    first_limits:  PaneLimits,
+    second_limits: PaneLimits,
+    border:        BoxDrawingType,
+    style:         Option<AttributesColorPairSet>,
+    dragging:      bool,
+    windows:       [Window; 3] // the first pane, the divider and the second pane.
+}
+
+impl SplitPane {
+    /// Create a new split pane of independent windows in a region of the screen.
+    pub fn new(origin: Origin, size: Size, direction: SplitDirection) -> result!(Self) {
+        Self::create(None, origin, size, direction)
+    }
+
+    /// Create a new split pane of windows derived from a parent window in a
+    /// region of the parent.
+    pub fn derived(parent: &Window, origin: Origin, size: Size, direction: SplitDirection) -> result!(Self) {
+        assert_origin!("derived", parent.size()?, origin);
+
+        Self::create(Some(parent.clone()), origin, size, direction)
+    }
+
+    /// The direction of the split.
+    pub fn direction(&self) -> SplitDirection {
+        self.direction
+    }
+
+    /// The window of the first pane, the left or top pane.
+    pub fn first(&self) -> &Window {
+        &self.windows[0]
+    }
+
+    /// The window of the second pane, the right or bottom pane.
+    pub fn second(&self) -> &Window {
+        &self.windows[2]
+    }
+
+    /// The length of the first pane.
+    pub fn position(&self) -> u16 {
+        self.position
+    }
+
+    /// Move the divider so the first pane is of a length, the length is
+    /// clamped to the limits of both panes and so neither pane is empty.
+    pub fn set_position(&mut self, position: u16) -> result!(()) {
+        let position = self.clamp_position(self.size, position);
+
+        if position != self.position {
+            self.layout(self.origin, self.size, position)?;
+            self.ratio = f64::from(position) / f64::from(cmp::max(self.length().saturating_sub(1), 1));
+        }
+
+        Ok(())
+    }
+
+    /// Set the limits of the first pane.
+    pub fn set_first_limits(&mut self, limits: PaneLimits) -> result!(()) {
+        self.first_limits = limits;
+
+        self.set_position(self.position)
+    }
+
+    /// Set the limits of the second pane.
+    pub fn set_second_limits(&mut self, limits: PaneLimits) -> result!(()) {
+        self.second_limits = limits;
+
+        self.set_position(self.position)
+    }
+
+    /// Set the box drawing type of the divider.
+    pub fn set_border(&mut self, border: BoxDrawingType) {
+        self.border = border;
+    }
+
+    /// Set the attributes and color pair of the divider.
+    pub fn set_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.style = style;
+    }
+
+    /// Move and resize the split pane's region, for example on a terminal
+    /// resize, the first pane keeps it's proportion of the region.
+    pub fn resize(&mut self, origin: Origin, size: Size) -> result!(()) {
+        let length = split_length(self.direction, size);
+        let position = self.clamp_position(size, (self.ratio * f64::from(length.saturating_sub(1))).round() as u16);
+
+        self.layout(origin, size, position)
+    }
+
+    /// Draw the divider.
+    pub fn draw_divider(&self) -> result!(()) {
+        let style = self.style.unwrap_or_else(default_attributes_color_pair);
+        let divider = &self.windows[1];
+        let size = divider.size()?;
+
+        match self.direction {
+            SplitDirection::Horizontal => {
+                let ch = complex_box_char(self.border, BoxDrawingGraphic::VerticalLine, style)?;
+
+                for y in 0..size.lines {
+                    mvadd_complex_chars(divider, Origin { y, x: 0 }, &[ch])?;
+                }
+            },
+            SplitDirection::Vertical   => {
+                let ch = complex_box_char(self.border, BoxDrawingGraphic::HorizontalLine, style)?;
+
+                mvadd_complex_chars(divider, Origin::default(), &vec!(ch; usize::from(size.columns)))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handle a key returning `true` if the divider was moved.
+    pub fn handle_key(&mut self, key: CharacterResult<WideChar>) -> result!(bool) {
+        let delta: i32 = match (self.direction, key) {
+            (SplitDirection::Horizontal, CharacterResult::Key(KeyBinding::ShiftLeftArrow))  => -1,
+            (SplitDirection::Horizontal, CharacterResult::Key(KeyBinding::ShiftRightArrow)) => 1,
+            (SplitDirection::Vertical, CharacterResult::Key(KeyBinding::ScrollBackward))    => -1,
+            (SplitDirection::Vertical, CharacterResult::Key(KeyBinding::ScrollForward))     => 1,
+            _                                                                               => return Ok(false)
+        };
+
+        let position = u16::try_from((i32::from(self.position) + delta).max(0))?;
+
+        self.set_position(position)?;
+
+        Ok(true)
+    }
+
+    /// Handle a mouse event returning `true` if the event was used to drag the divider.
+    pub fn handle_mouse(&mut self, mouse: &Mouse) -> result!(bool) {
+        let events = mouse.events();
+        let origin = mouse.origin()?.origin();
+        let screen_origin = self.screen_origin()?;
+
+        let region = match region_origin(origin, screen_origin, self.size) {
+            Some(region) => region,
+            None         => return Ok(self.dragging && self.end_drag(events))
+        };
+
+        let position = match self.direction {
+            SplitDirection::Horizontal => region.x,
+            SplitDirection::Vertical   => region.y
+        };
+
+        if events.is_button_state(MouseButtonState::new(MouseButton::One, MouseButtonEvent::Pressed)) && position == self.position {
+            self.dragging = true;
+
+            return Ok(true);
+        }
+
+        if self.dragging {
+            self.end_drag(events);
+            self.set_position(position)?;
+
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    fn create(parent: Option<Window>, origin: Origin, size: Size, direction: SplitDirection) -> result!(Self) {
+        let position = clamp_position(0, split_length(direction, size).saturating_sub(1), PaneLimits::default(), PaneLimits::default());
+        let windows = Self::create_windows(parent.as_ref(), &pane_geometry(direction, origin, size, position))?;
+        let mut split_pane = Self {
+            parent,
+            direction,
+            origin,
+            size,
+            position,
+            ratio:         0.5,
+            first_limits:  PaneLimits::default(),
+            second_limits: PaneLimits::default(),
+            border:        BoxDrawingType::Light(BoxDrawingTypeDetail::Normal),
+            style:         None,
+            dragging:      false,
+            windows
+        };
+
+        split_pane.resize(origin, size)?;
+
+        Ok(split_pane)
+    }
+
+    fn create_windows(parent: Option<&Window>, geometry: &[(Origin, Size); 3]) -> result!([Window; 3]) {
+        let create = |(origin, size): (Origin, Size)| {
+            // ncurses does not allow a window with no lines or columns.
+            let size = Size { lines: cmp::max(size.lines, 1), columns: cmp::max(size.columns, 1) };
+
+            match parent {
+                Some(parent) => parent.derwin(size, origin),
+                None         => Window::new(size, origin)
+            }
+        };
+
+        Ok([create(geometry[0])?, create(geometry[1])?, create(geometry[2])?])
+    }
+
+    // recreate the windows of the panes and divider, the region and position
+    // are only kept once the windows have been created.
+    fn layout(&mut self, origin: Origin, size: Size, position: u16) -> result!(()) {
+        self.windows = Self::create_windows(self.parent.as_ref(), &pane_geometry(self.direction, origin, size, position))?;
+        self.origin = origin;
+        self.size = size;
+        self.position = position;
+
+        Ok(())
+    }
+
+    fn length(&self) -> u16 {
+        split_length(self.direction, self.size)
+    }
+
+    fn clamp_position(&self, size: Size, position: u16) -> u16 {
+        clamp_position(position, split_length(self.direction, size).saturating_sub(1), self.first_limits, self.second_limits)
+    }
+
+    fn screen_origin(&self) -> result!(Origin) {
+        match &self.parent {
+            Some(parent) => {
+                let parent_origin = parent.origin()?;
+
+                Ok(Origin { y: parent_origin.y + self.origin.y, x: parent_origin.x + self.origin.x })
+            },
+            None         => Ok(self.origin)
+        }
+    }
+
+    fn end_drag(&mut self, events: crate::MouseEvents) -> bool {
+        if events.is_button_state(MouseButtonState::new(MouseButton::One, MouseButtonEvent::Released)) {
+            self.dragging = false;
+        }
+
+        true
+    }
+}
+
+// the length of a region in the direction of the split.
+fn split_length(direction: SplitDirection, size: Size) -> u16 {
+    match direction {
+        SplitDirection::Horizontal => size.columns,
+        SplitDirection::Vertical   => size.lines
+    }
+}
+
+// clamp the length of the first pane to the limits of both panes sharing a total length,
+// neither pane is shorter than 1 as ncurses does not allow a window with no lines or columns.
+fn clamp_position(position: u16, total: u16, first: PaneLimits, second: PaneLimits) -> u16 {
+    let lower = cmp::max(cmp::max(first.min, 1), second.max.map_or(0, |max| total.saturating_sub(max)));
+    let upper = cmp::min(first.max.unwrap_or(total), total.saturating_sub(cmp::max(second.min, 1)));
+
+    cmp::max(cmp::min(cmp::max(cmp::min(position, upper), lower), total.saturating_sub(1)), cmp::min(total, 1))
+}
+
+// the origin and size of the first pane, the divider and the second pane.
+fn pane_geometry(direction: SplitDirection, origin: Origin, size: Size, position: u16) -> [(Origin, Size); 3] {
+    match direction {
+        SplitDirection::Horizontal => {
+            let second = size.columns.saturating_sub(position + 1);
+
+            [
+                (origin, Size { columns: position, ..size }),
+                (Origin { x: origin.x + position, ..origin }, Size { columns: 1, ..size }),
+                (Origin { x: origin.x + position + 1, ..origin }, Size { columns: second, ..size })
+            ]
+        },
+        SplitDirection::Vertical   => {
+            let second = size.lines.saturating_sub(position + 1);
+
+            [
+                (origin, Size { lines: position, ..size }),
+                (Origin { y: origin.y + position, ..origin }, Size { lines: 1, ..size }),
+                (Origin { y: origin.y + position + 1, ..origin }, Size { lines: second, ..size })
+            ]
+        }
+    }
+}
+
+#[test]
+fn split_pane_test() {
+    let origin = Origin { y: 1, x: 2 };
+    let size = Size { lines: 10, columns: 41 };
+
+    assert_eq!(pane_geometry(SplitDirection::Horizontal, origin, size, 20), [
+        (origin, Size { lines: 10, columns: 20 }),
+        (Origin { y: 1, x: 22 }, Size { lines: 10, columns: 1 }),
+        (Origin { y: 1, x: 23 }, Size { lines: 10, columns: 20 })
+    ]);
+    assert_eq!(clamp_position(35, 40, PaneLimits { min: 5, max: None }, PaneLimits { min: 10, max: None }), 30);
+    assert_eq!(clamp_position(2, 40, PaneLimits { min: 5, max: None }, PaneLimits { min: 0, max: Some(20) }), 20);
+    assert_eq!(clamp_position(0, 40, PaneLimits::default(), PaneLimits::default()), 1);
+    assert_eq!(clamp_position(40, 40, PaneLimits::default(), PaneLimits::default()), 39);
+    assert_eq!(clamp_position(0, 2, PaneLimits { min: 5, max: None }, PaneLimits { min: 5, max: None }), 1);
+
+    let size = Size { lines: 10, columns: 10 };
+
+    assert_eq!(pane_geometry(SplitDirection::Horizontal, origin, size, clamp_position(u16::MAX, 9, PaneLimits::default(), PaneLimits::default())), [
+        (origin, Size { lines: 10, columns: 8 }),
+        (Origin { y: 1, x: 10 }, Size { lines: 10, columns: 1 }),
+        (Origin { y: 1, x: 11 }, Size { lines: 10, columns: 1 })
+    ]);
+    assert_eq!(pane_geometry(SplitDirection::Vertical, origin, size, clamp_position(0, 9, PaneLimits::default(), PaneLimits::default())), [
+        (origin, Size { lines: 1, columns: 10 }),
+        (Origin { y: 2, x: 2 }, Size { lines: 1, columns: 10 }),
+        (Origin { y: 3, x: 2 }, Size { lines: 8, columns: 10 })
+    ]);
+}
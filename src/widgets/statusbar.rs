@@ -0,0 +1,316 @@
+/*
+    src/widgets/statusbar.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{cmp, fmt, sync::{Arc, Mutex}};
+use ncursesw::AttributesColorPairSet;
+use crate::{
+    Cell, Justification, RipoffLine, IsWindow, HasAddFunctions, doupdate,
+    widgets::funcs::{cells_complex_string, push_str_cells},
+    funcs::{default_attributes_color_pair, str_width}
+};
+
+/// The identifier of a segment of a `StatusBar`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StatusSegmentId(usize);
+
+/// A segment of text on a `StatusBar`.
+///
+/// Segments are justified to the left, centre or right of the status bar,
+/// when the status bar is too narrow for all of it's segments the segments
+/// with the lowest priority are dropped first.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StatusSegment {
+    text:          String,
+    justification: Justification,
+    priority:      u8,
+    style:         Option<AttributesColorPairSet>
+}
+
+impl StatusSegment {
+    /// Create a new segment of text justified on the status bar with a priority.
+    pub fn new(text: &str, justification: Justification, priority: u8) -> Self {
+        Self { text: text.to_string(), justification, priority, style: None }
+    }
+
+    /// The text of the segment.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The justification of the segment.
+    pub fn justification(&self) -> Justification {
+        self.justification
+    }
+
+    /// The priority of the segment.
+    pub fn priority(&self) -> u8 {
+        self.priority
+    }
+
+    /// The attributes and color pair of the segment.
+    pub fn style(&self) -> Option<AttributesColorPairSet> {
+        self.style
+    }
+
+    /// Set the attributes and color pair of the segment, if `None` the
+    /// status bar's style is used.
+    pub fn set_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.style = style;
+    }
+}
+
+struct StatusBarState {
+    segments:  Vec<(StatusSegmentId, StatusSegment)>,
+    next_id:   usize,
+    style:     Option<AttributesColorPairSet>,
+    separator: String,
+    line:      Vec<Cell> // the line last drawn on the ripoff line.
+}
+
+unsafe impl Send for StatusBarState { } // too make thread safe
+unsafe impl Sync for StatusBarState { } // too make thread safe
+
+/// A status bar of text segments on a top or bottom ripoff line.
+///
+/// A status bar can be cloned and it's segments updated from any thread,
+/// for example by a clock or a spinner, the ripoff line is only redrawn by
+/// `refresh()` or `noutrefresh()` when it's content has changed.
+///
+/// ```text
+/// let status_bar = StatusBar::new(RipoffLine::new(Orientation::Bottom)?);
+/// let clock = status_bar.add_segment(StatusSegment::new("12:00", Justification::Right, 1));
+///
+/// ncursesw_entry(|stdscr| {
+///     status_bar.set_text(clock, "12:01");
+///     status_bar.refresh()?;
+///     ...
+/// })
+/// ```
+#[derive(Clone)]
+pub struct StatusBar {
+    ripoff_line: RipoffLine,
+    state:       Arc<Mutex<StatusBarState>>
+}
+
+impl StatusBar {
+    /// Create a new status bar on a ripoff line.
+    pub fn new(ripoff_line: RipoffLine) -> Self {
+        Self {
+            ripoff_line,
+            state: Arc::new(Mutex::new(StatusBarState {
+                segments:  vec!(),
+                next_id:   0,
+                style:     None,
+                separator: " \u{2502} ".to_string(),
+                line:      vec!()
+            }))
+        }
+    }
+
+    /// The ripoff line of the status bar.
+    pub fn ripoff_line(&self) -> &RipoffLine {
+        &self.ripoff_line
+    }
+
+    /// Add a segment, segments with the same justification are displayed
+    /// in the order they are added.
+    pub fn add_segment(&self, segment: StatusSegment) -> StatusSegmentId {
+        self.with_state(|state| {
+            let id = StatusSegmentId(state.next_id);
+
+            state.next_id += 1;
+            state.segments.push((id, segment));
+
+            id
+        })
+    }
+
+    /// Remove a segment.
+    pub fn remove_segment(&self, id: StatusSegmentId) {
+        self.with_state(|state| state.segments.retain(|(segment_id, _)| *segment_id != id));
+    }
+
+    /// A copy of a segment.
+    pub fn segment(&self, id: StatusSegmentId) -> Option<StatusSegment> {
+        self.with_state(|state| state.segment(id).cloned())
+    }
+
+    /// Set the text of a segment.
+    pub fn set_text(&self, id: StatusSegmentId, text: &str) {
+        self.with_state(|state| {
+            if let Some(segment) = state.segment(id) {
+                segment.text = text.to_string();
+            }
+        });
+    }
+
+    /// Set the attributes and color pair of a segment.
+    pub fn set_segment_style(&self, id: StatusSegmentId, style: Option<AttributesColorPairSet>) {
+        self.with_state(|state| {
+            if let Some(segment) = state.segment(id) {
+                segment.style = style;
+            }
+        });
+    }
+
+    /// Set the attributes and color pair of the status bar.
+    pub fn set_style(&self, style: Option<AttributesColorPairSet>) {
+        self.with_state(|state| state.style = style);
+    }
+
+    /// Set the separator drawn between segments, the default is `" │ "`.
+    pub fn set_separator(&self, separator: &str) {
+        self.with_state(|state| state.separator = separator.to_string());
+    }
+
+    /// Redraw the status bar if it's changed and update the physical
+    /// screen, returns `true` if the status bar was redrawn.
+    pub fn refresh(&self) -> anyhow::Result<bool> {
+        let redrawn = self.noutrefresh()?;
+
+        if redrawn {
+            doupdate()?;
+        }
+
+        Ok(redrawn)
+    }
+
+    /// Redraw the status bar if it's changed and copy it to the virtual
+    /// screen, call `doupdate()` to update the physical screen. Returns
+    /// `true` if the status bar was redrawn.
+    pub fn noutrefresh(&self) -> anyhow::Result<bool> {
+        self.ripoff_line.update(|ripoff_window, columns| {
+            self.with_state(|state| {
+                let line = state.render(columns);
+
+                if line == state.line {
+                    return Ok(false);
+                }
+
+                ripoff_window.set_column(0)?;
+                ripoff_window.add_wchnstr(&cells_complex_string(&line, 0, columns)?, Some(columns))?;
+                ripoff_window.noutrefresh()?;
+
+                state.line = line;
+
+                Ok(true)
+            })
+        })
+    }
+
+    fn with_state<F: FnOnce(&mut StatusBarState) -> T, T>(&self, func: F) -> T {
+        func(&mut self.state.lock().unwrap_or_else(|_| panic!("StatusBar::with_state() : self.state.lock() failed!!!")))
+    }
+}
+
+impl fmt::Debug for StatusBar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "StatusBar {{ ripoff_line: {:?}, segments: {:?} }}", self.ripoff_line, self.with_state(|state| state.segments.clone()))
+    }
+}
+
+impl StatusBarState {
+    fn segment(&mut self, id: StatusSegmentId) -> Option<&mut StatusSegment> {
+        self.segments.iter_mut().find(|(segment_id, _)| *segment_id == id).map(|(_, segment)| segment)
+    }
+
+    // the cells of the status bar for a number of columns.
+    fn render(&self, columns: u16) -> Vec<Cell> {
+        let style = self.style.unwrap_or_else(default_attributes_color_pair);
+        let segments: Vec<&StatusSegment> = self.segments.iter().map(|(_, segment)| segment).collect();
+        let widths: Vec<(Justification, u8, u16)> = segments.iter().map(|segment| (segment.justification, segment.priority, str_width(&segment.text))).collect();
+        let separator_width = str_width(&self.separator);
+        let mut line = vec!(Cell::new(' ', style); usize::from(columns));
+
+        for (justification, x) in [Justification::Left, Justification::Centered, Justification::Right].into_iter().zip(layout(&widths, columns, separator_width)) {
+            let mut cells = vec!();
+
+            for (index, segment) in segments.iter().enumerate() {
+                if segment.justification == justification && x.1.contains(&index) {
+                    if !cells.is_empty() {
+                        push_str_cells(&mut cells, &self.separator, separator_width, Justification::Left, style);
+                    }
+
+                    push_str_cells(&mut cells, &segment.text, str_width(&segment.text), Justification::Left, segment.style.unwrap_or(style));
+                }
+            }
+
+            for (cell, index) in cells.into_iter().zip(usize::from(x.0)..usize::from(columns)) {
+                line[index] = cell;
+            }
+        }
+
+        line
+    }
+}
+
+// lay out segments of a justification, priority and width dropping the lowest priority segments until
+// they fit, returns the column and the indices of the displayed segments for the left, centred and
+// right justified segments.
+fn layout(segments: &[(Justification, u8, u16)], columns: u16, separator_width: u16) -> [(u16, Vec<usize>); 3] {
+    let justifications = [Justification::Left, Justification::Centered, Justification::Right];
+    let mut visible: Vec<usize> = (0..segments.len()).collect();
+
+    loop {
+        let widths = justifications.map(|justification| {
+            let (count, width) = visible.iter()
+                .filter(|&&index| segments[index].0 == justification)
+                .fold((0, 0), |(count, width), &index| (count + 1, width + segments[index].2));
+
+            if count == 0 { 0 } else { width + separator_width * (count - 1) }
+        });
+        let groups = widths.iter().filter(|&&width| width > 0).count();
+        let total = widths.iter().sum::<u16>() + separator_width * u16::try_from(groups.saturating_sub(1)).unwrap_or(0);
+
+        if total <= columns || visible.is_empty() {
+            let left_end = if widths[0] > 0 { widths[0] + separator_width } else { 0 };
+            let right_start = columns.saturating_sub(widths[2] + if widths[2] > 0 { separator_width } else { 0 });
+            let centre = cmp::min(cmp::max(columns.saturating_sub(widths[1]) / 2, left_end), right_start.saturating_sub(widths[1]));
+
+            return [
+                (0, visible.iter().copied().filter(|&index| segments[index].0 == Justification::Left).collect()),
+                (centre, visible.iter().copied().filter(|&index| segments[index].0 == Justification::Centered).collect()),
+                (columns.saturating_sub(widths[2]), visible.iter().copied().filter(|&index| segments[index].0 == Justification::Right).collect())
+            ];
+        }
+
+        // drop the lowest priority segment, the last added of equal priority.
+        if let Some(position) = visible.iter().enumerate().min_by_key(|&(position, &index)| (segments[index].1, cmp::Reverse(position))).map(|(position, _)| position) {
+            visible.remove(position);
+        }
+    }
+}
+
+#[test]
+fn status_bar_test() {
+    let segments = [
+        (Justification::Left, 2, 10),
+        (Justification::Left, 0, 8),
+        (Justification::Centered, 1, 6),
+        (Justification::Right, 2, 5)
+    ];
+
+    assert_eq!(layout(&segments, 80, 3), [(0, vec!(0, 1)), (37, vec!(2)), (75, vec!(3))]);
+    assert_eq!(layout(&segments, 30, 3), [(0, vec!(0)), (13, vec!(2)), (25, vec!(3))]);
+    assert_eq!(layout(&segments, 18, 3), [(0, vec!(0)), (10, vec!()), (13, vec!(3))]);
+    assert_eq!(layout(&segments, 16, 3), [(0, vec!(0)), (13, vec!()), (16, vec!())]);
+}
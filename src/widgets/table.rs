@@ -0,0 +1,602 @@
+/*
+    src/widgets/table.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{cmp::{self, Ordering}, fmt::{Display, Formatter}};
+use ncursesw::AttributesColorPairSet;
+use crate::{
+    Origin, Size, Cell, NCurseswWinError, HasMvAddFunctions, Mouse, CharacterResult,
+    WideChar, KeyBinding, Justification, BoxDrawingType, BoxDrawingGraphic, wide_box_graphic,
+    widgets::{selection::Selection, funcs::{is_button_1, mvadd_cells, push_str_cells, region_origin, wheel_rows}},
+    funcs::{default_attributes_color_pair, reverse_attributes_color_pair, str_width}
+};
+
+const MIN_COLUMN_WIDTH: u16 = 1;
+const SCROLL_COLUMNS: u16 = 4;
+
+/// The width policy of a table column.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ColumnWidth {
+    /// A fixed number of columns.
+    Fixed(u16),
+    /// A percentage of the width of the table, up to 100.
+    Percent(u8),
+    /// The width of the column's widest content.
+    Fit
+}
+
+/// The sort order of a table column.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SortOrder {
+    /// The smallest value first, shown with `▲`.
+    Ascending,
+    /// The largest value first, shown with `▼`.
+    Descending
+}
+
+impl SortOrder {
+    fn indicator(self) -> char {
+        match self {
+            SortOrder::Ascending  => '\u{25b2}',
+            SortOrder::Descending => '\u{25bc}'
+        }
+    }
+}
+
+impl Display for SortOrder {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            SortOrder::Ascending  => "Ascending",
+            SortOrder::Descending => "Descending"
+        })
+    }
+}
+
+/// A column of a `Table`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Column {
+    title:         String,
+    width:         ColumnWidth,
+    justification: Justification
+}
+
+impl Column {
+    /// Create a new left justified column with a title and width policy,
+    /// panics if the width is a percentage over 100.
+    pub fn new(title: &str, width: ColumnWidth) -> Self {
+        assert_column_width("Column::new", width);
+
+        Self { title: title.to_string(), width, justification: Justification::Left }
+    }
+
+    /// The title of the column.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// The width policy of the column.
+    pub fn width(&self) -> ColumnWidth {
+        self.width
+    }
+
+    /// Set the width policy of the column, panics if the width is a
+    /// percentage over 100.
+    pub fn set_width(&mut self, width: ColumnWidth) {
+        assert_column_width("Column::set_width", width);
+
+        self.width = width;
+    }
+
+    /// The justification of the column's header and cells.
+    pub fn justification(&self) -> Justification {
+        self.justification
+    }
+
+    /// Set the justification of the column's header and cells.
+    pub fn set_justification(&mut self, justification: Justification) {
+        self.justification = justification;
+    }
+}
+
+/// A table of rows with a header row, sortable and resizable columns and
+/// optional box drawing grid lines.
+///
+/// Cells are truncated with an ellipsis to their column's width and when the
+/// columns are wider than the table it can be scrolled horizontally. The
+/// selected row is moved with the up and down arrows, page up/down, home and
+/// end keys, the focused column with the left and right arrows and the
+/// focused column is resized with the shifted left and right arrows. A
+/// button 1 click on the header sorts by that column.
+#[derive(Clone, Debug)]
+pub struct Table {
+    columns:        Vec<Column>,
+    column_widths:  Vec<Option<u16>>,
+    rows:           Vec<Vec<String>>,
+    selection:      Selection,
+    focused:        usize,
+    sort:           Option<(usize, SortOrder)>,
+    scroll_x:       u16,
+    grid:           Option<BoxDrawingType>,
+    style:          Option<AttributesColorPairSet>,
+    header_style:   Option<AttributesColorPairSet>,
+    selected_style: Option<AttributesColorPairSet>,
+    layout:         Vec<u16>,   // the widths of the columns from the last render.
+    size:           Size        // the size of the table from the last render.
+}
+
+impl Table {
+    /// Create a new empty table of columns.
+    pub fn new(columns: Vec<Column>) -> Self {
+        Self {
+            column_widths:  vec!(None; columns.len()),
+            columns,
+            rows:           vec!(),
+            selection:      Selection::new(0),
+            focused:        0,
+            sort:           None,
+            scroll_x:       0,
+            grid:           None,
+            style:          None,
+            header_style:   None,
+            selected_style: None,
+            layout:         vec!(),
+            size:           Size::default()
+        }
+    }
+
+    /// The columns of the table.
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    /// The rows of the table.
+    pub fn rows(&self) -> &[Vec<String>] {
+        &self.rows
+    }
+
+    /// Replace the rows of the table, if sorted the rows are sorted.
+    pub fn set_rows(&mut self, rows: Vec<Vec<String>>) {
+        self.rows = rows;
+        self.selection.set_count(self.rows.len(), self.page());
+        self.sort_rows();
+    }
+
+    /// Append a row to the table, the table is not resorted.
+    pub fn push_row(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+        self.selection.set_count(self.rows.len(), self.page());
+    }
+
+    /// The index of the selected row, `None` if the table is empty.
+    pub fn selected(&self) -> Option<usize> {
+        self.selection.selected()
+    }
+
+    /// Select a row scrolling it into view.
+    pub fn set_selected(&mut self, selected: Option<usize>) {
+        self.selection.select(selected, self.page());
+    }
+
+    /// The index of the first visible row.
+    pub fn top(&self) -> usize {
+        self.selection.top()
+    }
+
+    /// The index of the focused column.
+    pub fn focused_column(&self) -> usize {
+        self.focused
+    }
+
+    /// Set the focused column.
+    pub fn set_focused_column(&mut self, column: usize) {
+        self.focused = cmp::min(column, self.columns.len().saturating_sub(1));
+    }
+
+    /// The width of a column set by resizing, `None` if the column's width policy is used.
+    pub fn column_width(&self, column: usize) -> Option<u16> {
+        self.column_widths.get(column).copied().flatten()
+    }
+
+    /// Set the width of a column overriding it's width policy, `None` restores the policy.
+    pub fn set_column_width(&mut self, column: usize, width: Option<u16>) {
+        if let Some(column_width) = self.column_widths.get_mut(column) {
+            *column_width = width.map(|width| cmp::max(width, MIN_COLUMN_WIDTH));
+        }
+    }
+
+    /// The column and order the table is sorted by.
+    pub fn sort(&self) -> Option<(usize, SortOrder)> {
+        self.sort
+    }
+
+    /// Sort the table by a column, a numeric sort is used when both cells
+    /// are numbers. The selected row is kept selected.
+    pub fn set_sort(&mut self, sort: Option<(usize, SortOrder)>) {
+        self.sort = sort.filter(|&(column, _)| column < self.columns.len());
+        self.sort_rows();
+    }
+
+    /// Sort the table by a column ascending, if the table is already sorted
+    /// ascending by the column it's sorted descending.
+    pub fn toggle_sort(&mut self, column: usize) {
+        let order = match self.sort {
+            Some((sorted, SortOrder::Ascending)) if sorted == column => SortOrder::Descending,
+            _                                                        => SortOrder::Ascending
+        };
+
+        self.set_sort(Some((column, order)));
+    }
+
+    /// The number of columns the table is scrolled horizontally.
+    pub fn scroll_x(&self) -> u16 {
+        self.scroll_x
+    }
+
+    /// Scroll the table horizontally.
+    pub fn set_scroll_x(&mut self, scroll_x: u16) {
+        self.scroll_x = scroll_x;
+        self.clamp_scroll_x();
+    }
+
+    /// Set the box drawing type of the grid lines, `None` draws no grid lines.
+    pub fn set_grid(&mut self, grid: Option<BoxDrawingType>) {
+        self.grid = grid;
+    }
+
+    /// Set the attributes and color pair of the cells.
+    pub fn set_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.style = style;
+    }
+
+    /// Set the attributes and color pair of the header, if `None` the style is used.
+    pub fn set_header_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.header_style = style;
+    }
+
+    /// Set the attributes and color pair of the selected row, if `None` the
+    /// style is reversed.
+    pub fn set_selected_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.selected_style = style;
+    }
+
+    /// Handle a key returning `true` if the key was used by the table.
+    pub fn handle_key(&mut self, key: CharacterResult<WideChar>) -> bool {
+        let key_binding = match key {
+            CharacterResult::Key(key_binding) => key_binding,
+            CharacterResult::Character(_)     => return false
+        };
+
+        match key_binding {
+            KeyBinding::LeftArrow       => self.set_focused_column(self.focused.saturating_sub(1)),
+            KeyBinding::RightArrow      => self.set_focused_column(self.focused + 1),
+            KeyBinding::ShiftLeftArrow  => self.resize_focused(-1),
+            KeyBinding::ShiftRightArrow => self.resize_focused(1),
+            KeyBinding::MouseEvent      => return false,
+            _                           => return self.selection.handle_key(key_binding, self.page())
+        }
+
+        self.ensure_focused_visible();
+
+        true
+    }
+
+    /// Handle a mouse event with the table displayed at the screen origin,
+    /// returns `true` if the event was used by the table.
+    pub fn handle_mouse(&mut self, mouse: &Mouse, screen_origin: Origin) -> result!(bool) {
+        let events = mouse.events();
+
+        let origin = match region_origin(mouse.origin()?.origin(), screen_origin, self.size) {
+            Some(origin) => origin,
+            None         => return Ok(false)
+        };
+
+        if let Some(rows) = wheel_rows(events) {
+            self.selection.scroll_by(rows, self.page());
+        } else if is_button_1(events) {
+            if origin.y == 0 {
+                if let Some(column) = self.column_at(origin.x.saturating_add(self.scroll_x)) {
+                    self.toggle_sort(column);
+                }
+            } else if origin.y >= self.header_lines() {
+                let row = self.selection.top() + usize::from(origin.y - self.header_lines());
+
+                if row < self.rows.len() {
+                    self.selection.select(Some(row), self.page());
+                }
+
+                if let Some(column) = self.column_at(origin.x.saturating_add(self.scroll_x)) {
+                    self.focused = column;
+                }
+            }
+        } else {
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+
+    /// Render the table into the region of a window at origin of size.
+    pub fn render<W: HasMvAddFunctions>(&mut self, window: &W, origin: Origin, size: Size) -> result!(()) {
+        assert_origin!("render", window.size()?, origin);
+
+        self.size = size;
+        self.layout = self.column_layout(size.columns);
+        self.clamp_scroll_x();
+        self.selection.set_count(self.rows.len(), self.page());
+
+        let style = self.style.unwrap_or_else(default_attributes_color_pair);
+        let header_style = self.header_style.unwrap_or(style);
+        let selected_style = self.selected_style.unwrap_or_else(|| reverse_attributes_color_pair(style));
+        let offset = usize::from(self.scroll_x);
+
+        if size.lines == 0 {
+            return Ok(());
+        }
+
+        let header: Vec<String> = self.columns.iter().enumerate().map(|(index, column)| {
+            match self.sort {
+                Some((sorted, order)) if sorted == index => format!("{} {}", column.title, order.indicator()),
+                _                                         => column.title.clone()
+            }
+        }).collect();
+
+        let cells = self.line_cells(&header, |index| {
+            if index == self.focused { reverse_attributes_color_pair(header_style) } else { header_style }
+        }, header_style)?;
+
+        mvadd_cells(window, origin, &cells, offset, size.columns)?;
+
+        if let Some(grid) = self.grid {
+            if size.lines > 1 {
+                let cells = self.separator_cells(grid, style)?;
+
+                mvadd_cells(window, Origin { y: origin.y + 1, x: origin.x }, &cells, offset, size.columns)?;
+            }
+        }
+
+        let header_lines = self.header_lines();
+        let visible = self.selection.visible(self.page());
+        let mut y = header_lines;
+
+        for index in visible {
+            let row_style = if self.selection.selected() == Some(index) { selected_style } else { style };
+            let cells = self.line_cells(&self.rows[index], |_| row_style, row_style)?;
+
+            mvadd_cells(window, Origin { y: origin.y + y, x: origin.x }, &cells, offset, size.columns)?;
+            y += 1;
+        }
+
+        let blank = vec!(Cell::new(' ', style); usize::from(size.columns));
+
+        for y in y..size.lines {
+            mvadd_cells(window, Origin { y: origin.y + y, x: origin.x }, &blank, 0, size.columns)?;
+        }
+
+        Ok(())
+    }
+
+    // the number of lines used by the header and it's grid line.
+    fn header_lines(&self) -> u16 {
+        if self.grid.is_some() { 2 } else { 1 }
+    }
+
+    // the number of rows visible on a page.
+    fn page(&self) -> usize {
+        usize::from(self.size.lines.saturating_sub(self.header_lines()))
+    }
+
+    // the width of the vertical grid line between columns.
+    fn separator_width(&self) -> u16 {
+        u16::from(self.grid.is_some())
+    }
+
+    // the widths of the columns for a table width.
+    fn column_layout(&self, table_columns: u16) -> Vec<u16> {
+        let separators = self.separator_width().saturating_mul(u16::try_from(self.columns.len().saturating_sub(1)).unwrap_or(u16::MAX));
+        let available = u32::from(table_columns.saturating_sub(separators));
+
+        self.columns.iter().enumerate().map(|(index, column)| {
+            let width = self.column_widths[index].unwrap_or_else(|| match column.width {
+                ColumnWidth::Fixed(width)     => width,
+                ColumnWidth::Percent(percent) => u16::try_from(available * u32::from(percent) / 100).unwrap_or(u16::MAX),
+                ColumnWidth::Fit              => {
+                    let header_width = str_width(&column.title) + if self.sort.is_some_and(|(sorted, _)| sorted == index) { 2 } else { 0 };
+
+                    self.rows
+                        .iter()
+                        .filter_map(|row| row.get(index).map(|cell| str_width(cell)))
+                        .fold(header_width, cmp::max)
+                }
+            });
+
+            cmp::max(width, MIN_COLUMN_WIDTH)
+        }).collect()
+    }
+
+    // the total width of all columns and grid lines.
+    fn total_width(&self) -> u16 {
+        let separators = self.separator_width().saturating_mul(u16::try_from(self.layout.len().saturating_sub(1)).unwrap_or(u16::MAX));
+
+        self.layout.iter().fold(separators, |total, &width| total.saturating_add(width))
+    }
+
+    // the starting column of a column.
+    fn column_start(&self, column: usize) -> u16 {
+        self.layout.iter().take(column).fold(0, |start, &width| start.saturating_add(width).saturating_add(self.separator_width()))
+    }
+
+    // the column containing a column of the table.
+    fn column_at(&self, x: u16) -> Option<usize> {
+        let mut start: u16 = 0;
+
+        for (index, &width) in self.layout.iter().enumerate() {
+            if x < start.saturating_add(width) {
+                return Some(index);
+            }
+
+            start = start.saturating_add(width).saturating_add(self.separator_width());
+        }
+
+        None
+    }
+
+    fn clamp_scroll_x(&mut self) {
+        self.scroll_x = cmp::min(self.scroll_x, self.total_width().saturating_sub(self.size.columns));
+    }
+
+    // scroll horizontally so the focused column is visible.
+    fn ensure_focused_visible(&mut self) {
+        if let Some(&width) = self.layout.get(self.focused) {
+            let start = self.column_start(self.focused);
+            let end = start.saturating_add(width);
+
+            if start < self.scroll_x {
+                self.scroll_x = start;
+            } else if end > self.scroll_x.saturating_add(self.size.columns) {
+                self.scroll_x = cmp::min(start, end.saturating_sub(self.size.columns).div_ceil(SCROLL_COLUMNS).saturating_mul(SCROLL_COLUMNS));
+            }
+        }
+    }
+
+    // grow or shrink the focused column.
+    fn resize_focused(&mut self, columns: i16) {
+        if let Some(&width) = self.layout.get(self.focused) {
+            let width = width.saturating_add_signed(columns);
+
+            self.set_column_width(self.focused, Some(width));
+
+            if let Some(layout_width) = self.layout.get_mut(self.focused) {
+                *layout_width = cmp::max(width, MIN_COLUMN_WIDTH);
+            }
+        }
+    }
+
+    // sort the rows by the sort column keeping the selected row selected.
+    fn sort_rows(&mut self) {
+        if let Some((column, order)) = self.sort {
+            let selected = self.selection.selected();
+            let mut indices: Vec<usize> = (0..self.rows.len()).collect();
+
+            indices.sort_by(|&lhs, &rhs| {
+                let ordering = compare_cells(self.rows[lhs].get(column), self.rows[rhs].get(column));
+
+                if order == SortOrder::Ascending { ordering } else { ordering.reverse() }
+            });
+
+            let mut rows: Vec<Option<Vec<String>>> = self.rows.drain(..).map(Some).collect();
+
+            self.rows = indices.iter().filter_map(|&index| rows[index].take()).collect();
+            self.selection.select(selected.and_then(|selected| indices.iter().position(|&index| index == selected)), self.page());
+        }
+    }
+
+    // the cells of a line of text with each column justified and truncated.
+    fn line_cells<F>(&self, texts: &[String], column_style: F, style: AttributesColorPairSet) -> result!(Vec<Cell>)
+        where F: Fn(usize) -> AttributesColorPairSet
+    {
+        let mut cells = Vec::with_capacity(usize::from(self.total_width()));
+
+        for (index, (column, &width)) in self.columns.iter().zip(self.layout.iter()).enumerate() {
+            if index > 0 {
+                if let Some(grid) = self.grid {
+                    cells.push(Cell::new(wide_box_graphic(grid, BoxDrawingGraphic::VerticalLine).as_char()?, style));
+                }
+            }
+
+            let text = texts.get(index).map_or("", |text| text.as_str());
+
+            push_str_cells(&mut cells, text, width, column.justification, column_style(index));
+        }
+
+        Ok(cells)
+    }
+
+    // the cells of the grid line below the header.
+    fn separator_cells(&self, grid: BoxDrawingType, style: AttributesColorPairSet) -> result!(Vec<Cell>) {
+        let horizontal = wide_box_graphic(grid, BoxDrawingGraphic::HorizontalLine).as_char()?;
+        let plus = wide_box_graphic(grid, BoxDrawingGraphic::Plus).as_char()?;
+        let mut cells = Vec::with_capacity(usize::from(self.total_width()));
+
+        for (index, &width) in self.layout.iter().enumerate() {
+            if index > 0 {
+                cells.push(Cell::new(plus, style));
+            }
+
+            cells.extend((0..width).map(|_| Cell::new(horizontal, style)));
+        }
+
+        Ok(cells)
+    }
+}
+
+// compare two cells numerically if both are numbers otherwise as strings.
+fn compare_cells(lhs: Option<&String>, rhs: Option<&String>) -> Ordering {
+    let lhs = lhs.map_or("", |lhs| lhs.as_str());
+    let rhs = rhs.map_or("", |rhs| rhs.as_str());
+
+    match (lhs.trim().parse::<f64>(), rhs.trim().parse::<f64>()) {
+        (Ok(lhs), Ok(rhs)) => lhs.partial_cmp(&rhs).unwrap_or(Ordering::Equal),
+        _                  => lhs.cmp(rhs)
+    }
+}
+
+fn assert_column_width(func: &str, width: ColumnWidth) {
+    if let ColumnWidth::Percent(percent) = width {
+        assert!(percent <= 100, "{}() : percent is invalid, percent={}", func, percent);
+    }
+}
+
+#[test]
+fn table_test() {
+    let mut table = Table::new(vec!(
+        Column::new("Name", ColumnWidth::Fit),
+        Column::new("Size", ColumnWidth::Fixed(6)),
+        Column::new("Notes", ColumnWidth::Percent(50))
+    ));
+
+    table.set_rows(vec!(
+        vec!("b".to_string(), "10".to_string(), "".to_string()),
+        vec!("alpha".to_string(), "9".to_string(), "".to_string()),
+        vec!("c".to_string(), "100".to_string(), "".to_string())
+    ));
+    table.set_selected(Some(1));
+    table.set_sort(Some((1, SortOrder::Ascending)));
+
+    assert_eq!(table.rows()[0][1], "9");
+    assert_eq!(table.selected(), Some(0));
+
+    table.toggle_sort(1);
+
+    assert_eq!(table.rows()[0][1], "100");
+    assert_eq!(table.selected(), Some(2));
+
+    table.set_grid(Some(BoxDrawingType::Light(crate::BoxDrawingTypeDetail::Normal)));
+
+    assert_eq!(table.column_layout(41), vec!(5, 6, 19));
+
+    // columns wider than the table's coordinates saturate.
+    table.layout = vec!(u16::MAX - 1, u16::MAX, 5);
+    table.focused = 2;
+    table.ensure_focused_visible();
+    assert_eq!(table.column_at(u16::MAX - 1), Some(1));
+    assert_eq!(table.column_start(2), u16::MAX);
+    assert!(std::panic::catch_unwind(|| Column::new("Percent", ColumnWidth::Percent(101))).is_err());
+}
@@ -0,0 +1,853 @@
+/*
+    src/widgets/textarea.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{cmp, collections::VecDeque};
+use ncursesw::AttributesColorPairSet;
+use crate::{
+    Origin, Size, Cell, NCurseswWinError, Pad, IsPad, HasYXAxis, BaseCanvas, Mouse,
+    MouseButton, MouseButtonEvent, MouseButtonState, CharacterResult, WideChar, KeyBinding,
+    widgets::{ScrollTarget, ScrollAxis, ScrollState, funcs::{mvadd_cells, region_origin, wheel_rows}},
+    funcs::{char_width, default_attributes_color_pair, reverse_attributes_color_pair}
+};
+
+const TAB_WIDTH: usize = 4;
+const UNDO_LIMIT: usize = 1000;
+
+// a position in the text as a line and a character index within the line.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Location {
+    line:   usize,
+    column: usize
+}
+
+// a display row of the text, a line or part of a soft wrapped line.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+struct Row {
+    line:  usize,
+    start: usize,
+    end:   usize
+}
+
+// the kind of edit used to group typing into a single undo.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum Edit {
+    Insert,
+    Other
+}
+
+// an edit replacing the removed text at a location with the inserted text,
+// lines are separated by `'\n'`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct Change {
+    start:    Location,
+    removed:  Vec<char>,
+    inserted: Vec<char>,
+    cursor:   Location  // the cursor before the edit.
+}
+
+// the text, cursor, selection and undo history of a text area.
+#[derive(Debug)]
+struct TextBuffer {
+    lines:     Vec<Vec<char>>,
+    cursor:    Location,
+    anchor:    Option<Location>,
+    overwrite: bool,
+    undo:      VecDeque<Change>,
+    redo:      Vec<Change>,
+    last_edit: Option<Edit>
+}
+
+/// A multi-line text editor rendered on a `Pad`.
+///
+/// Lines are either soft wrapped at the width of the viewport or scrolled
+/// horizontally, and may be prefixed by their line number. Text is selected
+/// with the shifted arrow, home and end keys (`ScrollBackward` and
+/// `ScrollForward` being the shifted up and down arrows on most terminals)
+/// or by dragging with mouse button 1. The insert key toggles overwrite
+/// mode and `Ctrl-z` and `Ctrl-y` or the undo and redo keys undo and redo
+/// edits, consecutive typing is undone as a single edit.
+#[derive(Debug)]
+pub struct TextArea {
+    pad:               Pad,
+    viewport:          Size,
+    buffer:            TextBuffer,
+    goal_x:            Option<usize>,
+    top:               usize,  // the first visible display row.
+    left:              usize,  // the first visible display column when not wrapping.
+    scroll_to_cursor:  bool,
+    wrap:              bool,
+    line_numbers:      bool,
+    style:             Option<AttributesColorPairSet>,
+    line_number_style: Option<AttributesColorPairSet>
+}
+
+impl TextArea {
+    /// Create a new empty text area of a viewport size.
+    pub fn new(size: Size) -> result!(Self) {
+        Ok(Self {
+            pad:               Pad::new(size)?,
+            viewport:          size,
+            buffer:            TextBuffer::new(),
+            goal_x:            None,
+            top:               0,
+            left:              0,
+            scroll_to_cursor:  true,
+            wrap:              false,
+            line_numbers:      false,
+            style:             None,
+            line_number_style: None
+        })
+    }
+
+    /// The viewport size of the text area.
+    pub fn size(&self) -> Size {
+        self.viewport
+    }
+
+    /// Resize the viewport of the text area.
+    pub fn resize(&mut self, size: Size) -> result!(()) {
+        self.pad.resize(size)?;
+        self.viewport = size;
+        self.scroll_to_cursor = true;
+
+        Ok(())
+    }
+
+    /// The text of the text area with lines separated by `'\n'`.
+    pub fn text(&self) -> String {
+        self.buffer.text()
+    }
+
+    /// Replace the text of the text area clearing the undo history.
+    pub fn set_text(&mut self, text: &str) {
+        self.buffer.set_text(text);
+        self.top = 0;
+        self.left = 0;
+        self.scroll_to_cursor = true;
+    }
+
+    /// The number of lines.
+    pub fn line_count(&self) -> usize {
+        self.buffer.lines.len()
+    }
+
+    /// The cursor position as a line and a character index within the line.
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.buffer.cursor.line, self.buffer.cursor.column)
+    }
+
+    /// Set the cursor position clearing the selection.
+    pub fn set_cursor(&mut self, line: usize, column: usize) {
+        self.move_to(self.buffer.clamp(Location { line, column }), false);
+    }
+
+    /// The start and end of the selection as lines and character indices.
+    pub fn selection(&self) -> Option<((usize, usize), (usize, usize))> {
+        self.buffer.selection_range().map(|(start, end)| ((start.line, start.column), (end.line, end.column)))
+    }
+
+    /// The selected text.
+    pub fn selected_text(&self) -> Option<String> {
+        self.buffer.selection_range().map(|(start, end)| self.buffer.range(start, end).into_iter().collect())
+    }
+
+    /// Select all of the text.
+    pub fn select_all(&mut self) {
+        self.buffer.anchor = Some(Location::default());
+        self.buffer.cursor = self.buffer.end_location();
+        self.scroll_to_cursor = true;
+    }
+
+    /// Clear the selection.
+    pub fn clear_selection(&mut self) {
+        self.buffer.anchor = None;
+    }
+
+    /// Is soft wrapping enabled.
+    pub fn wrap(&self) -> bool {
+        self.wrap
+    }
+
+    /// Soft wrap lines at the viewport's width, when disabled the text is
+    /// scrolled horizontally.
+    pub fn set_wrap(&mut self, wrap: bool) {
+        self.wrap = wrap;
+        self.top = 0;
+        self.left = 0;
+        self.scroll_to_cursor = true;
+    }
+
+    /// Is overwrite mode enabled.
+    pub fn overwrite(&self) -> bool {
+        self.buffer.overwrite
+    }
+
+    /// Set overwrite mode, when disabled characters are inserted.
+    pub fn set_overwrite(&mut self, overwrite: bool) {
+        self.buffer.overwrite = overwrite;
+    }
+
+    /// Are line numbers shown.
+    pub fn line_numbers(&self) -> bool {
+        self.line_numbers
+    }
+
+    /// Show line numbers to the left of the text.
+    pub fn set_line_numbers(&mut self, line_numbers: bool) {
+        self.line_numbers = line_numbers;
+        self.scroll_to_cursor = true;
+    }
+
+    /// Set the attributes and color pair of the text.
+    pub fn set_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.style = style;
+    }
+
+    /// Set the attributes and color pair of the line numbers.
+    pub fn set_line_number_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.line_number_style = style;
+    }
+
+    /// Insert a string at the cursor replacing the selection.
+    pub fn insert_str(&mut self, str: &str) {
+        self.buffer.insert_str(str);
+        self.scroll_to_cursor = true;
+    }
+
+    /// Undo the last edit, returns `false` if there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        self.scroll_to_cursor = true;
+
+        self.buffer.restore(true)
+    }
+
+    /// Redo the last undone edit, returns `false` if there is nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        self.scroll_to_cursor = true;
+
+        self.buffer.restore(false)
+    }
+
+    /// Handle a key returning `true` if the key was used by the text area.
+    pub fn handle_key(&mut self, key: CharacterResult<WideChar>) -> result!(bool) {
+        let page = usize::from(self.viewport.lines);
+
+        match key {
+            CharacterResult::Key(key_binding) => Ok(self.handle_key_binding(key_binding, page)?),
+            CharacterResult::Character(wch)   => Ok(self.handle_character(wch.as_char()?))
+        }
+    }
+
+    /// Handle a mouse event with the text area displayed at the screen origin,
+    /// returns `true` if the event was used by the text area.
+    pub fn handle_mouse(&mut self, mouse: &Mouse, screen_origin: Origin) -> result!(bool) {
+        let viewport = self.viewport;
+        let events = mouse.events();
+        let button_1 = |event| events.is_button_state(MouseButtonState::new(MouseButton::One, event));
+
+        let origin = match region_origin(mouse.origin()?.origin(), screen_origin, viewport) {
+            Some(origin) => origin,
+            None         => return Ok(false)
+        };
+
+        if let Some(rows) = wheel_rows(events) {
+            let max_top = self.rows(viewport).len().saturating_sub(usize::from(viewport.lines));
+
+            self.top = cmp::min(self.top.saturating_add_signed(rows), max_top);
+
+            return Ok(true);
+        }
+
+        let location = self.location_at(origin, viewport);
+
+        if button_1(MouseButtonEvent::Pressed) {
+            self.buffer.anchor = Some(location);
+            self.move_to(location, true);
+        } else if button_1(MouseButtonEvent::Released) {
+            self.move_to(location, true);
+
+            if self.buffer.anchor == Some(location) {
+                self.buffer.anchor = None;
+            }
+        } else if button_1(MouseButtonEvent::Clicked) {
+            self.move_to(location, false);
+        } else {
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+
+    /// Render the visible rows of the text into the text area's pad.
+    pub fn render(&mut self) -> result!(()) {
+        let viewport = self.viewport;
+        let rows = self.rows(viewport);
+        let gutter = self.gutter();
+        let text_columns = viewport.columns.saturating_sub(gutter);
+
+        if self.scroll_to_cursor {
+            self.ensure_cursor_visible(&rows, viewport);
+            self.scroll_to_cursor = false;
+        }
+
+        self.pad.erase()?;
+
+        let style = self.style.unwrap_or_else(default_attributes_color_pair);
+        let selected_style = reverse_attributes_color_pair(style);
+        let line_number_style = self.line_number_style.unwrap_or(style);
+        let selection = self.buffer.selection_range();
+        let offset = if self.wrap { 0 } else { self.left };
+
+        for (y, row) in rows.iter().skip(self.top).take(usize::from(viewport.lines)).enumerate() {
+            let y = u16::try_from(y)?;
+
+            if gutter > 0 && row.start == 0 {
+                let number = format!("{:>width$} ", row.line + 1, width = usize::from(gutter - 1));
+                let cells: Vec<Cell> = number.chars().map(|ch| Cell::new(ch, line_number_style)).collect();
+
+                mvadd_cells(&self.pad, Origin { y, x: 0 }, &cells, 0, gutter)?;
+            }
+
+            let mut cells = Vec::with_capacity(row.end - row.start + usize::from(text_columns));
+
+            for column in row.start..row.end {
+                let ch = self.buffer.lines[row.line][column];
+                let location = Location { line: row.line, column };
+                let style = match selection {
+                    Some((start, end)) if start <= location && location < end => selected_style,
+                    _                                                          => style
+                };
+
+                cells.push(Cell::new(ch, style));
+
+                if char_width(ch) > 1 {
+                    cells.push(Cell::continuation(style));
+                }
+            }
+
+            mvadd_cells(&self.pad, Origin { y, x: gutter }, &cells, offset, text_columns)?;
+        }
+
+        let (y, x) = self.cursor_display(&rows, viewport);
+
+        if y >= self.top && y - self.top < usize::from(viewport.lines) && x >= offset && x - offset < usize::from(text_columns) {
+            self.pad.set_cursor(Origin { y: u16::try_from(y - self.top)?, x: gutter + u16::try_from(x - offset)? })?;
+        }
+
+        Ok(())
+    }
+
+    /// Render the text area and refresh it onto the screen at the screen origin.
+    pub fn refresh(&mut self, screen_origin: Origin) -> result!(()) {
+        self.render()?;
+        self.pad.prefresh(Origin::default(), screen_origin, self.screen_max(screen_origin))
+    }
+
+    /// Render the text area and copy it to the virtual screen at the screen
+    /// origin, call `doupdate()` to update the physical screen.
+    pub fn noutrefresh(&mut self, screen_origin: Origin) -> result!(()) {
+        self.render()?;
+        self.pad.pnoutrefresh(Origin::default(), screen_origin, self.screen_max(screen_origin))
+    }
+
+    fn handle_key_binding(&mut self, key_binding: KeyBinding, page: usize) -> result!(bool) {
+        let viewport = self.viewport;
+        let cursor = self.buffer.cursor;
+        let line_end = Location { column: self.buffer.lines[cursor.line].len(), ..cursor };
+
+        match key_binding {
+            KeyBinding::LeftArrow       => self.move_to(self.buffer.previous_location(), false),
+            KeyBinding::RightArrow      => self.move_to(self.buffer.next_location(), false),
+            KeyBinding::UpArrow         => self.move_rows(-1, viewport, false),
+            KeyBinding::DownArrow       => self.move_rows(1, viewport, false),
+            KeyBinding::PreviousPage    => self.move_rows(-isize::try_from(page)?, viewport, false),
+            KeyBinding::NextPage        => self.move_rows(isize::try_from(page)?, viewport, false),
+            KeyBinding::Home            => self.move_to(Location { column: 0, ..cursor }, false),
+            KeyBinding::End             => self.move_to(line_end, false),
+            KeyBinding::ShiftLeftArrow  => self.move_to(self.buffer.previous_location(), true),
+            KeyBinding::ShiftRightArrow => self.move_to(self.buffer.next_location(), true),
+            KeyBinding::ScrollBackward  => self.move_rows(-1, viewport, true),
+            KeyBinding::ScrollForward   => self.move_rows(1, viewport, true),
+            KeyBinding::ShiftHome       => self.move_to(Location { column: 0, ..cursor }, true),
+            KeyBinding::ShiftEnd        => self.move_to(line_end, true),
+            KeyBinding::InsertCharacter => self.buffer.overwrite = !self.buffer.overwrite,
+            KeyBinding::Backspace       => self.buffer.backspace(),
+            KeyBinding::DeleteCharacter => self.buffer.delete(),
+            KeyBinding::Enter           => self.buffer.insert_str("\n"),
+            KeyBinding::Undo            => { self.buffer.restore(true); },
+            KeyBinding::Redo            => { self.buffer.restore(false); },
+            _                           => return Ok(false)
+        }
+
+        self.scroll_to_cursor = true;
+
+        Ok(true)
+    }
+
+    fn handle_character(&mut self, ch: char) -> bool {
+        match ch {
+            '\n' | '\r'        => self.buffer.insert_str("\n"),
+            '\t'               => {
+                let spaces = TAB_WIDTH - self.buffer.cursor.column % TAB_WIDTH;
+
+                self.buffer.insert_str(&" ".repeat(spaces));
+            },
+            '\u{8}' | '\u{7f}' => self.buffer.backspace(),
+            '\u{1a}'           => { self.buffer.restore(true); },
+            '\u{19}'           => { self.buffer.restore(false); },
+            ch if ch.is_control() => return false,
+            ch                 => self.buffer.type_char(ch)
+        }
+
+        self.scroll_to_cursor = true;
+
+        true
+    }
+
+    // the width of the line numbers and their trailing space.
+    fn gutter(&self) -> u16 {
+        if self.line_numbers {
+            u16::try_from(self.buffer.lines.len().to_string().len() + 1).unwrap_or(u16::MAX)
+        } else {
+            0
+        }
+    }
+
+    // the display rows of the text for a viewport.
+    fn rows(&self, viewport: Size) -> Vec<Row> {
+        let width = usize::from(cmp::max(viewport.columns.saturating_sub(self.gutter()), 1));
+
+        wrap_rows(&self.buffer.lines, if self.wrap { Some(width) } else { None })
+    }
+
+    // the display row and column of the cursor.
+    fn cursor_display(&self, rows: &[Row], viewport: Size) -> (usize, usize) {
+        let cursor = self.buffer.cursor;
+        let index = row_index(rows, cursor);
+        let row = rows[index];
+        let x = self.buffer.lines[row.line][row.start..cursor.column].iter().map(|&ch| usize::from(char_width(ch))).sum();
+        let width = usize::from(cmp::max(viewport.columns.saturating_sub(self.gutter()), 1));
+
+        (index, if self.wrap { cmp::min(x, width - 1) } else { x })
+    }
+
+    // the location displayed at an origin of the viewport.
+    fn location_at(&self, origin: Origin, viewport: Size) -> Location {
+        let rows = self.rows(viewport);
+
+        match rows.get(self.top + usize::from(origin.y)) {
+            Some(&row) => {
+                let offset = if self.wrap { 0 } else { self.left };
+                let x = usize::from(origin.x.saturating_sub(self.gutter())) + offset;
+
+                Location { line: row.line, column: self.column_at(row, x) }
+            },
+            None       => self.buffer.end_location()
+        }
+    }
+
+    // the character index within a row at a display column.
+    fn column_at(&self, row: Row, x: usize) -> usize {
+        let mut width = 0;
+
+        for column in row.start..row.end {
+            width += usize::from(char_width(self.buffer.lines[row.line][column]));
+
+            if width > x {
+                return column;
+            }
+        }
+
+        row.end
+    }
+
+    fn ensure_cursor_visible(&mut self, rows: &[Row], viewport: Size) {
+        let (y, x) = self.cursor_display(rows, viewport);
+        let lines = usize::from(cmp::max(viewport.lines, 1));
+        let columns = usize::from(cmp::max(viewport.columns.saturating_sub(self.gutter()), 1));
+
+        if y < self.top {
+            self.top = y;
+        } else if y >= self.top + lines {
+            self.top = y + 1 - lines;
+        }
+
+        if self.wrap {
+            self.left = 0;
+        } else if x < self.left {
+            self.left = x;
+        } else if x >= self.left + columns {
+            self.left = x + 1 - columns;
+        }
+    }
+
+    // the screen's maximum origin of the viewport.
+    fn screen_max(&self, screen_origin: Origin) -> Origin {
+        Origin {
+            y: screen_origin.y + self.viewport.lines.saturating_sub(1),
+            x: screen_origin.x + self.viewport.columns.saturating_sub(1)
+        }
+    }
+
+    // move the cursor to a location extending the selection or clearing it.
+    fn move_to(&mut self, location: Location, select: bool) {
+        let buffer = &mut self.buffer;
+
+        if select {
+            if buffer.anchor.is_none() {
+                buffer.anchor = Some(buffer.cursor);
+            }
+        } else {
+            buffer.anchor = None;
+        }
+
+        buffer.cursor = location;
+        buffer.last_edit = None;
+        self.goal_x = None;
+        self.scroll_to_cursor = true;
+    }
+
+    // move the cursor up or down a number of display rows keeping it's display column.
+    fn move_rows(&mut self, delta: isize, viewport: Size, select: bool) {
+        let rows = self.rows(viewport);
+        let (y, x) = self.cursor_display(&rows, viewport);
+        let goal_x = self.goal_x.unwrap_or(x);
+        let y = cmp::min(y.saturating_add_signed(delta), rows.len() - 1);
+        let row = rows[y];
+
+        self.move_to(Location { line: row.line, column: self.column_at(row, goal_x) }, select);
+        self.goal_x = Some(goal_x);
+    }
+}
+
+impl TextBuffer {
+    fn new() -> Self {
+        Self { lines: vec!(vec!()), cursor: Location::default(), anchor: None, overwrite: false, undo: VecDeque::new(), redo: vec!(), last_edit: None }
+    }
+
+    fn text(&self) -> String {
+        self.lines.iter().map(|line| line.iter().collect::<String>()).collect::<Vec<String>>().join("\n")
+    }
+
+    fn set_text(&mut self, text: &str) {
+        self.lines = text.split('\n').map(|line| line.trim_end_matches('\r').chars().collect()).collect();
+        self.cursor = Location::default();
+        self.anchor = None;
+        self.undo.clear();
+        self.redo.clear();
+        self.last_edit = None;
+    }
+
+    // the ordered start and end of a non empty selection.
+    fn selection_range(&self) -> Option<(Location, Location)> {
+        self.anchor
+            .filter(|&anchor| anchor != self.cursor)
+            .map(|anchor| (cmp::min(anchor, self.cursor), cmp::max(anchor, self.cursor)))
+    }
+
+    // the text between two locations with lines separated by `'\n'`.
+    fn range(&self, start: Location, end: Location) -> Vec<char> {
+        let mut text = vec!();
+
+        for line in start.line..=end.line {
+            let from = if line == start.line { start.column } else { 0 };
+            let to = if line == end.line { end.column } else { self.lines[line].len() };
+
+            text.extend(&self.lines[line][from..to]);
+
+            if line != end.line {
+                text.push('\n');
+            }
+        }
+
+        text
+    }
+
+    fn previous_location(&self) -> Location {
+        match self.cursor {
+            Location { line: 0, column: 0 } => self.cursor,
+            Location { line, column: 0 }    => Location { line: line - 1, column: self.lines[line - 1].len() },
+            Location { line, column }       => Location { line, column: column - 1 }
+        }
+    }
+
+    fn next_location(&self) -> Location {
+        if self.cursor.column < self.lines[self.cursor.line].len() {
+            Location { column: self.cursor.column + 1, ..self.cursor }
+        } else if self.cursor.line + 1 < self.lines.len() {
+            Location { line: self.cursor.line + 1, column: 0 }
+        } else {
+            self.cursor
+        }
+    }
+
+    fn end_location(&self) -> Location {
+        let line = self.lines.len() - 1;
+
+        Location { line, column: self.lines[line].len() }
+    }
+
+    fn clamp(&self, location: Location) -> Location {
+        let line = cmp::min(location.line, self.lines.len() - 1);
+
+        Location { line, column: cmp::min(location.column, self.lines[line].len()) }
+    }
+
+    // insert a string replacing the selection.
+    fn insert_str(&mut self, str: &str) {
+        let (start, end) = self.selection_range().unwrap_or((self.cursor, self.cursor));
+        let text: Vec<char> = str.chars().filter(|&ch| ch != '\r').collect();
+
+        self.replace(start, end, &text, Edit::Other);
+    }
+
+    // type a character replacing the selection, or the character at the cursor in overwrite mode.
+    fn type_char(&mut self, ch: char) {
+        let overwrite = self.overwrite && self.cursor.column < self.lines[self.cursor.line].len();
+        let (start, end) = match self.selection_range() {
+            Some(range)        => range,
+            None if overwrite  => (self.cursor, Location { column: self.cursor.column + 1, ..self.cursor }),
+            None               => (self.cursor, self.cursor)
+        };
+
+        self.replace(start, end, &[ch], Edit::Insert);
+    }
+
+    fn backspace(&mut self) {
+        match self.selection_range() {
+            Some((start, end))                         => self.replace(start, end, &[], Edit::Other),
+            None if self.cursor != Location::default() => self.replace(self.previous_location(), self.cursor, &[], Edit::Other),
+            None                                       => { }
+        }
+    }
+
+    fn delete(&mut self) {
+        match self.selection_range() {
+            Some((start, end))                         => self.replace(start, end, &[], Edit::Other),
+            None if self.cursor != self.end_location() => self.replace(self.cursor, self.next_location(), &[], Edit::Other),
+            None                                       => { }
+        }
+    }
+
+    // replace the text between two locations recording the change for undo, consecutive
+    // typing is merged into a single change.
+    fn replace(&mut self, start: Location, end: Location, text: &[char], edit: Edit) {
+        let cursor = self.cursor;
+        let merge = edit == Edit::Insert && self.last_edit == Some(Edit::Insert) && self.anchor.is_none();
+        let removed = self.splice(start, end, text);
+
+        match self.undo.back_mut() {
+            Some(change) if merge && text_end(change.start, &change.inserted) == start => {
+                change.removed.extend(removed);
+                change.inserted.extend_from_slice(text);
+            },
+            _                                                                           => {
+                self.undo.push_back(Change { start, removed, inserted: text.to_vec(), cursor });
+
+                if self.undo.len() > UNDO_LIMIT {
+                    self.undo.pop_front();
+                }
+            }
+        }
+
+        self.redo.clear();
+        self.last_edit = Some(edit);
+    }
+
+    // replace the text between two locations moving the cursor to the end of the new text
+    // and clearing the selection, returning the text replaced.
+    fn splice(&mut self, start: Location, end: Location, text: &[char]) -> Vec<char> {
+        let removed = self.range(start, end);
+        let mut tail = self.lines[end.line].split_off(end.column);
+        let mut inserted = text.split(|&ch| ch == '\n').map(|line| line.to_vec());
+
+        self.lines[start.line].truncate(start.column);
+        self.lines[start.line].extend(inserted.next().unwrap_or_default());
+
+        let new_lines: Vec<Vec<char>> = inserted.collect();
+        let last = start.line + new_lines.len();
+
+        self.lines.splice(start.line + 1..=end.line, new_lines);
+        self.cursor = text_end(start, text);
+        self.lines[last].append(&mut tail);
+        self.anchor = None;
+
+        removed
+    }
+
+    // undo or redo a change returning `false` if there is nothing to undo or redo.
+    fn restore(&mut self, undo: bool) -> bool {
+        let change = if undo { self.undo.pop_back() } else { self.redo.pop() };
+
+        match change {
+            Some(change) => {
+                if undo {
+                    self.splice(change.start, text_end(change.start, &change.inserted), &change.removed);
+                    self.cursor = self.clamp(change.cursor);
+                    self.redo.push(change);
+                } else {
+                    self.splice(change.start, text_end(change.start, &change.removed), &change.inserted);
+                    self.undo.push_back(change);
+                }
+
+                self.last_edit = None;
+
+                true
+            },
+            None         => false
+        }
+    }
+}
+
+impl ScrollTarget for TextArea {
+    fn scroll_state(&self, axis: ScrollAxis) -> result!(ScrollState) {
+        let viewport = self.viewport;
+
+        Ok(match axis {
+            ScrollAxis::Vertical   => ScrollState::new(self.rows(viewport).len(), usize::from(viewport.lines), self.top),
+            ScrollAxis::Horizontal => {
+                let columns = usize::from(viewport.columns.saturating_sub(self.gutter()));
+
+                if self.wrap {
+                    ScrollState::new(columns, columns, 0)
+                } else {
+                    // the widest line and the cursor after it.
+                    let width = self.buffer.lines.iter().map(|line| line.iter().map(|&ch| usize::from(char_width(ch))).sum::<usize>()).max().unwrap_or(0);
+
+                    ScrollState::new(width + 1, columns, self.left)
+                }
+            }
+        })
+    }
+
+    fn set_scroll_position(&mut self, axis: ScrollAxis, position: usize) -> result!(()) {
+        let position = cmp::min(position, self.scroll_state(axis)?.max_position());
+
+        match axis {
+            ScrollAxis::Vertical   => self.top = position,
+            ScrollAxis::Horizontal => self.left = if self.wrap { 0 } else { position }
+        }
+
+        Ok(())
+    }
+}
+
+// the display row containing a location.
+fn row_index(rows: &[Row], location: Location) -> usize {
+    rows.iter()
+        .rposition(|row| row.line == location.line && row.start <= location.column)
+        .unwrap_or(0)
+}
+
+// the location at the end of text inserted at a location.
+fn text_end(start: Location, text: &[char]) -> Location {
+    text.iter().fold(start, |location, &ch| match ch {
+        '\n' => Location { line: location.line + 1, column: 0 },
+        _    => Location { column: location.column + 1, ..location }
+    })
+}
+
+// split lines into display rows soft wrapped at a width in columns.
+fn wrap_rows(lines: &[Vec<char>], width: Option<usize>) -> Vec<Row> {
+    let mut rows = Vec::with_capacity(lines.len());
+
+    for (line, chars) in lines.iter().enumerate() {
+        let width = match width {
+            Some(width) => width,
+            None        => {
+                rows.push(Row { line, start: 0, end: chars.len() });
+
+                continue;
+            }
+        };
+
+        let mut start = 0;
+        let mut columns = 0;
+
+        for (column, &ch) in chars.iter().enumerate() {
+            let ch_width = usize::from(char_width(ch));
+
+            if columns + ch_width > width && column > start {
+                rows.push(Row { line, start, end: column });
+                start = column;
+                columns = 0;
+            }
+
+            columns += ch_width;
+        }
+
+        rows.push(Row { line, start, end: chars.len() });
+    }
+
+    rows
+}
+
+#[test]
+fn text_area_test() {
+    let lines: Vec<Vec<char>> = vec!("abcdefgh".chars().collect(), vec!());
+
+    assert_eq!(wrap_rows(&lines, Some(3)), vec!(
+        Row { line: 0, start: 0, end: 3 },
+        Row { line: 0, start: 3, end: 6 },
+        Row { line: 0, start: 6, end: 8 },
+        Row { line: 1, start: 0, end: 0 }
+    ));
+
+    let mut buffer = TextBuffer::new();
+
+    buffer.set_text("one\ntwo");
+    buffer.cursor = Location { line: 0, column: 3 };
+    "!?".chars().for_each(|ch| buffer.type_char(ch));
+    buffer.insert_str("\nthree");
+    assert_eq!(buffer.text(), "one!?\nthree\ntwo");
+    assert_eq!(buffer.cursor, Location { line: 1, column: 5 });
+    assert!(buffer.restore(true));
+    assert_eq!((buffer.text().as_str(), buffer.cursor), ("one!?\ntwo", Location { line: 0, column: 5 }));
+    assert!(buffer.restore(true));
+    assert_eq!((buffer.text().as_str(), buffer.cursor), ("one\ntwo", Location { line: 0, column: 3 }));
+    assert!(!buffer.restore(true));
+    assert!(buffer.restore(false));
+    assert!(buffer.restore(false));
+    assert_eq!((buffer.text().as_str(), buffer.cursor), ("one!?\nthree\ntwo", Location { line: 1, column: 5 }));
+    assert!(!buffer.restore(false));
+
+    buffer.anchor = Some(Location { line: 0, column: 1 });
+    buffer.cursor = Location { line: 2, column: 1 };
+    buffer.type_char('X');
+    assert_eq!((buffer.text().as_str(), buffer.cursor, buffer.anchor), ("oXwo", Location { line: 0, column: 2 }, None));
+    assert!(buffer.restore(true));
+    assert_eq!(buffer.text(), "one!?\nthree\ntwo");
+
+    buffer.overwrite = true;
+    buffer.cursor = Location { line: 2, column: 1 };
+    "ABC".chars().for_each(|ch| buffer.type_char(ch));
+    assert_eq!(buffer.text(), "one!?\nthree\ntABC");
+    assert!(buffer.restore(true));
+    assert_eq!((buffer.text().as_str(), buffer.cursor), ("one!?\nthree\ntwo", Location { line: 2, column: 1 }));
+
+    buffer.overwrite = false;
+    buffer.cursor = Location { line: 1, column: 0 };
+    buffer.backspace();
+    buffer.delete();
+    assert_eq!(buffer.text(), "one!?hree\ntwo");
+    assert_eq!(buffer.undo.len(), 4);
+}
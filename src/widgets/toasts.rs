@@ -0,0 +1,584 @@
+/*
+    src/widgets/toasts.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{cmp, fmt, time::{Duration, Instant}};
+use ncursesw::AttributesColorPairSet;
+use crate::{
+    Origin, Size, NCurseswWinError, Window, NCurseswWindow, HasYXAxis, Panel, CellBuffer, Cell, Timeout,
+    BoxDrawingType, BoxDrawingTypeDetail, LINES, COLS, doupdate, update_panels, normal, extend,
+    widgets::funcs::{mvadd_cells, wrap_str},
+    funcs::{bold_attributes_color_pair, default_attributes_color_pair, truncate_str}
+};
+
+// the default width of a toast including it's border.
+const DEFAULT_COLUMNS: u16 = 40;
+
+// the default time a toast is shown for.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(4);
+
+// the time between the steps of a toast sliding in or moving in the stack.
+const FRAME: Duration = Duration::from_millis(20);
+
+// the number of steps a toast takes to slide in.
+const SLIDE_STEPS: u16 = 8;
+
+/// The identifier of a toast.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ToastId(u64);
+
+/// The severity of a toast.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ToastSeverity {
+    /// Information.
+    Info,
+    /// An operation succeeded.
+    Success,
+    /// A warning.
+    Warning,
+    /// An error.
+    Error
+}
+
+impl fmt::Display for ToastSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match self {
+            ToastSeverity::Info    => "Info",
+            ToastSeverity::Success => "Success",
+            ToastSeverity::Warning => "Warning",
+            ToastSeverity::Error   => "Error"
+        })
+    }
+}
+
+/// The corner of the screen toasts are stacked in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ToastCorner {
+    /// The upper left corner, toasts stack downwards.
+    TopLeft,
+    /// The upper right corner, toasts stack downwards.
+    TopRight,
+    /// The lower left corner, toasts stack upwards.
+    BottomLeft,
+    /// The lower right corner, toasts stack upwards.
+    BottomRight
+}
+
+/// The attributes and color pairs of toasts of each severity.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ToastStyles {
+    /// The style of information toasts.
+    pub info:    AttributesColorPairSet,
+    /// The style of success toasts.
+    pub success: AttributesColorPairSet,
+    /// The style of warning toasts.
+    pub warning: AttributesColorPairSet,
+    /// The style of error toasts.
+    pub error:   AttributesColorPairSet
+}
+
+impl ToastStyles {
+    /// Create new toast styles.
+    pub fn new(
+        info:    AttributesColorPairSet,
+        success: AttributesColorPairSet,
+        warning: AttributesColorPairSet,
+        error:   AttributesColorPairSet) -> Self
+    {
+        Self { info, success, warning, error }
+    }
+
+    /// Create new toast styles of white on blue, black on green, black on yellow
+    /// and white on red allocating the color pairs using `normal::alloc_pair()`.
+    pub fn normal() -> result!(Self) {
+        let style = |foreground, background| -> result!(AttributesColorPairSet) {
+            let color_pair = normal::alloc_pair(normal::Colors::new(normal::Color::new(foreground), normal::Color::new(background)))?;
+
+            Ok(AttributesColorPairSet::Normal(normal::AttributesColorPair::new(normal::Attributes::default(), color_pair)))
+        };
+
+        Ok(Self::new(
+            style(normal::ColorPalette::White, normal::ColorPalette::Blue)?,
+            style(normal::ColorPalette::Black, normal::ColorPalette::Green)?,
+            style(normal::ColorPalette::Black, normal::ColorPalette::Yellow)?,
+            style(normal::ColorPalette::White, normal::ColorPalette::Red)?
+        ))
+    }
+
+    /// Create new toast styles of white on blue, black on green, black on yellow
+    /// and white on red allocating the color pairs using `extend::alloc_pair()`.
+    pub fn extend() -> result!(Self) {
+        let style = |foreground, background| -> result!(AttributesColorPairSet) {
+            let color_pair = extend::alloc_pair(extend::Colors::new(extend::Color::new(foreground), extend::Color::new(background)))?;
+
+            Ok(AttributesColorPairSet::Extend(extend::AttributesColorPair::new(extend::Attributes::default(), color_pair)))
+        };
+
+        Ok(Self::new(
+            style(extend::ColorPalette::White, extend::ColorPalette::Blue)?,
+            style(extend::ColorPalette::Black, extend::ColorPalette::Green)?,
+            style(extend::ColorPalette::Black, extend::ColorPalette::Yellow)?,
+            style(extend::ColorPalette::White, extend::ColorPalette::Red)?
+        ))
+    }
+
+    /// The attributes and color pair of a severity.
+    pub fn style(&self, severity: ToastSeverity) -> AttributesColorPairSet {
+        match severity {
+            ToastSeverity::Info    => self.info,
+            ToastSeverity::Success => self.success,
+            ToastSeverity::Warning => self.warning,
+            ToastSeverity::Error   => self.error
+        }
+    }
+}
+
+impl Default for ToastStyles {
+    // without colors warnings and errors are bold.
+    fn default() -> Self {
+        let style = default_attributes_color_pair();
+
+        Self::new(style, style, bold_attributes_color_pair(style), bold_attributes_color_pair(style))
+    }
+}
+
+// the panel and window of a toast on the screen, the panel is dropped before it's window.
+struct ToastView {
+    panel:  Panel,
+    window: Window
+}
+
+struct Toast {
+    id:       ToastId,
+    severity: ToastSeverity,
+    text:     String,
+    timeout:  Duration,
+    expires:  Option<Instant>,      // set when the toast is first shown or first doesn't fit.
+    buffer:   Option<CellBuffer>,   // the toast drawn at it's full size.
+    view:     Option<ToastView>,
+    y:        u16,                  // the current line of the toast.
+    shown:    u16                   // the columns of the toast that have slid in.
+}
+
+/// Non-modal toast notifications stacked in a corner of the screen.
+///
+/// Each toast is a bordered window on it's own `Panel` above the other
+/// panels, it slides into the corner pushing older toasts along the stack
+/// and disappears after a timeout. Toasts that don't fit on the screen wait
+/// until the toasts in front of them have gone.
+///
+/// The toasts are animated by calling `update()` from the event loop and
+/// using `timeout()` as the timeout when reading a key, any windows
+/// underneath should be refreshed with `update_panels()`.
+///
+/// ```text
+/// let mut toasts = Toasts::new(ToastCorner::TopRight);
+///
+/// toasts.set_styles(ToastStyles::normal()?);
+/// toasts.push(ToastSeverity::Info, "File saved");
+///
+/// loop {
+///     toasts.update()?;
+///
+///     match window.get_wch_nonblocking(toasts.timeout())? {
+///         ...
+///     }
+/// }
+/// ```
+pub struct Toasts {
+    corner:  ToastCorner,
+    columns: u16,
+    timeout: Duration,
+    border:  BoxDrawingType,
+    styles:  ToastStyles,
+    parent:  Option<(Origin, Size)>,
+    visible: bool,
+    pending: bool,                  // the toasts have changed since the last update.
+    next_id: u64,
+    toasts:  Vec<Toast>             // the newest toast first.
+}
+
+impl Toasts {
+    /// Create new toasts stacked in a corner of the screen.
+    pub fn new(corner: ToastCorner) -> Self {
+        Self {
+            corner,
+            columns: DEFAULT_COLUMNS,
+            timeout: DEFAULT_TIMEOUT,
+            border:  BoxDrawingType::Light(BoxDrawingTypeDetail::Normal),
+            styles:  ToastStyles::default(),
+            parent:  None,
+            visible: true,
+            pending: false,
+            next_id: 0,
+            toasts:  vec!()
+        }
+    }
+
+    /// Stack the toasts in a corner of a parent window instead of the screen.
+    pub fn set_parent(&mut self, parent: &Window) -> result!(()) {
+        self.parent = Some((parent.origin()?, parent.size()?));
+        self.relayout();
+
+        Ok(())
+    }
+
+    /// Set the corner the toasts are stacked in.
+    pub fn set_corner(&mut self, corner: ToastCorner) {
+        self.corner = corner;
+        self.relayout();
+    }
+
+    /// Set the width of a toast including it's border.
+    pub fn set_columns(&mut self, columns: u16) {
+        self.columns = cmp::max(columns, 5);
+        self.relayout();
+    }
+
+    /// Set the time a toast is shown for unless pushed with it's own timeout.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Set the box drawing type of a toast's border.
+    pub fn set_border(&mut self, border: BoxDrawingType) {
+        self.border = border;
+        self.relayout();
+    }
+
+    /// Set the attributes and color pairs of each severity, use
+    /// `ToastStyles::normal()` or `ToastStyles::extend()` for colored toasts.
+    pub fn set_styles(&mut self, styles: ToastStyles) {
+        self.styles = styles;
+        self.relayout();
+    }
+
+    /// Show a toast of a severity for the default timeout.
+    pub fn push(&mut self, severity: ToastSeverity, text: &str) -> ToastId {
+        self.push_with_timeout(severity, text, self.timeout)
+    }
+
+    /// Show a toast of a severity for a timeout.
+    pub fn push_with_timeout(&mut self, severity: ToastSeverity, text: &str, timeout: Duration) -> ToastId {
+        let id = ToastId(self.next_id);
+
+        self.next_id += 1;
+        self.toasts.insert(0, Toast { id, severity, text: text.to_string(), timeout, expires: None, buffer: None, view: None, y: 0, shown: 0 });
+        self.pending = true;
+
+        id
+    }
+
+    /// Remove a toast before it's timeout, returns `false` if it has already gone.
+    pub fn dismiss(&mut self, id: ToastId) -> bool {
+        let len = self.toasts.len();
+
+        self.toasts.retain(|toast| toast.id != id);
+        self.pending = true;
+
+        self.toasts.len() != len
+    }
+
+    /// Remove all toasts.
+    pub fn dismiss_all(&mut self) {
+        self.toasts.clear();
+        self.pending = true;
+    }
+
+    /// The number of toasts waiting or shown.
+    pub fn len(&self) -> usize {
+        self.toasts.len()
+    }
+
+    /// Are there no toasts waiting or shown.
+    pub fn is_empty(&self) -> bool {
+        self.toasts.is_empty()
+    }
+
+    /// Are the toasts visible.
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Hide or show the toasts, for example while a modal dialog is displayed.
+    pub fn set_visible(&mut self, visible: bool) -> result!(()) {
+        if visible != self.visible {
+            for view in self.toasts.iter().filter_map(|toast| toast.view.as_ref()) {
+                if visible {
+                    view.panel.show_panel()?;
+                } else {
+                    view.panel.hide_panel()?;
+                }
+            }
+
+            self.visible = visible;
+            self.pending = true;
+        }
+
+        Ok(())
+    }
+
+    /// The time until the toasts next need updating, `None` if there are no
+    /// toasts that can be shown. Use this as the timeout when reading a key in
+    /// the event loop.
+    pub fn timeout(&self) -> Timeout {
+        if self.pending {
+            return Some(Duration::ZERO);
+        }
+
+        if !self.visible || self.toasts.is_empty() {
+            return None;
+        }
+
+        if self.toasts.iter().any(|toast| toast.view.is_some() && toast.buffer.as_ref().is_some_and(|buffer| toast.shown < buffer.size().columns)) {
+            return Some(FRAME);
+        }
+
+        let now = Instant::now();
+
+        // toasts without an expiry can't be shown until the area is larger.
+        self.toasts.iter()
+            .filter_map(|toast| toast.expires)
+            .min()
+            .map(|expires| expires.saturating_duration_since(now))
+    }
+
+    /// Remove expired toasts and move the toasts a step, updating the screen
+    /// with `update_panels()` and `doupdate()` if anything changed. Returns
+    /// `true` if the screen was updated.
+    pub fn update(&mut self) -> result!(bool) {
+        let changed = self.step(Instant::now())?;
+
+        if changed {
+            update_panels();
+            doupdate()?;
+        }
+
+        Ok(changed)
+    }
+
+    /// Remove expired toasts and move the toasts a step without updating the
+    /// screen, returns `true` if the panels have changed.
+    pub fn step(&mut self, now: Instant) -> result!(bool) {
+        if !self.visible {
+            return Ok(false);
+        }
+
+        let mut changed = self.pending;
+        let count = self.toasts.len();
+
+        self.pending = false;
+        self.toasts.retain(|toast| toast.expires.is_none_or(|expires| expires > now));
+        changed |= self.toasts.len() != count;
+
+        let (area_origin, area_size) = self.area()?;
+        let columns = cmp::min(self.columns, area_size.columns);
+
+        if columns < 5 || area_size.lines < 3 {
+            return Ok(changed);
+        }
+
+        // toasts taller than the area (for example after the terminal has shrunk) are redrawn to fit.
+        for toast in self.toasts.iter_mut().filter(|toast| toast.buffer.as_ref().is_none_or(|buffer| buffer.size().lines > area_size.lines)) {
+            toast.buffer = Some(draw_toast(toast, Size { lines: area_size.lines, columns }, self.border, self.styles.style(toast.severity))?);
+            changed |= toast.view.take().is_some();
+        }
+
+        let heights: Vec<u16> = self.toasts.iter().map(|toast| toast.buffer.as_ref().map_or(0, |buffer| buffer.size().lines)).collect();
+        let targets = stack_lines(&heights, self.corner, area_size.lines);
+        let step = cmp::max(columns / SLIDE_STEPS, 1);
+
+        // the newest toasts are raised last so they are on top.
+        for (toast, target) in self.toasts.iter_mut().zip(targets).rev() {
+            let buffer = match &toast.buffer {
+                Some(buffer) => buffer,
+                None         => continue
+            };
+            let size = buffer.size();
+
+            let target = match target {
+                Some(target) => area_origin.y + target,
+                None         => {
+                    // wait until there is room, sliding in again, the toast still expires while it's waiting.
+                    changed |= toast.view.take().is_some();
+                    toast.expires.get_or_insert(now + toast.timeout);
+
+                    continue;
+                }
+            };
+
+            if toast.view.is_none() {
+                toast.y = target;
+                toast.shown = 0;
+                toast.expires.get_or_insert(now + toast.timeout);
+            }
+
+            let y = match toast.y.cmp(&target) {
+                cmp::Ordering::Less    => toast.y + 1,
+                cmp::Ordering::Greater => toast.y - 1,
+                cmp::Ordering::Equal   => toast.y
+            };
+            let shown = cmp::min(toast.shown + step, size.columns);
+
+            if shown != toast.shown {
+                // slide in by replacing the window with a wider one.
+                let x = match self.corner {
+                    ToastCorner::TopLeft | ToastCorner::BottomLeft   => area_origin.x,
+                    ToastCorner::TopRight | ToastCorner::BottomRight => area_origin.x + area_size.columns - shown
+                };
+                let offset = match self.corner {
+                    ToastCorner::TopLeft | ToastCorner::BottomLeft   => size.columns - shown,
+                    ToastCorner::TopRight | ToastCorner::BottomRight => 0
+                };
+                let window = Window::new(Size { lines: size.lines, columns: shown }, Origin { y, x })?;
+
+                for line in 0..size.lines {
+                    let cells: Vec<Cell> = (0..size.columns).map(|x| *buffer.cell(Origin { y: line, x })).collect();
+
+                    mvadd_cells(&window, Origin { y: line, x: 0 }, &cells, usize::from(offset), shown)?;
+                }
+
+                match &mut toast.view {
+                    Some(view) => {
+                        view.panel.replace_panel(&window)?;
+                        view.panel.move_panel(Origin { y, x })?;
+                        view.window = window;
+                    },
+                    None       => toast.view = Some(ToastView { panel: Panel::new(&window)?, window })
+                }
+
+                toast.shown = shown;
+                changed = true;
+            } else if y != toast.y {
+                if let Some(view) = &toast.view {
+                    let x = view.window.origin()?.x;
+
+                    view.panel.move_panel(Origin { y, x })?;
+                }
+
+                changed = true;
+            }
+
+            toast.y = y;
+
+            if let Some(view) = &toast.view {
+                view.panel.top_panel()?;
+            }
+        }
+
+        // keep sliding while any toast is moving in the stack.
+        self.pending = self.toasts.iter().zip(stack_lines(&heights, self.corner, area_size.lines))
+            .any(|(toast, target)| toast.view.is_some() && target.is_some_and(|target| toast.y != area_origin.y + target));
+
+        Ok(changed)
+    }
+
+    // the origin and size of the screen or parent window the toasts are stacked in.
+    fn area(&self) -> result!((Origin, Size)) {
+        match self.parent {
+            Some(parent) => Ok(parent),
+            None         => Ok((Origin::default(), Size { lines: LINES()?, columns: COLS()? }))
+        }
+    }
+
+    // redraw and slide in the toasts again.
+    fn relayout(&mut self) {
+        for toast in &mut self.toasts {
+            toast.buffer = None;
+            toast.view = None;
+        }
+
+        self.pending = true;
+    }
+}
+
+impl fmt::Debug for Toasts {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Toasts {{ corner: {:?}, columns: {}, timeout: {:?}, toasts: {}, visible: {} }}", self.corner, self.columns, self.timeout, self.toasts.len(), self.visible)
+    }
+}
+
+// draw a toast at it's full size with it's severity as the title, text that is taller
+// than the maximum size is truncated with an ellipsis.
+fn draw_toast(toast: &Toast, max_size: Size, border: BoxDrawingType, style: AttributesColorPairSet) -> result!(CellBuffer) {
+    let columns = max_size.columns;
+    let mut text = wrap_str(&toast.text, columns - 4);
+    let max_lines = usize::from(max_size.lines - 2);
+
+    if text.len() > max_lines {
+        text.truncate(max_lines);
+
+        if let Some(last) = text.last_mut() {
+            *last = truncate_str(&format!("{} \u{2026}", last), columns - 4, true);
+        }
+    }
+
+    let size = Size { lines: u16::try_from(text.len())? + 2, columns };
+    let mut buffer = CellBuffer::new(size);
+
+    buffer.set_attributes_color_pair(style);
+
+    for line in 0..size.lines {
+        buffer.mvaddstr(Origin { y: line, x: 0 }, " ".repeat(usize::from(columns)));
+    }
+
+    buffer.mvtbox_set(Origin::default(), size, border)?;
+    buffer.mvaddnstr(Origin { y: 0, x: 2 }, format!(" {} ", toast.severity), Some(columns - 4));
+
+    for (line, text) in (1..).zip(text) {
+        buffer.mvaddstr(Origin { y: line, x: 2 }, text);
+    }
+
+    Ok(buffer)
+}
+
+// the lines of a stack of toasts of heights from the newest within a number
+// of lines, `None` for the toasts that don't fit. A toast taller than the lines
+// never fits so it doesn't stop the older toasts from being shown.
+fn stack_lines(heights: &[u16], corner: ToastCorner, lines: u16) -> Vec<Option<u16>> {
+    let mut used = 0;
+
+    heights.iter().map(|&height| {
+        if height > lines {
+            None
+        } else if used + height > lines {
+            used = lines;
+
+            None
+        } else {
+            used += height;
+
+            Some(match corner {
+                ToastCorner::TopLeft | ToastCorner::TopRight       => used - height,
+                ToastCorner::BottomLeft | ToastCorner::BottomRight => lines - used
+            })
+        }
+    }).collect()
+}
+
+#[test]
+fn toasts_test() {
+    assert_eq!(stack_lines(&[3, 4, 3], ToastCorner::TopRight, 10), vec!(Some(0), Some(3), Some(7)));
+    assert_eq!(stack_lines(&[3, 4, 3], ToastCorner::BottomLeft, 10), vec!(Some(7), Some(3), Some(0)));
+    assert_eq!(stack_lines(&[3, 8, 3], ToastCorner::TopLeft, 10), vec!(Some(0), None, None));
+    assert_eq!(stack_lines(&[12, 3, 4], ToastCorner::TopLeft, 10), vec!(None, Some(0), Some(3)));
+    assert_eq!(stack_lines(&[12, 3], ToastCorner::BottomRight, 10), vec!(None, Some(7)));
+}
@@ -0,0 +1,556 @@
+/*
+    src/widgets/treeview.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::fmt;
+use ncursesw::AttributesColorPairSet;
+use crate::{
+    Origin, Size, NCurseswWinError, HasMvAddFunctions, Mouse, CharacterResult, WideChar, KeyBinding,
+    BoxDrawingType, BoxDrawingTypeDetail, BoxDrawingGraphic, wide_box_graphic,
+    widgets::{selection::Selection, funcs::{is_button_1, region_origin, wheel_rows}},
+    funcs::{default_attributes_color_pair, mvadd_str_columns, reverse_attributes_color_pair}
+};
+
+const COLLAPSED: char = '\u{25b8}';
+const EXPANDED: char = '\u{25be}';
+const GUIDE_COLUMNS: usize = 3;
+
+/// The loader of a `TreeView`, called with the labels from a root to the
+/// node being expanded and returning the node's children.
+pub type TreeLoader = Box<dyn FnMut(&[String]) -> Vec<TreeItem>>;
+
+/// The identifier of a node of a `TreeView`, it's invalid once the node's
+/// parent has been reloaded.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TreeNodeId(usize, u32); // the index of the node and it's generation.
+
+/// An item returned by a `TreeView`'s loader.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TreeItem {
+    label:        String,
+    has_children: bool
+}
+
+impl TreeItem {
+    /// An item without children.
+    pub fn leaf(label: &str) -> Self {
+        Self { label: label.to_string(), has_children: false }
+    }
+
+    /// An item that may have children, they are loaded when it's expanded.
+    pub fn branch(label: &str) -> Self {
+        Self { label: label.to_string(), has_children: true }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct TreeNode {
+    label:        String,
+    parent:       Option<usize>,
+    children:     Option<Vec<usize>>, // `None` until the children are loaded.
+    has_children: bool,
+    expanded:     bool,
+    generation:   u32                 // incremented when the node is discarded.
+}
+
+// a displayed node with it's depth and whether it and each of it's ancestors is the last of it's siblings.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct TreeRow {
+    node: usize,
+    last: Vec<bool>
+}
+
+/// A tree of expandable and collapsible nodes with box drawing guide lines.
+///
+/// The children of a node are loaded when the node is first expanded by
+/// calling the loader. The selected node is moved with the up and down
+/// arrows, page up/down, home and end keys, the right arrow expands a node
+/// or moves to it's first child, the left arrow collapses a node or moves to
+/// it's parent and enter or space toggles a node. A button 1 click on a
+/// node's expander toggles it and elsewhere selects it. When a filter is set
+/// only nodes whose labels contain it, and their ancestors, are shown.
+pub struct TreeView {
+    nodes:          Vec<TreeNode>,
+    free:           Vec<usize>,        // the nodes discarded by a reload to be reused.
+    roots:          Vec<usize>,
+    loader:         TreeLoader,
+    rows:           Vec<TreeRow>,
+    selection:      Selection,
+    filter:         Option<String>,
+    guides:         BoxDrawingType,
+    style:          Option<AttributesColorPairSet>,
+    selected_style: Option<AttributesColorPairSet>,
+    size:           Size // the size of the tree view from the last render.
+}
+
+impl TreeView {
+    /// Create a new tree view of root items, the loader is called to load
+    /// the children of a node when it's first expanded.
+    pub fn new<F>(roots: Vec<TreeItem>, loader: F) -> Self
+        where F: FnMut(&[String]) -> Vec<TreeItem> + 'static
+    {
+        let mut tree_view = Self {
+            nodes:          vec!(),
+            free:           vec!(),
+            roots:          vec!(),
+            loader:         Box::new(loader),
+            rows:           vec!(),
+            selection:      Selection::new(0),
+            filter:         None,
+            guides:         BoxDrawingType::Light(BoxDrawingTypeDetail::Normal),
+            style:          None,
+            selected_style: None,
+            size:           Size::default()
+        };
+
+        tree_view.roots = tree_view.add_nodes(None, roots);
+        tree_view.rebuild(None);
+
+        tree_view
+    }
+
+    /// The root nodes.
+    pub fn roots(&self) -> Vec<TreeNodeId> {
+        self.roots.iter().map(|&node| self.node_id(node)).collect()
+    }
+
+    /// Is a node's identifier valid, it's invalid once the node's parent has been reloaded.
+    pub fn contains(&self, node: TreeNodeId) -> bool {
+        self.nodes.get(node.0).is_some_and(|tree_node| tree_node.generation == node.1)
+    }
+
+    /// The label of a node.
+    pub fn label(&self, node: TreeNodeId) -> &str {
+        &self.nodes[self.node_index("label", node)].label
+    }
+
+    /// The labels from the root to a node.
+    pub fn path(&self, node: TreeNodeId) -> Vec<String> {
+        let mut path = vec!();
+        let mut current = Some(self.node_index("path", node));
+
+        while let Some(node) = current {
+            path.push(self.nodes[node].label.clone());
+            current = self.nodes[node].parent;
+        }
+
+        path.reverse();
+
+        path
+    }
+
+    /// The parent of a node, `None` for a root node.
+    pub fn parent(&self, node: TreeNodeId) -> Option<TreeNodeId> {
+        self.nodes[self.node_index("parent", node)].parent.map(|parent| self.node_id(parent))
+    }
+
+    /// The loaded children of a node, `None` if they have not been loaded.
+    pub fn children(&self, node: TreeNodeId) -> Option<Vec<TreeNodeId>> {
+        self.nodes[self.node_index("children", node)].children.as_ref().map(|children| children.iter().map(|&child| self.node_id(child)).collect())
+    }
+
+    /// Is a node expanded.
+    pub fn is_expanded(&self, node: TreeNodeId) -> bool {
+        self.nodes[self.node_index("is_expanded", node)].expanded
+    }
+
+    /// Expand a node loading it's children if required.
+    pub fn expand(&mut self, node: TreeNodeId) {
+        let index = self.node_index("expand", node);
+
+        if self.nodes[index].has_children {
+            if self.nodes[index].children.is_none() {
+                let path = self.path(node);
+                let items = (self.loader)(&path);
+                let children = self.add_nodes(Some(index), items);
+
+                self.nodes[index].has_children = !children.is_empty();
+                self.nodes[index].children = Some(children);
+            }
+
+            self.nodes[index].expanded = self.nodes[index].has_children;
+            self.rebuild(self.selected());
+        }
+    }
+
+    /// Collapse a node.
+    pub fn collapse(&mut self, node: TreeNodeId) {
+        let index = self.node_index("collapse", node);
+
+        self.nodes[index].expanded = false;
+        self.rebuild(self.selected());
+    }
+
+    /// Expand a collapsed node or collapse an expanded node.
+    pub fn toggle(&mut self, node: TreeNodeId) {
+        if self.is_expanded(node) {
+            self.collapse(node);
+        } else {
+            self.expand(node);
+        }
+    }
+
+    /// Collapse a node and discard it's children so they are loaded again
+    /// when it's next expanded, the identifiers of the discarded nodes are
+    /// no longer valid.
+    pub fn reload(&mut self, node: TreeNodeId) {
+        let index = self.node_index("reload", node);
+
+        if let Some(children) = self.nodes[index].children.take() {
+            self.discard_nodes(children);
+        }
+
+        self.nodes[index].has_children = true;
+        self.collapse(node);
+    }
+
+    /// The selected node.
+    pub fn selected(&self) -> Option<TreeNodeId> {
+        self.selection.selected().map(|index| self.node_id(self.rows[index].node))
+    }
+
+    /// Select a node if it's displayed.
+    pub fn set_selected(&mut self, node: TreeNodeId) {
+        if let Some(index) = self.rows.iter().position(|row| self.node_id(row.node) == node) {
+            self.selection.select(Some(index), self.page());
+        }
+    }
+
+    /// The filter of the labels, `None` if not filtered.
+    pub fn filter(&self) -> Option<&str> {
+        self.filter.as_deref()
+    }
+
+    /// Only show nodes whose labels contain the filter ignoring case, and
+    /// their ancestors. Only loaded nodes are searched.
+    pub fn set_filter(&mut self, filter: Option<&str>) {
+        self.filter = filter.filter(|filter| !filter.is_empty()).map(|filter| filter.to_lowercase());
+        self.rebuild(self.selected());
+    }
+
+    /// Set the box drawing type of the guide lines.
+    pub fn set_guides(&mut self, guides: BoxDrawingType) {
+        self.guides = guides;
+    }
+
+    /// Set the attributes and color pair of the nodes.
+    pub fn set_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.style = style;
+    }
+
+    /// Set the attributes and color pair of the selected node, if `None` the
+    /// style is reversed.
+    pub fn set_selected_style(&mut self, style: Option<AttributesColorPairSet>) {
+        self.selected_style = style;
+    }
+
+    /// Handle a key returning `true` if the key was used by the tree view.
+    pub fn handle_key(&mut self, key: CharacterResult<WideChar>) -> bool {
+        let selected = self.selected();
+
+        match (key, selected) {
+            (CharacterResult::Key(KeyBinding::RightArrow), Some(node)) => {
+                if self.is_expanded(node) {
+                    if let Some(&child) = self.visible_children(node.0).first() {
+                        self.set_selected(self.node_id(child));
+                    }
+                } else {
+                    self.expand(node);
+                }
+            },
+            (CharacterResult::Key(KeyBinding::LeftArrow), Some(node))  => {
+                if self.is_expanded(node) {
+                    self.collapse(node);
+                } else if let Some(parent) = self.parent(node) {
+                    self.set_selected(parent);
+                }
+            },
+            (CharacterResult::Key(KeyBinding::Enter), Some(node))      => self.toggle(node),
+            (CharacterResult::Key(KeyBinding::MouseEvent), _)          => return false,
+            (CharacterResult::Key(key_binding), _)                     => return self.selection.handle_key(key_binding, self.page()),
+            (CharacterResult::Character(wch), Some(node))
+                if wch == WideChar::new(' ') || wch == WideChar::new('\n')     => self.toggle(node),
+            _                                                          => return false
+        }
+
+        true
+    }
+
+    /// Handle a mouse event with the tree view displayed at the screen origin,
+    /// returns `true` if the event was used by the tree view.
+    pub fn handle_mouse(&mut self, mouse: &Mouse, screen_origin: Origin) -> result!(bool) {
+        let events = mouse.events();
+
+        let origin = match region_origin(mouse.origin()?.origin(), screen_origin, self.size) {
+            Some(origin) => origin,
+            None         => return Ok(false)
+        };
+
+        if let Some(rows) = wheel_rows(events) {
+            self.selection.scroll_by(rows, self.page());
+        } else if is_button_1(events) {
+            let index = self.selection.top() + usize::from(origin.y);
+
+            if let Some(row) = self.rows.get(index) {
+                let node = self.node_id(row.node);
+                let expander = (row.last.len() - 1) * GUIDE_COLUMNS;
+
+                self.selection.select(Some(index), self.page());
+
+                if usize::from(origin.x) == expander {
+                    self.toggle(node);
+                }
+            }
+        } else {
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+
+    /// Render the tree view into the region of a window at origin of size.
+    pub fn render<W: HasMvAddFunctions>(&mut self, window: &W, origin: Origin, size: Size) -> result!(()) {
+        assert_origin!("render", window.size()?, origin);
+
+        self.size = size;
+        self.selection.set_count(self.rows.len(), self.page());
+
+        let style = self.style.unwrap_or_else(default_attributes_color_pair);
+        let selected_style = self.selected_style.unwrap_or_else(|| reverse_attributes_color_pair(style));
+        let visible = self.selection.visible(self.page());
+        let mut y = 0;
+
+        for index in visible {
+            let row_style = if self.selection.selected() == Some(index) { selected_style } else { style };
+
+            mvadd_str_columns(window, Origin { y: origin.y + y, x: origin.x }, &self.row_text(&self.rows[index])?, size.columns, row_style)?;
+            y += 1;
+        }
+
+        for y in y..size.lines {
+            mvadd_str_columns(window, Origin { y: origin.y + y, x: origin.x }, "", size.columns, style)?;
+        }
+
+        Ok(())
+    }
+
+    fn page(&self) -> usize {
+        usize::from(self.size.lines)
+    }
+
+    // the identifier of a node.
+    fn node_id(&self, node: usize) -> TreeNodeId {
+        TreeNodeId(node, self.nodes[node].generation)
+    }
+
+    // the index of a valid node.
+    fn node_index(&self, func: &str, node: TreeNodeId) -> usize {
+        assert!(self.contains(node), "TreeView::{}() : node is invalid, node={:?}", func, node);
+
+        node.0
+    }
+
+    // add the nodes of items reusing discarded nodes.
+    fn add_nodes(&mut self, parent: Option<usize>, items: Vec<TreeItem>) -> Vec<usize> {
+        items.into_iter().map(|item| {
+            let tree_node = TreeNode {
+                label:        item.label,
+                parent,
+                children:     None,
+                has_children: item.has_children,
+                expanded:     false,
+                generation:   0
+            };
+
+            match self.free.pop() {
+                Some(node) => {
+                    self.nodes[node] = TreeNode { generation: self.nodes[node].generation, ..tree_node };
+
+                    node
+                },
+                None       => {
+                    self.nodes.push(tree_node);
+
+                    self.nodes.len() - 1
+                }
+            }
+        }).collect()
+    }
+
+    // discard nodes and their descendants so they are reused, invalidating their identifiers.
+    fn discard_nodes(&mut self, mut nodes: Vec<usize>) {
+        while let Some(node) = nodes.pop() {
+            let tree_node = &mut self.nodes[node];
+
+            nodes.extend(tree_node.children.take().unwrap_or_default());
+            tree_node.label.clear();
+            tree_node.parent = None;
+            tree_node.generation = tree_node.generation.wrapping_add(1);
+            self.free.push(node);
+        }
+    }
+
+    // does a node or any of it's loaded descendants match the filter.
+    fn matches(&self, node: usize) -> bool {
+        match &self.filter {
+            None         => true,
+            Some(filter) => {
+                self.nodes[node].label.to_lowercase().contains(filter.as_str()) ||
+                self.nodes[node].children.as_ref().is_some_and(|children| children.iter().any(|&child| self.matches(child)))
+            }
+        }
+    }
+
+    // the displayed children of a node, when filtered the loaded children are shown.
+    fn visible_children(&self, node: usize) -> Vec<usize> {
+        match &self.nodes[node].children {
+            Some(children) if self.nodes[node].expanded || self.filter.is_some() => {
+                children.iter().copied().filter(|&child| self.matches(child)).collect()
+            },
+            _                                                                   => vec!()
+        }
+    }
+
+    // rebuild the displayed rows keeping a node selected.
+    fn rebuild(&mut self, selected: Option<TreeNodeId>) {
+        let roots: Vec<usize> = self.roots.iter().copied().filter(|&root| self.matches(root)).collect();
+        let mut rows = vec!();
+
+        self.push_rows(&roots, &[], &mut rows);
+        self.rows = rows;
+
+        let index = selected
+            .and_then(|selected| self.rows.iter().position(|row| row.node == selected.0))
+            .or(self.selection.selected());
+
+        self.selection.set_count(self.rows.len(), self.page());
+        self.selection.select(index, self.page());
+    }
+
+    fn push_rows(&self, nodes: &[usize], last: &[bool], rows: &mut Vec<TreeRow>) {
+        for (index, &node) in nodes.iter().enumerate() {
+            let mut node_last = last.to_vec();
+
+            node_last.push(index + 1 == nodes.len());
+            rows.push(TreeRow { node, last: node_last.clone() });
+
+            self.push_rows(&self.visible_children(node), &node_last, rows);
+        }
+    }
+
+    // the guide lines, expander and label of a row.
+    fn row_text(&self, row: &TreeRow) -> result!(String) {
+        let vertical = wide_box_graphic(self.guides, BoxDrawingGraphic::VerticalLine).as_char()?;
+        let horizontal = wide_box_graphic(self.guides, BoxDrawingGraphic::HorizontalLine).as_char()?;
+        let tee = wide_box_graphic(self.guides, BoxDrawingGraphic::LeftTee).as_char()?;
+        let corner = wide_box_graphic(self.guides, BoxDrawingGraphic::LowerLeftCorner).as_char()?;
+        let node = &self.nodes[row.node];
+        let depth = row.last.len() - 1;
+        let mut text = String::new();
+
+        // the roots are drawn without guide lines.
+        for (level, &last) in row.last.iter().enumerate().skip(1) {
+            if level == depth {
+                text.push(if last { corner } else { tee });
+                text.push(horizontal);
+            } else {
+                text.push(if last { ' ' } else { vertical });
+                text.push(' ');
+            }
+
+            text.push(' ');
+        }
+
+        text.push(if !node.has_children {
+            ' '
+        } else if node.expanded || (self.filter.is_some() && node.children.is_some()) {
+            EXPANDED
+        } else {
+            COLLAPSED
+        });
+        text.push(' ');
+        text.push_str(&node.label);
+
+        Ok(text)
+    }
+}
+
+impl fmt::Debug for TreeView {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TreeView {{ nodes: {:?}, roots: {:?}, selection: {:?}, filter: {:?} }}", self.nodes, self.roots, self.selection, self.filter)
+    }
+}
+
+#[test]
+fn tree_view_test() -> Result<(), NCurseswWinError> {
+    let mut tree_view = TreeView::new(vec!(TreeItem::branch("src"), TreeItem::leaf("README")), |path| {
+        match path.join("/").as_str() {
+            "src"         => vec!(TreeItem::branch("widgets"), TreeItem::leaf("lib.rs")),
+            "src/widgets" => vec!(TreeItem::leaf("table.rs")),
+            _             => vec!()
+        }
+    });
+
+    let src = tree_view.roots()[0];
+
+    tree_view.expand(src);
+
+    let widgets = tree_view.children(src).unwrap()[0];
+
+    tree_view.expand(widgets);
+
+    let rows = tree_view.rows.iter().map(|row| tree_view.row_text(row)).collect::<Result<Vec<String>, NCurseswWinError>>()?;
+
+    assert_eq!(rows, vec!(
+        "\u{25be} src",
+        "\u{251c}\u{2500} \u{25be} widgets",
+        "\u{2502}  \u{2514}\u{2500}   table.rs",
+        "\u{2514}\u{2500}   lib.rs",
+        "  README"
+    ));
+    assert_eq!(tree_view.path(tree_view.children(widgets).unwrap()[0]), vec!("src", "widgets", "table.rs"));
+
+    tree_view.collapse(src);
+    tree_view.set_filter(Some("TABLE"));
+
+    assert_eq!(tree_view.rows.len(), 3);
+
+    // reloading reuses the discarded nodes and invalidates their identifiers.
+    tree_view.set_filter(None);
+
+    let nodes = tree_view.nodes.len();
+
+    for _ in 0..2 {
+        tree_view.reload(src);
+        tree_view.expand(src);
+
+        let widgets = tree_view.children(src).unwrap()[0];
+
+        tree_view.expand(widgets);
+        assert_eq!(tree_view.nodes.len(), nodes);
+    }
+
+    assert!(!tree_view.contains(widgets));
+    assert!(tree_view.contains(tree_view.children(src).unwrap()[0]));
+    assert_eq!(tree_view.label(tree_view.children(src).unwrap()[1]), "lib.rs");
+
+    Ok(())
+}
@@ -20,9 +20,9 @@
     IN THE SOFTWARE.
 */
 
-use std::{ptr, fmt, hash::{Hash, Hasher}};
+use std::{ptr, fmt, hash::{Hash, Hasher}, io::{Write, Read}};
 use ncursesw::{SCREEN, WINDOW};
-use crate::{Screen, newscr, gen::*};
+use crate::{Screen, NCurseswWinError, newscr, gen::*, windowtext::WindowText};
 
 /// A moveable window canvas.
 ///
@@ -57,6 +57,45 @@ impl Window {
     pub fn screen(&self) -> Option<Screen> {
         self.screen.map(|screen| Screen::_from(screen, false))
     }
+
+    /// Save the window in a human readable text format.
+    ///
+    /// Unlike `putwin()` the format does not depend on the version of the NCurses library.
+    /// It's made up of the following lines (each cell is `<character code point in hex>/<attributes>/<colors>`
+    /// where attributes are `-` or names joined by `+` and colors are `-` for color pair 0 or the foreground
+    /// and background color numbers, a cell followed by `*<count>` is repeated `count` times):
+    ///
+    /// ```text
+    /// ncursesw-win window 1
+    /// size 2 4
+    /// origin 0 0
+    /// cursor 1 3
+    /// flags keypad scrollok
+    /// background 20/-/-
+    /// line 0 48/bold/1,-1 69/bold/1,-1 20/-/-*2
+    /// line 1 20/-/-*4
+    /// ```
+    pub fn save_to<W: Write>(&self, writer: &mut W) -> result!(()) {
+        writer
+            .write_all(WindowText::from_window(self)?.to_text().as_bytes())
+?;
+
+        Ok(())
+    }
+
+    /// Create a new window from one previously saved with `save_to()`.
+    ///
+    /// Color pairs are allocated for the saved colors using `alloc_pair()`, if
+    /// color has not been started then color pair 0 is used.
+    pub fn load_from<R: Read>(reader: &mut R) -> result!(Window) {
+        let mut text = String::new();
+
+        reader
+            .read_to_string(&mut text)
+?;
+
+        WindowText::from_text(&text)?.to_window()
+    }
 }
 
 impl NCurseswWindow for Window { }
@@ -0,0 +1,434 @@
+/*
+    src/windowtext.rs
+
+    Copyright (c) 2022 Stephen Whittle  All rights reserved.
+
+    Permission is hereby granted, free of charge, to any person obtaining a copy
+    of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom
+    the Software is furnished to do so, subject to the following conditions:
+    The above copyright notice and this permission notice shall be included
+    in all copies or substantial portions of the Software.
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL
+    THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+    IN THE SOFTWARE.
+*/
+
+use std::{collections::HashMap, fmt::Write, convert::TryFrom};
+use ncursesw::{
+    AttributesColorPairSet, ComplexChar, ComplexString, NCursesColorType, getcchar, ncurses_colortype,
+    shims::{
+        ncurses::attr_t,
+        constants::{
+            A_STANDOUT, A_UNDERLINE, A_REVERSE, A_BLINK, A_DIM, A_BOLD, A_ALTCHARSET, A_INVIS,
+            A_PROTECT, A_HORIZONTAL, A_LEFT, A_LOW, A_RIGHT, A_TOP, A_VERTICAL, A_ITALIC
+        }
+    }
+};
+use crate::{
+    Origin, Size, Window, NCurseswWinError, AttributesType, ColorPairColors, ColorPairType,
+    ColorsType, ColorType, normal, extend, gen::*
+};
+
+// the first line of the text format and it's version.
+const HEADER: &str = "ncursesw-win window 1";
+
+// the attributes held in the text format by name.
+const ATTRIBUTES: [(&str, attr_t); 16] = [
+    ("standout", A_STANDOUT), ("underline", A_UNDERLINE), ("reverse", A_REVERSE), ("blink", A_BLINK),
+    ("dim", A_DIM), ("bold", A_BOLD), ("altcharset", A_ALTCHARSET), ("invisible", A_INVIS),
+    ("protect", A_PROTECT), ("horizontal", A_HORIZONTAL), ("left", A_LEFT), ("low", A_LOW),
+    ("right", A_RIGHT), ("top", A_TOP), ("vertical", A_VERTICAL), ("italic", A_ITALIC)
+];
+
+// the window flags held in the text format by name.
+const FLAGS: [&str; 10] = [
+    "clearok", "idcok", "idlok", "immedok", "keypad", "leaveok", "nodelay", "notimeout", "scrollok", "syncok"
+];
+
+// a character cell as held in the text format.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(in crate) struct TextCell {
+    character:  char,
+    attributes: attr_t,            // only the attributes named in `ATTRIBUTES`.
+    colors:     Option<(i32, i32)> // foreground and background color numbers, `None` is color pair 0.
+}
+
+// the contents of a window as held in the text format.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(in crate) struct WindowText {
+    size:       Size,
+    origin:     Origin,
+    cursor:     Origin,
+    flags:      Vec<&'static str>,
+    background: TextCell,
+    lines:      Vec<Vec<TextCell>>
+}
+
+impl WindowText {
+    // read the contents of a window.
+    pub(in crate) fn from_window(window: &Window) -> result!(Self) {
+        let size = window.size()?;
+        let flags = [
+            window.is_cleared(), window.is_idcok(), window.is_idlok(), window.is_immedok(), window.is_keypad(),
+            window.is_leaveok(), window.is_nodelay(), window.is_notimeout(), window.is_scrollok(), window.is_syncok()
+        ];
+        let mut lines = Vec::with_capacity(usize::from(size.lines));
+
+        for y in 0..size.lines {
+            let complex_chars: Vec<ComplexChar> = ComplexString::into(window.mvin_wchnstr(Origin { y, x: 0 }, size.columns)?);
+
+            lines.push(complex_chars.into_iter().map(text_cell).collect::<result!(Vec<TextCell>)>()?);
+        }
+
+        Ok(Self {
+            size,
+            origin:     window.origin()?,
+            cursor:     window.cursor()?,
+            flags:      FLAGS.iter().zip(flags.iter()).filter(|(_, &set)| set).map(|(&flag, _)| flag).collect(),
+            background: text_cell(window.getbkgrnd()?)?,
+            lines
+        })
+    }
+
+    // create a new window of the contents.
+    pub(in crate) fn to_window(&self) -> result!(Window) {
+        let window = Window::new(self.size, self.origin)?;
+        let mut color_pairs = HashMap::new();
+
+        window.bkgrndset(self.background.complex_char(&mut color_pairs)?);
+
+        for (y, line) in self.lines.iter().enumerate().filter(|(_, line)| !line.is_empty()) {
+            let mut wchstr = ComplexString::with_capacity(line.len());
+
+            for cell in line {
+                wchstr.push(&cell.complex_char(&mut color_pairs)?);
+            }
+
+            window.mvadd_wchnstr(Origin { y: u16::try_from(y)?, x: 0 }, &wchstr, Some(u16::try_from(wchstr.len())?))?;
+        }
+
+        let flag = |name: &str| self.flags.contains(&name);
+
+        window.clearok(flag("clearok"))?;
+        window.idcok(flag("idcok"));
+        window.idlok(flag("idlok"))?;
+        window.immedok(flag("immedok"));
+        window.keypad(flag("keypad"))?;
+        window.leaveok(flag("leaveok"))?;
+        window.nodelay(flag("nodelay"))?;
+        window.notimeout(flag("notimeout"))?;
+        window.scrollok(flag("scrollok"))?;
+        window.syncok(flag("syncok"))?;
+        window.set_cursor(self.cursor)?;
+
+        Ok(window)
+    }
+
+    // the contents as text.
+    pub(in crate) fn to_text(&self) -> String {
+        let mut text = String::new();
+
+        let _ = writeln!(text, "{}", HEADER);
+        let _ = writeln!(text, "size {} {}", self.size.lines, self.size.columns);
+        let _ = writeln!(text, "origin {} {}", self.origin.y, self.origin.x);
+        let _ = writeln!(text, "cursor {} {}", self.cursor.y, self.cursor.x);
+        let _ = writeln!(text, "flags {}", if self.flags.is_empty() { "-".to_string() } else { self.flags.join(" ") });
+        let _ = writeln!(text, "background {}", self.background.to_text());
+
+        for (y, line) in self.lines.iter().enumerate() {
+            let _ = write!(text, "line {}", y);
+
+            // consecutive identical cells are held as one cell with a repeat count.
+            let mut cells = line.iter().peekable();
+
+            while let Some(cell) = cells.next() {
+                let mut count = 1;
+
+                while cells.peek() == Some(&cell) {
+                    cells.next();
+                    count += 1;
+                }
+
+                let _ = if count > 1 { write!(text, " {}*{}", cell.to_text(), count) } else { write!(text, " {}", cell.to_text()) };
+            }
+
+            text.push('\n');
+        }
+
+        text
+    }
+
+    // parse the contents from text.
+    pub(in crate) fn from_text(text: &str) -> result!(Self) {
+        let mut lines = text.lines().enumerate().map(|(number, line)| (number + 1, line));
+        let mut last = 0;
+        let mut next = |keyword: &str| -> result!((usize, Vec<&str>)) {
+            match lines.next() {
+                Some((number, line)) => {
+                    last = number;
+
+                    let mut fields: Vec<&str> = line.split_whitespace().collect();
+
+                    if fields.first() != Some(&keyword) {
+                        return Err(format_error(number, format!("expected '{}'", keyword)));
+                    }
+
+                    fields.remove(0);
+
+                    Ok((number, fields))
+                },
+                None                 => Err(format_error(last + 1, format!("expected '{}' but reached the end", keyword)))
+            }
+        };
+
+        let (number, header) = next("ncursesw-win")?;
+
+        if header != ["window", "1"] {
+            return Err(format_error(number, format!("expected '{}'", HEADER)));
+        }
+
+        let (number, size) = next("size")?;
+        let size = Size { lines: parse_field(number, &size, 0)?, columns: parse_field(number, &size, 1)? };
+        let (number, origin) = next("origin")?;
+        let origin = Origin { y: parse_field(number, &origin, 0)?, x: parse_field(number, &origin, 1)? };
+        let (number, cursor) = next("cursor")?;
+        let cursor = Origin { y: parse_field(number, &cursor, 0)?, x: parse_field(number, &cursor, 1)? };
+        let (number, flag_names) = next("flags")?;
+        let mut flags = vec!();
+
+        for name in flag_names.into_iter().filter(|&name| name != "-") {
+            flags.push(*FLAGS.iter().find(|&&flag| flag == name).ok_or_else(|| format_error(number, format!("unknown flag '{}'", name)))?);
+        }
+
+        let (number, background) = next("background")?;
+        let background = TextCell::from_text(number, background.first().copied().unwrap_or_default())?;
+        let mut window_lines = Vec::with_capacity(usize::from(size.lines));
+
+        for y in 0..size.lines {
+            let (number, fields) = next("line")?;
+
+            if parse_field::<u16>(number, &fields, 0)? != y {
+                return Err(format_error(number, format!("expected line {}", y)));
+            }
+
+            let mut line = vec!();
+
+            for field in &fields[1..] {
+                let (cell, count) = match field.rsplit_once('*') {
+                    Some((cell, count)) => (cell, count.parse::<usize>().map_err(|_| format_error(number, format!("invalid repeat '{}'", field)))?),
+                    None                => (*field, 1)
+                };
+
+                // check the length before extending so a large repeat can't exhaust memory.
+                if count > usize::from(size.columns) - line.len() {
+                    return Err(format_error(number, format!("more than {} cells", size.columns)));
+                }
+
+                line.extend(std::iter::repeat_n(TextCell::from_text(number, cell)?, count));
+            }
+
+            window_lines.push(line);
+        }
+
+        Ok(Self { size, origin, cursor, flags, background, lines: window_lines })
+    }
+}
+
+impl TextCell {
+    // the cell as text, `<character code point>/<attributes>/<foreground>,<background>`.
+    fn to_text(self) -> String {
+        let attributes: Vec<&str> = ATTRIBUTES.iter().filter(|(_, attr)| self.attributes & attr != 0).map(|(name, _)| *name).collect();
+
+        format!(
+            "{:x}/{}/{}",
+            u32::from(self.character),
+            if attributes.is_empty() { "-".to_string() } else { attributes.join("+") },
+            self.colors.map_or_else(|| "-".to_string(), |(foreground, background)| format!("{},{}", foreground, background))
+        )
+    }
+
+    // parse the cell from text.
+    fn from_text(number: usize, text: &str) -> result!(Self) {
+        let error = || format_error(number, format!("invalid cell '{}'", text));
+        let fields: Vec<&str> = text.split('/').collect();
+
+        if fields.len() != 3 {
+            return Err(error());
+        }
+
+        let character = u32::from_str_radix(fields[0], 16).ok().and_then(char::from_u32).ok_or_else(error)?;
+        let mut attributes = 0;
+
+        for name in fields[1].split('+').filter(|&name| name != "-") {
+            attributes |= ATTRIBUTES.iter().find(|(attr_name, _)| *attr_name == name).ok_or_else(error)?.1;
+        }
+
+        let colors = match fields[2] {
+            "-"    => None,
+            colors => {
+                let (foreground, background) = colors.split_once(',').ok_or_else(error)?;
+
+                Some((foreground.parse().map_err(|_| error())?, background.parse().map_err(|_| error())?))
+            }
+        };
+
+        Ok(Self { character, attributes, colors })
+    }
+
+    // the cell as a complex character allocating color pairs as required.
+    fn complex_char(self, color_pairs: &mut HashMap<(i32, i32), AttributesColorPairSet>) -> result!(ComplexChar) {
+        let attrs_colorpair = match self.colors {
+            None         => attributes_color_pair(self.attributes, None),
+            Some(colors) => *color_pairs.entry(colors).or_insert_with(|| attributes_color_pair(0, Some(colors)))
+        };
+
+        let attrs_colorpair = match attrs_colorpair {
+            AttributesColorPairSet::Normal(attrs_colorpair) => AttributesColorPairSet::Normal(
+                normal::AttributesColorPair::new(normal::Attributes::new(self.attributes), attrs_colorpair.color_pair())
+            ),
+            AttributesColorPairSet::Extend(attrs_colorpair) => AttributesColorPairSet::Extend(
+                extend::AttributesColorPair::new(extend::Attributes::new(self.attributes), attrs_colorpair.color_pair())
+            )
+        };
+
+        crate::funcs::complex_char(self.character, attrs_colorpair)
+    }
+}
+
+// read a complex character as a text cell.
+fn text_cell(complex_char: ComplexChar) -> result!(TextCell) {
+    let char_attr_pair = getcchar(complex_char)?;
+    let known_attributes = ATTRIBUTES.iter().fold(0, |attributes, (_, attr)| attributes | attr);
+
+    macro_rules! decode {
+        ($attrs_colorpair: ident) => {{
+            let color_pair = $attrs_colorpair.color_pair();
+            let colors = if color_pair.number() == 0 {
+                None
+            } else {
+                color_pair.colors().ok().map(|colors| (colors.foreground().number(), colors.background().number()))
+            };
+
+            ($attrs_colorpair.attributes().as_attr_t() & known_attributes, colors)
+        }}
+    }
+
+    let (attributes, colors) = match char_attr_pair.attributes_and_color_pair() {
+        AttributesColorPairSet::Normal(attrs_colorpair) => decode!(attrs_colorpair),
+        AttributesColorPairSet::Extend(attrs_colorpair) => decode!(attrs_colorpair)
+    };
+
+    Ok(TextCell { character: char_attr_pair.character().as_char()?, attributes, colors })
+}
+
+// the attributes and color pair of the current color type, if the color pair of the
+// colors can't be allocated (for example color has not been started) color pair 0 is used.
+fn attributes_color_pair(attributes: attr_t, colors: Option<(i32, i32)>) -> AttributesColorPairSet {
+    match ncurses_colortype() {
+        NCursesColorType::Normal => {
+            let color_pair = colors.and_then(|(foreground, background)| {
+                let color = |number: i32| normal::Color::new(i16::try_from(number).map_or_else(|_| normal::ColorPalette::TerminalDefault, normal_color_palette));
+
+                normal::alloc_pair(normal::Colors::new(color(foreground), color(background))).ok()
+            });
+
+            AttributesColorPairSet::Normal(normal::AttributesColorPair::new(normal::Attributes::new(attributes), color_pair.unwrap_or_default()))
+        },
+        NCursesColorType::Extend => {
+            let color_pair = colors.and_then(|(foreground, background)| {
+                let color = |number: i32| extend::Color::new(extend_color_palette(number));
+
+                extend::alloc_pair(extend::Colors::new(color(foreground), color(background))).ok()
+            });
+
+            AttributesColorPairSet::Extend(extend::AttributesColorPair::new(extend::Attributes::new(attributes), color_pair.unwrap_or_default()))
+        }
+    }
+}
+
+macro_rules! color_palette {
+    ($func: ident, $module: ident, $type: ty) => {
+        // the color palette of a color number.
+        fn $func(number: $type) -> $module::ColorPalette {
+            match number {
+                -1 => $module::ColorPalette::TerminalDefault,
+                0  => $module::ColorPalette::Black,
+                1  => $module::ColorPalette::Red,
+                2  => $module::ColorPalette::Green,
+                3  => $module::ColorPalette::Yellow,
+                4  => $module::ColorPalette::Blue,
+                5  => $module::ColorPalette::Magenta,
+                6  => $module::ColorPalette::Cyan,
+                7  => $module::ColorPalette::White,
+                8  => $module::ColorPalette::LightBlack,
+                9  => $module::ColorPalette::LightRed,
+                10 => $module::ColorPalette::LightGreen,
+                11 => $module::ColorPalette::LightYellow,
+                12 => $module::ColorPalette::LightBlue,
+                13 => $module::ColorPalette::LightMagenta,
+                14 => $module::ColorPalette::LightCyan,
+                15 => $module::ColorPalette::LightWhite,
+                _  => $module::ColorPalette::Custom(number)
+            }
+        }
+    }
+}
+
+color_palette!(normal_color_palette, normal, i16);
+color_palette!(extend_color_palette, extend, i32);
+
+fn parse_field<T: std::str::FromStr>(number: usize, fields: &[&str], index: usize) -> result!(T) {
+    fields
+        .get(index)
+        .and_then(|field| field.parse().ok())
+        .ok_or_else(|| format_error(number, format!("invalid or missing field {}", index + 1)))
+}
+
+fn format_error(line: usize, message: String) -> NCurseswWinError {
+    NCurseswWinError::WindowFormat { line, message }
+}
+
+#[test]
+fn window_text_test() {
+    let blank = TextCell { character: ' ', attributes: 0, colors: None };
+    let bold_red = TextCell { character: '\u{2500}', attributes: A_BOLD | A_UNDERLINE, colors: Some((1, -1)) };
+    let window_text = WindowText {
+        size:       Size { lines: 2, columns: 4 },
+        origin:     Origin { y: 1, x: 2 },
+        cursor:     Origin { y: 1, x: 3 },
+        flags:      vec!("keypad", "scrollok"),
+        background: blank,
+        lines:      vec!(vec!(bold_red, blank, blank, blank), vec!(blank; 4))
+    };
+    let text = window_text.to_text();
+
+    assert_eq!(
+        text,
+        "ncursesw-win window 1\nsize 2 4\norigin 1 2\ncursor 1 3\nflags keypad scrollok\nbackground 20/-/-\n\
+         line 0 2500/underline+bold/1,-1 20/-/-*3\nline 1 20/-/-*4\n"
+    );
+    assert_eq!(WindowText::from_text(&text).unwrap(), window_text);
+    assert_eq!(
+        WindowText::from_text(&text.replace("flags keypad", "flags keypads")),
+        Err(NCurseswWinError::WindowFormat { line: 5, message: "unknown flag 'keypads'".to_string() })
+    );
+    assert_eq!(
+        WindowText::from_text(&text.replace("20/-/-*4", "20/-/-*18446744073709551615")),
+        Err(NCurseswWinError::WindowFormat { line: 8, message: "more than 4 cells".to_string() })
+    );
+    assert_eq!(
+        WindowText::from_text(&text.replace("20/-/-*4", "20/-/-*4 20/-/-")),
+        Err(NCurseswWinError::WindowFormat { line: 8, message: "more than 4 cells".to_string() })
+    );
+    assert_eq!(
+        WindowText::from_text(text.trim_end().rsplit_once('\n').unwrap().0),
+        Err(NCurseswWinError::WindowFormat { line: 8, message: "expected 'line' but reached the end".to_string() })
+    );
+}